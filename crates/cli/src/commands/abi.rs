@@ -0,0 +1,87 @@
+use x_core::invoker::{event_topic, CalldataDecoder, Codec, DynAbiFunction};
+use super::helpers::parse_hex;
+
+fn print_values(values: &[alloy_dyn_abi::DynSolValue], types: &[(String, String)]) {
+    for (i, (value, (name, ty))) in values.iter().zip(types).enumerate() {
+        let name = if name.is_empty() { i.to_string() } else { name.clone() };
+        println!("{} ({}): {}", name, ty, Codec::format_value(value, ty));
+    }
+}
+
+pub async fn handle_abi_encode(signature: String, args: Vec<String>) -> anyhow::Result<()> {
+    let function = DynAbiFunction::from_signature(&signature)?;
+    let inputs = function.get_inputs();
+
+    if args.len() != inputs.len() {
+        anyhow::bail!("Expected {} arguments for {}, got {}", inputs.len(), function.signature(), args.len());
+    }
+
+    let mut values = Vec::new();
+    for (i, (arg, (_, ty))) in args.iter().zip(&inputs).enumerate() {
+        let value = Codec::parse_value(arg, ty)
+            .map_err(|e| anyhow::anyhow!("Failed to parse argument {}: {}", i, e))?;
+        values.push(value);
+    }
+
+    println!("0x{}", hex::encode(function.encode_input(&values)?));
+    Ok(())
+}
+
+pub async fn handle_abi_decode(input: Option<String>, output: Option<String>, data: String) -> anyhow::Result<()> {
+    let data = parse_hex(&data)?;
+
+    match (input, output) {
+        (Some(signature), None) => {
+            let function = DynAbiFunction::from_signature(&signature)?;
+            print_values(&function.decode_input(&data)?, &function.get_inputs());
+        }
+        (None, Some(signature)) => {
+            let function = DynAbiFunction::from_signature(&signature)?;
+            print_values(&function.decode_output(&data)?, &function.get_outputs());
+        }
+        _ => anyhow::bail!("Specify exactly one of --input or --output"),
+    }
+
+    Ok(())
+}
+
+/// 4-byte selector of a function, or the topic of an event when prefixed with `event`
+pub async fn handle_selector(signature: String) -> anyhow::Result<()> {
+    if signature.trim_start().starts_with("event ") {
+        println!("{:#x}", event_topic(&signature)?);
+        return Ok(());
+    }
+
+    let function = DynAbiFunction::from_signature(&signature)?;
+    println!("0x{}  {}", hex::encode(function.selector()), function.signature());
+    Ok(())
+}
+
+pub async fn handle_calldata_decode(artifact: Option<String>, data: String) -> anyhow::Result<()> {
+    let calldata = parse_hex(&data)?;
+
+    let decoder = match &artifact {
+        Some(reference) => {
+            let path = x_core::artifacts::resolve_artifact(x_core::project::artifacts_dir(), reference)?;
+            let mut decoder = CalldataDecoder::new();
+            decoder.add_artifact(x_core::artifacts::contract_name(reference), path)?;
+            decoder
+        }
+        None => CalldataDecoder::from_artifacts(x_core::project::artifacts_dir())?,
+    };
+
+    let call = decoder.decode(&calldata).ok_or_else(|| {
+        anyhow::anyhow!(
+            "No function {}matches selector 0x{}",
+            artifact.as_ref().map(|a| format!("of {} ", a)).unwrap_or_default(),
+            hex::encode(&calldata[..calldata.len().min(4)])
+        )
+    })?;
+
+    println!("{}", call);
+    for (i, (name, ty, value)) in call.args.iter().enumerate() {
+        let name = if name.is_empty() { i.to_string() } else { name.clone() };
+        println!("{} ({}): {}", name, ty, value);
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use colored::Colorize;
+use x_core::config;
+use x_core::gas::GasStrategy;
+use x_core::network::HttpClient;
+use x_transfer::approvals::{self, ApprovalKind, LiveApproval};
+use x_transfer::erc20;
+use super::helpers::parse_address;
+
+/// Blocks scanned back from the latest one when no `--from-block` is given
+const DEFAULT_LOOKBACK: u64 = 100_000;
+
+async fn describe(client: &HttpClient, approval: &LiveApproval) -> String {
+    match approval.kind {
+        ApprovalKind::Allowance(amount) => match erc20::token_info(client, approval.token).await {
+            Ok(info) => format!("{:#x} allowance {}", approval.token, info.format_amount(amount)),
+            Err(_) => format!("{:#x} allowance {}", approval.token, amount),
+        },
+        ApprovalKind::Token(id) => format!("{:#x} token {}", approval.token, id),
+        ApprovalKind::All => format!("{:#x} all tokens", approval.token),
+    }
+}
+
+pub async fn handle_approvals(
+    network: String,
+    address: Option<String>,
+    from_block: Option<u64>,
+    to_block: Option<u64>,
+    spender: Option<String>,
+    revoke: bool,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let owner = match &address {
+        Some(address) => parse_address(address)?,
+        None => x_signature::get_address_from_private_key(&config::load_private_key()?)?,
+    };
+    let spender = spender.as_deref().map(parse_address).transpose()?;
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::new(rpc_url).await?;
+
+    let to_block = match to_block {
+        Some(block) => block,
+        None => client.get_block_number().await?.as_u64(),
+    };
+    let from_block = from_block.unwrap_or_else(|| to_block.saturating_sub(DEFAULT_LOOKBACK));
+
+    println!("Scanning approvals of {:#x} on {} from block {} to {}...", owner, network_obj.name, from_block, to_block);
+
+    let live: Vec<LiveApproval> = approvals::scan_approvals(&client, owner, from_block, to_block)
+        .await?
+        .into_iter()
+        .filter(|a| spender.is_none_or(|s| a.spender == s))
+        .collect();
+
+    if live.is_empty() {
+        println!("{}", "No live approvals found".green());
+        return Ok(());
+    }
+
+    let mut current = None;
+    for approval in &live {
+        if current != Some(approval.spender) {
+            println!("\nSpender {:#x}", approval.spender);
+            current = Some(approval.spender);
+        }
+        println!("  {}", describe(&client, approval).await);
+    }
+    println!("\n{} live approval(s)", live.len());
+
+    if !revoke {
+        println!("Run again with --revoke to revoke them");
+        return Ok(());
+    }
+
+    let private_key = config::load_private_key()?;
+    if x_signature::get_address_from_private_key(&private_key)? != owner {
+        anyhow::bail!("Only the owner can revoke, the configured key is not {:#x}", owner);
+    }
+
+    println!("\nRevoking {} approval(s)...", live.len());
+    let receipts = approvals::revoke_approvals(&private_key, network_obj, &live, gas_strategy.parse::<GasStrategy>()?).await?;
+
+    let mut failed = 0;
+    for (approval, receipt) in live.iter().zip(&receipts) {
+        let status = if receipt.status == Some(1u64.into()) {
+            "✓".green()
+        } else {
+            failed += 1;
+            "✗".red()
+        };
+        println!("{} {} -> {:#x} ({:#x})", status, describe(&client, approval).await, approval.spender, receipt.transaction_hash);
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} revocations reverted", failed, receipts.len());
+    }
+    println!("{}", "All approvals revoked".green().bold());
+    Ok(())
+}
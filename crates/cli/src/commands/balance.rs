@@ -0,0 +1,168 @@
+use colored::Colorize;
+use ethers::types::Address;
+use ethers::utils::format_units;
+use std::path::Path;
+use x_core::account::{fetch_accounts, AccountQuery};
+use x_core::config;
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use x_transfer::erc20;
+
+use super::erc20::resolve_token;
+
+/// Addresses of every wallet in a `gen-wallet` file
+pub fn load_wallet_addresses(file: &str) -> anyhow::Result<Vec<Address>> {
+    // `gen-wallet` writes under wallet/, so a bare file name is looked up there too
+    let path = if Path::new(file).exists() { file.to_string() } else { format!("wallet/{}", file) };
+    if !Path::new(&path).exists() {
+        anyhow::bail!("Wallets file not found: {}", file);
+    }
+
+    x_wallet::WalletGenerator::load_existing_wallets(&path)?
+        .iter()
+        .map(|w| w.address.parse().map_err(|_| anyhow::anyhow!("Invalid address in {}: {}", path, w.address)))
+        .collect()
+}
+
+/// Addresses to inspect: the given one, every wallet of a wallets file, or the configured account
+fn resolve_addresses(address: Option<String>, wallets: Option<String>) -> anyhow::Result<Vec<Address>> {
+    if let Some(address) = address {
+        let address = address.parse().map_err(|_| anyhow::anyhow!("Invalid address: {}", address))?;
+        return Ok(vec![address]);
+    }
+
+    if let Some(file) = wallets {
+        return load_wallet_addresses(&file);
+    }
+
+    let private_key = config::load_private_key()?;
+    Ok(vec![x_signature::get_address_from_private_key(&private_key)?])
+}
+
+fn print_row(query: &AccountQuery) {
+    let address = format!("{:#x}", query.address);
+
+    match &query.state {
+        Ok(state) => {
+            let balance = format_units(state.balance, query.network.currency.decimals)
+                .unwrap_or_else(|_| state.balance.to_string());
+            let pending = state.pending_count();
+            let nonce = if pending.is_zero() {
+                state.nonce.to_string()
+            } else {
+                format!("{} (+{} pending)", state.nonce, pending)
+            };
+
+            println!(
+                "{:<20} {:<42} {:>28} {:<8} {:<18} {}",
+                query.network.id,
+                address,
+                balance,
+                query.network.currency.symbol,
+                nonce,
+                if state.is_contract { "contract" } else { "eoa" }
+            );
+        }
+        Err(e) => println!("{:<20} {:<42} {}", query.network.id, address, format!("error: {}", e).red()),
+    }
+}
+
+/// Token balances of every address on one network, fetched in a single multicall
+async fn print_token_balances(network: &Network, token: &str, addresses: &[Address]) -> anyhow::Result<usize> {
+    let token = resolve_token(&network.id, token)?;
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::new(rpc_url).await?;
+
+    let info = erc20::token_info(&client, token).await?;
+    let balances = erc20::balances_of(&client, network.chain_id, token, addresses).await?;
+
+    let mut failed = 0;
+    for (address, balance) in addresses.iter().zip(balances) {
+        let address = format!("{:#x}", address);
+        match balance {
+            Some(balance) => println!("{:<20} {:<42} {:>28} {}", network.id, address, info.format_amount(balance), info.symbol),
+            None => {
+                failed += 1;
+                println!("{:<20} {:<42} {}", network.id, address, "error: balanceOf reverted".red());
+            }
+        }
+    }
+    Ok(failed)
+}
+
+pub async fn handle_balance(
+    address: Option<String>,
+    wallets: Option<String>,
+    network: Option<String>,
+    all_networks: bool,
+    token: Option<String>,
+) -> anyhow::Result<()> {
+    let addresses = resolve_addresses(address, wallets)?;
+    if addresses.is_empty() {
+        anyhow::bail!("No addresses to query");
+    }
+
+    let networks = x_core::networks::load_networks()?;
+    // Every network unless one is picked; clap rejects `--network` together with `--all-networks`.
+    // With `--rpc-url` only the endpoint's own chain is queried, and a tx.toml profile narrows
+    // the query to its network
+    let network = network
+        .or_else(|| {
+            x_core::networks::rpc_override()
+                .filter(|_| !all_networks)
+                .map(|_| x_core::networks::CUSTOM_NETWORK_ID.to_string())
+        })
+        .or_else(|| x_core::project::default_network().filter(|_| !all_networks).map(String::from));
+    let networks = match network.filter(|_| !all_networks) {
+        Some(id) => {
+            x_core::networks::check_rpc_override(&id)?;
+            vec![x_core::networks::get_network_by_id(&networks, &id)
+                .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", id))?
+                .clone()]
+        }
+        None => networks,
+    };
+
+    println!(
+        "Querying {} address(es) on {} network(s)...\n",
+        addresses.len(),
+        networks.len()
+    );
+
+    if let Some(token) = token {
+        let header = format!("{:<20} {:<42} {:>28} {}", "NETWORK", "ADDRESS", "BALANCE", "TOKEN");
+        println!("{}", header.bold());
+
+        let mut failed = 0;
+        for network in &networks {
+            match print_token_balances(network, &token, &addresses).await {
+                Ok(count) => failed += count,
+                Err(e) => {
+                    failed += addresses.len();
+                    println!("{:<20} {:<42} {}", network.id, "", format!("error: {}", e).red());
+                }
+            }
+        }
+
+        if failed > 0 {
+            println!("\n{}", format!("{} of {} queries failed", failed, addresses.len() * networks.len()).yellow());
+        }
+        return Ok(());
+    }
+
+    let results = fetch_accounts(&networks, &addresses).await;
+
+    let header = format!("{:<20} {:<42} {:>28} {:<8} {:<18} {}", "NETWORK", "ADDRESS", "BALANCE", "", "NONCE", "CODE");
+    println!("{}", header.bold());
+    for query in &results {
+        print_row(query);
+    }
+
+    let failed = results.iter().filter(|q| q.state.is_err()).count();
+    if failed > 0 {
+        println!("\n{}", format!("{} of {} queries failed", failed, results.len()).yellow());
+    }
+
+    Ok(())
+}
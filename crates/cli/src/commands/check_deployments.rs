@@ -0,0 +1,49 @@
+use x_core::project;
+use x_deploy::{DriftChecker, DriftStatus};
+
+pub async fn handle_check_deployments(network: String) -> anyhow::Result<()> {
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    println!("Checking deployments on {} against artifacts...\n", network_obj.name);
+
+    let checker = DriftChecker::new(rpc_url).await?;
+    let reports = checker.check_network(&network, project::artifacts_dir()).await?;
+
+    if reports.is_empty() {
+        println!("No deployments registered for {}", network);
+        return Ok(());
+    }
+
+    let mut drifted = 0;
+    for report in &reports {
+        let marker = match report.status {
+            DriftStatus::Match | DriftStatus::MetadataMismatch => "✓",
+            DriftStatus::Mismatch | DriftStatus::Missing => {
+                drifted += 1;
+                "✗"
+            }
+            DriftStatus::NoArtifact(_) => "?",
+        };
+
+        println!("{} {}@{} {} ({}): {}", marker, report.name, report.version, report.address, report.artifact, report.status);
+        if let Some(implementation) = &report.implementation {
+            println!("    via proxy implementation {}", implementation);
+        }
+        if report.artifact_changed {
+            println!("    artifact was recompiled with different bytecode since this deployment");
+        }
+    }
+
+    println!("\n{} checked, {} drifted", reports.len(), drifted);
+
+    if drifted > 0 {
+        anyhow::bail!("{} deployment(s) do not match their artifacts", drifted);
+    }
+
+    Ok(())
+}
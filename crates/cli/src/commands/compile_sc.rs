@@ -1,10 +1,93 @@
-use x_core::compiler::SmartContractCompiler;
+use colored::Colorize;
+use std::time::Duration;
+use x_core::compiler::{CompileOutput, CompilerBackend, SmartContractCompiler, SolcCompiler, SourceWatcher};
 
-pub async fn handle_compile_sc(contract: Option<String>) -> anyhow::Result<()> {
-    if let Some(contract_name) = contract {
-        SmartContractCompiler::compile_contract(&contract_name)?;
+use crate::ui::handlers::print_diagnostics;
+
+pub async fn handle_compile_sc(contract: Option<String>, backend: String, watch: bool) -> anyhow::Result<()> {
+    let backend = CompilerBackend::parse(&backend)?;
+
+    if !watch {
+        let output = compile(backend, contract.as_deref())?;
+        return report(&output);
+    }
+
+    let contracts_dir = x_core::project::contracts_dir();
+    let mut watcher = SourceWatcher::new(contracts_dir)?;
+    println!("Watching {}/ for changes (Ctrl+C to stop)...\n", contracts_dir);
+    compile_and_refresh(backend, contract.as_deref());
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let changed = watcher.changed()?;
+        if changed.is_empty() {
+            continue;
+        }
+
+        for path in &changed {
+            println!("{} {}", "changed:".cyan(), path.display());
+        }
+        compile_and_refresh(backend, contract.as_deref());
+    }
+}
+
+fn compile(backend: CompilerBackend, contract: Option<&str>) -> anyhow::Result<CompileOutput> {
+    match backend {
+        CompilerBackend::Forge => {
+            println!("Compiling {}...", contract.unwrap_or("all smart contracts"));
+            match contract {
+                Some(contract_name) => SmartContractCompiler::compile_contract(contract_name),
+                None => SmartContractCompiler::compile_all(),
+            }
+        }
+        CompilerBackend::Solc => {
+            let compiler = SolcCompiler::new(".")?;
+            println!("Compiling with solc {}...", compiler.version()?);
+            match contract {
+                Some(contract_name) => compiler.compile_contract(contract_name),
+                None => compiler.compile_all(),
+            }
+        }
+    }
+}
+
+fn report(output: &CompileOutput) -> anyhow::Result<()> {
+    print_diagnostics(&output.diagnostics);
+
+    if output.has_errors() {
+        anyhow::bail!(
+            "Compilation failed with {} error(s), {} warning(s)",
+            output.errors(),
+            output.warnings()
+        );
+    }
+
+    if output.is_up_to_date() {
+        println!("✓ Nothing to compile, all sources are up to date");
+    } else if output.warnings() > 0 {
+        println!("✓ Compiled with {} warning(s)", output.warnings());
     } else {
-        SmartContractCompiler::compile_all()?;
+        println!("✓ Compiled successfully");
     }
     Ok(())
 }
+
+/// One watch iteration: compile, report, and show the contracts the Gate can now deploy
+fn compile_and_refresh(backend: CompilerBackend, contract: Option<&str>) {
+    let result = compile(backend, contract).and_then(|output| report(&output));
+
+    match result {
+        Ok(()) => match x_gate::Gate::get_available_contracts() {
+            Ok(contracts) => {
+                println!("Gate contracts:");
+                for contract in contracts {
+                    println!("  - {}", contract);
+                }
+            }
+            Err(e) => println!("{}", format!("Gate contracts unavailable: {}", e).yellow()),
+        },
+        Err(e) => println!("{}", format!("❌ {}", e).red().bold()),
+    }
+    println!();
+}
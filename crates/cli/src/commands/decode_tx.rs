@@ -0,0 +1,86 @@
+use ethers::types::U256;
+use ethers::utils::format_units;
+use x_core::invoker::CalldataDecoder;
+use x_core::transaction::decode_raw;
+
+fn gwei(value: U256) -> String {
+    format_units(value, "gwei").unwrap_or_else(|_| value.to_string())
+}
+
+pub async fn handle_decode_tx(raw: String) -> anyhow::Result<()> {
+    let bytes = hex::decode(raw.trim().trim_start_matches("0x"))
+        .map_err(|e| anyhow::anyhow!("Invalid hex: {}", e))?;
+    let tx = decode_raw(&bytes)?;
+
+    let network = tx.chain_id.and_then(|chain_id| {
+        x_core::networks::load_networks()
+            .ok()?
+            .into_iter()
+            .find(|n| n.chain_id == chain_id)
+    });
+
+    println!("Type: {}", tx.type_name());
+    println!("Hash: {:#x}", tx.hash);
+    match (tx.chain_id, &network) {
+        (Some(chain_id), Some(network)) => println!("Chain ID: {} ({})", chain_id, network.name),
+        (Some(chain_id), None) => println!("Chain ID: {}", chain_id),
+        (None, _) => println!("Chain ID: none (pre EIP-155, replayable on any chain)"),
+    }
+    println!("From: {:#x}", tx.from);
+    match tx.to {
+        Some(to) => println!("To: {:#x}", to),
+        None => println!("To: (contract creation)"),
+    }
+    println!("Nonce: {}", tx.nonce);
+
+    let symbol = network.as_ref().map_or("ETH", |n| n.currency.symbol.as_str());
+    println!("Value: {} {}", format_units(tx.value, "ether")?, symbol);
+    println!("Gas Limit: {}", tx.gas_limit);
+    if let Some(gas_price) = tx.gas_price {
+        println!("Gas Price: {} gwei", gwei(gas_price));
+    }
+    if let (Some(max_fee), Some(priority_fee)) = (tx.max_fee_per_gas, tx.max_priority_fee_per_gas) {
+        println!("Max Fee: {} gwei", gwei(max_fee));
+        println!("Max Priority Fee: {} gwei", gwei(priority_fee));
+    }
+    if let Some(blob_fee) = tx.max_fee_per_blob_gas {
+        println!("Max Fee Per Blob Gas: {} gwei", gwei(blob_fee));
+        for hash in &tx.blob_versioned_hashes {
+            println!("Blob: {:#x}", hash);
+        }
+    }
+    if tx.access_list_len > 0 {
+        println!("Access List: {} address(es)", tx.access_list_len);
+    }
+
+    match tx.max_cost() {
+        Some(max_cost) => println!("Max Cost: {} {}", format_units(max_cost, "ether")?, symbol),
+        None => println!("Max Cost: overflow"),
+    }
+
+    if tx.data.is_empty() {
+        return Ok(());
+    }
+
+    println!("\nData: {} bytes", tx.data.len());
+    if tx.to.is_none() {
+        return Ok(());
+    }
+
+    let decoder = CalldataDecoder::from_artifacts(x_core::project::artifacts_dir())?;
+    match decoder.decode(&tx.data) {
+        Some(call) => {
+            println!("Call: {}", call);
+            for (name, ty, value) in &call.args {
+                let name = if name.is_empty() { "_" } else { name.as_str() };
+                println!("  {} ({}): {}", name, ty, value);
+            }
+        }
+        None => println!(
+            "Call: unknown selector 0x{}",
+            hex::encode(&tx.data[..tx.data.len().min(4)])
+        ),
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use x_core::config;
 use x_core::gas::GasStrategy;
+use x_core::project;
 use x_signature;
 use x_deploy;
 
@@ -13,18 +14,13 @@ pub async fn handle_deploy(
     let network_obj = x_core::networks::get_network_by_id(&networks, &network)
         .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
 
-    let strategy = match gas_strategy.as_str() {
-        "low" => GasStrategy::Low,
-        "standard" => GasStrategy::Standard,
-        "fast" => GasStrategy::Fast,
-        "instant" => GasStrategy::Instant,
-        _ => return Err(anyhow::anyhow!("Invalid gas strategy: {}", gas_strategy)),
-    };
+    let strategy: GasStrategy = gas_strategy.parse()?;
 
-    let artifact_path = format!("artifacts/{}.sol/{}.json", contract, contract);
+    let artifact_path = x_core::artifacts::resolve_artifact(project::artifacts_dir(), &contract)?;
     
-    println!("Loading contract artifact from {}...", artifact_path);
+    println!("Loading contract artifact from {}...", artifact_path.display());
     let artifact = x_deploy::ArtifactLoader::load_artifact(&artifact_path)?;
+    let contract_name = x_core::artifacts::contract_name(&contract);
 
     println!("Deploying {} to {} with {:?} strategy...", contract, network_obj.name, strategy);
 
@@ -34,21 +30,35 @@ pub async fn handle_deploy(
     let deployer = x_deploy::ContractDeployer::new(rpc_url, &private_key, network_obj.clone())
         .await?;
 
-    let result = deployer.deploy(&artifact, None, strategy).await?;
+    let linked = x_deploy::LibraryLinker::new(&deployer, project::artifacts_dir())
+        .link(&artifact, strategy)
+        .await?;
+    for library in &linked.deployed {
+        println!("Deployed library {}", library);
+    }
+
+    let result = deployer.deploy(&linked.artifact, None, strategy).await?;
 
     let deployer_address = x_signature::get_address_from_private_key(&private_key)?;
     
-    x_deploy::MetadataManager::save_deployment(
+    let version = x_deploy::MetadataManager::record_linked_deployment(
+        &network,
+        contract_name,
         &contract,
+        &linked.artifact,
         &format!("{:#x}", result.contract_address),
-        &network,
         &format!("{:#x}", result.tx_hash),
         &format!("{:#x}", deployer_address),
+        &linked.libraries,
     )?;
 
     println!("\n✓ Deployment successful!");
+    println!("Registered as {}@{}", contract_name, version);
     println!("Contract Address: {:#x}", result.contract_address);
     println!("Transaction Hash: {:#x}", result.tx_hash);
+    for (library, address) in &linked.libraries {
+        println!("Linked Library: {} at {}", library, address);
+    }
     println!("Gas Used: {} ({} gwei)", 
         result.gas_used,
         result.gas_estimate.max_fee_per_gas.unwrap_or(result.gas_estimate.gas_price) / 1_000_000_000u64
@@ -0,0 +1,54 @@
+use x_core::config;
+use x_core::gas::GasStrategy;
+use x_core::project;
+use x_deploy::{DeploymentPlan, PlanExecutor};
+
+pub async fn handle_deploy_plan(
+    plan: String,
+    network: String,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let strategy: GasStrategy = gas_strategy.parse()?;
+
+    println!("Loading deployment plan from {}...", plan);
+    let deployment_plan = DeploymentPlan::load(&plan)?;
+
+    let order = deployment_plan.execution_order()?;
+    println!("Executing {} step(s) on {} with {:?} strategy:", order.len(), network_obj.name, strategy);
+    for (i, step) in order.iter().enumerate() {
+        println!("  {}. {} ({})", i + 1, step.name, step.artifact_name());
+    }
+    println!();
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    let executor = PlanExecutor::new(rpc_url, &private_key, network_obj.clone()).await?;
+
+    let results = executor.execute(&deployment_plan, project::artifacts_dir(), strategy, |result| {
+        if result.skipped {
+            println!("↷ {} already deployed at {}", result.name, result.address);
+        } else {
+            println!("✓ {} deployed at {}", result.name, result.address);
+            println!("  Transaction Hash: {}", result.tx_hash);
+        }
+        for (function, tx_hash) in &result.calls {
+            println!("  Called {}: {}", function, tx_hash);
+        }
+    }).await?;
+
+    let deployed = results.iter().filter(|r| !r.skipped).count();
+
+    println!("\n✓ Deployment plan completed!");
+    println!("Deployed: {}, Skipped: {}", deployed, results.len() - deployed);
+    for result in &results {
+        println!("{}: {}/address/{}", result.name, network_obj.block_explorer.url, result.address);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use clap::Subcommand;
+use x_core::project;
+use x_core::registry::DeploymentRegistry;
+
+use super::resolve_network;
+
+#[derive(Subcommand, Clone)]
+pub enum DeploymentsAction {
+    /// List registered contracts and their latest version
+    List {
+        #[arg(short, long)]
+        network: Option<String>,
+    },
+
+    /// Show every version of a contract, e.g. `HelloWorld` or `HelloWorld@stable`
+    Show {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        contract: String,
+    },
+
+    /// Point a tag at a version, e.g. `deployments tag HelloWorld@2 stable`
+    Tag {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        contract: String,
+
+        tag: String,
+    },
+
+    /// Remove a version from the registry, e.g. `HelloWorld@1` or `HelloWorld@stable`
+    Remove {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        contract: String,
+    },
+}
+
+pub async fn handle_deployments(action: DeploymentsAction) -> anyhow::Result<()> {
+    match action {
+        DeploymentsAction::List { network } => handle_list(&resolve_network(&network)?),
+        DeploymentsAction::Show { network, contract } => handle_show(&resolve_network(&network)?, &contract),
+        DeploymentsAction::Tag { network, contract, tag } => handle_tag(&resolve_network(&network)?, &contract, &tag),
+        DeploymentsAction::Remove { network, contract } => handle_remove(&resolve_network(&network)?, &contract),
+    }
+}
+
+fn handle_list(network: &str) -> anyhow::Result<()> {
+    let registry = DeploymentRegistry::load(project::deployments_dir(), network)?;
+
+    if registry.entries().is_empty() {
+        println!("No deployments registered in {}", registry.path().display());
+        return Ok(());
+    }
+
+    for (name, history) in &registry.contracts {
+        if let Some(latest) = history.latest() {
+            let tags = history.tags_for(latest.version);
+            let tags = if tags.is_empty() { String::new() } else { format!(" [{}]", tags.join(", ")) };
+            println!(
+                "{}@{} {} ({} version{}){}",
+                name,
+                latest.version,
+                latest.address,
+                history.deployments.len(),
+                if history.deployments.len() == 1 { "" } else { "s" },
+                tags
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_show(network: &str, contract: &str) -> anyhow::Result<()> {
+    let registry = DeploymentRegistry::load(project::deployments_dir(), network)?;
+    let (name, selected) = registry.resolve(contract)?;
+    let history = registry
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("Contract '{}' is not registered", name))?;
+
+    println!("Contract: {}", name);
+    println!("Network: {}", network);
+
+    for entry in &history.deployments {
+        let marker = if entry.version == selected.version { "*" } else { " " };
+        let tags = history.tags_for(entry.version);
+
+        println!("\n{} Version {}{}", marker, entry.version, if tags.is_empty() { String::new() } else { format!(" [{}]", tags.join(", ")) });
+        println!("  Artifact: {}", entry.artifact);
+        println!("  Address: {}", entry.address);
+        println!("  Transaction Hash: {}", entry.tx_hash);
+        println!("  Deployer: {}", entry.deployer);
+        println!("  Timestamp: {}", entry.timestamp);
+        if let Some(hash) = &entry.bytecode_hash {
+            println!("  Bytecode Hash: {}", hash);
+        }
+        if let Some(hash) = &entry.abi_hash {
+            println!("  ABI Hash: {}", hash);
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_tag(network: &str, contract: &str, tag: &str) -> anyhow::Result<()> {
+    let mut registry = DeploymentRegistry::load(project::deployments_dir(), network)?;
+    let version = registry.tag(contract, tag)?;
+    registry.save()?;
+
+    let name = contract.split('@').next().unwrap_or(contract);
+    println!("✓ Tagged {}@{} as {}", name, version, tag);
+    Ok(())
+}
+
+fn handle_remove(network: &str, contract: &str) -> anyhow::Result<()> {
+    // Removing whatever happens to be latest is too easy to get wrong, the version has to be named
+    match contract.split_once('@') {
+        Some((_, selector)) if !selector.trim().is_empty() && selector.trim() != "latest" => {}
+        _ => {
+            let name = contract.split('@').next().unwrap_or(contract).trim();
+            anyhow::bail!("Name the version to remove, e.g. {}@3 or {}@<tag>", name, name);
+        }
+    }
+
+    let mut registry = DeploymentRegistry::load(project::deployments_dir(), network)?;
+    let (name, entry) = registry.remove(contract)?;
+    registry.save()?;
+
+    println!("✓ Removed {}@{} ({}) from {}", name, entry.version, entry.address, registry.path().display());
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use colored::Colorize;
+use ethers::types::Address;
+use x_core::config;
+use x_core::gas::GasStrategy;
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use x_core::project;
+use x_core::registry::DeploymentRegistry;
+use x_transfer::erc20::{self, TokenEvent, TokenInfo, TokenTxResult};
+use super::helpers::{load_network, parse_address};
+
+/// A token address, or the name of a contract in the deployment registry
+pub fn resolve_token(network: &str, token: &str) -> anyhow::Result<Address> {
+    if token.starts_with("0x") {
+        return parse_address(token);
+    }
+
+    let registry = DeploymentRegistry::load(project::deployments_dir(), network)?;
+    let (_, entry) = registry.resolve(token)?;
+    parse_address(&entry.address)
+}
+
+fn print_token_events(info: &TokenInfo, events: &[TokenEvent]) {
+    for event in events {
+        match event {
+            TokenEvent::Transfer { from, to, value } => {
+                println!("  Transfer {:#x} -> {:#x}: {}", from, to, info.format_amount(*value))
+            }
+            TokenEvent::Approval { owner, spender, value } => {
+                println!("  Approval {:#x} -> {:#x}: {}", owner, spender, info.format_amount(*value))
+            }
+        }
+    }
+}
+
+fn print_result(network: &Network, info: &TokenInfo, result: &TokenTxResult) {
+    println!("Transaction successful!");
+    println!("TX Hash: {}", result.tx_hash);
+    if !result.events.is_empty() {
+        println!("Events:");
+        print_token_events(info, &result.events);
+    }
+    println!("View on Explorer: {}/tx/{}", network.block_explorer.url, result.tx_hash);
+}
+
+pub async fn handle_transfer_token(
+    network: String,
+    token: String,
+    to: String,
+    amount: String,
+    from: Option<String>,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let network_obj = load_network(&network)?;
+    let token = resolve_token(&network, &token)?;
+    let to = parse_address(&to)?;
+    let from = from.as_deref().map(parse_address).transpose()?;
+
+    println!("Sending {} of token {:#x} to {:#x}...", amount, token, to);
+
+    let (info, result) = erc20::transfer_token_with_strategy_async(
+        &private_key,
+        &network_obj,
+        token,
+        to,
+        &amount,
+        from,
+        gas_strategy.parse::<GasStrategy>()?,
+    )
+    .await?;
+
+    print_result(&network_obj, &info, &result);
+    Ok(())
+}
+
+pub async fn handle_approve(
+    network: String,
+    token: String,
+    spender: String,
+    amount: String,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let network_obj = load_network(&network)?;
+    let token = resolve_token(&network, &token)?;
+    let spender = parse_address(&spender)?;
+
+    println!("Approving {:#x} to spend {} of token {:#x}...", spender, amount, token);
+
+    let (info, result) = erc20::approve_token_with_strategy_async(
+        &private_key,
+        &network_obj,
+        token,
+        spender,
+        &amount,
+        gas_strategy.parse::<GasStrategy>()?,
+    )
+    .await?;
+
+    print_result(&network_obj, &info, &result);
+    Ok(())
+}
+
+pub async fn handle_allowance(
+    network: String,
+    token: String,
+    owner: Option<String>,
+    spender: String,
+) -> anyhow::Result<()> {
+    let network_obj = load_network(&network)?;
+    let token = resolve_token(&network, &token)?;
+    let spender = parse_address(&spender)?;
+    let owner = match owner {
+        Some(owner) => parse_address(&owner)?,
+        None => x_signature::get_address_from_private_key(&config::load_private_key()?)?,
+    };
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::new(rpc_url).await?;
+
+    let info = erc20::token_info(&client, token).await?;
+    let allowance = erc20::allowance(&client, token, owner, spender).await?;
+    let balance = erc20::balance_of(&client, token, owner).await?;
+
+    println!("Token: {:#x} ({}, {} decimals)", token, info.symbol, info.decimals);
+    println!("Owner: {:#x}", owner);
+    println!("Spender: {:#x}", spender);
+    println!("Allowance: {}", info.format_amount(allowance).bold());
+    println!("Owner Balance: {}", info.format_amount(balance));
+    Ok(())
+}
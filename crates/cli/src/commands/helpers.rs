@@ -0,0 +1,27 @@
+use ethers::types::Address;
+use x_core::networks::Network;
+
+pub fn parse_address(address: &str) -> anyhow::Result<Address> {
+    address.trim().parse().map_err(|_| anyhow::anyhow!("Invalid address: {}", address))
+}
+
+/// Hex data with or without a `0x` prefix
+pub fn parse_hex(data: &str) -> anyhow::Result<Vec<u8>> {
+    hex::decode(data.trim().trim_start_matches("0x")).map_err(|e| anyhow::anyhow!("Invalid hex data: {}", e))
+}
+
+/// Comma separated `--args`, empty when none are given
+pub fn split_args(args: &str) -> Vec<String> {
+    if args.is_empty() {
+        Vec::new()
+    } else {
+        args.split(',').map(|s| s.trim().to_string()).collect()
+    }
+}
+
+pub fn load_network(network: &str) -> anyhow::Result<Network> {
+    let networks = x_core::networks::load_networks()?;
+    x_core::networks::get_network_by_id(&networks, network)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))
+}
@@ -0,0 +1,91 @@
+use colored::Colorize;
+use ethers::types::Address;
+use ethers::utils::format_units;
+use x_core::config;
+use x_core::gas::GasStrategy;
+use x_core::invoker::{BatchFile, ContractInvoker, EncodedCall};
+use x_core::project;
+use x_transfer::batch;
+
+fn print_outputs(call: &EncodedCall, return_data: &[u8]) {
+    match call.decode_output(return_data) {
+        Ok(outputs) => {
+            for (name, value) in outputs {
+                println!("    {}: {}", name, value.green());
+            }
+        }
+        Err(_) if return_data.is_empty() => {}
+        Err(_) => println!("    0x{}", hex::encode(return_data)),
+    }
+}
+
+pub async fn handle_invoke_batch(
+    file: String,
+    network: String,
+    sequential: bool,
+    batcher: Option<String>,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+    let gas_strategy = gas_strategy.parse::<GasStrategy>()?;
+    let batcher = batcher
+        .map(|b| b.parse::<Address>().map_err(|_| anyhow::anyhow!("Invalid batcher address: {}", b)))
+        .transpose()?;
+
+    let deployments_file = project::deployments_file(&network);
+    let invoker = ContractInvoker::new(&deployments_file, project::artifacts_dir());
+    let calls = BatchFile::load(&file)?.encode(&invoker, &network)?;
+
+    println!("\n📦 Batch of {} call(s) on {}", calls.len(), network_obj.name);
+    for call in &calls {
+        if call.value.is_zero() {
+            println!("  {} -> {:#x}", call.label, call.target);
+        } else {
+            let value = format_units(call.value, network_obj.currency.decimals).unwrap_or_else(|_| call.value.to_string());
+            println!("  {} -> {:#x} ({} {})", call.label, call.target, value, network_obj.currency.symbol);
+        }
+    }
+    println!();
+
+    if sequential {
+        println!("Sending {} transaction(s)...", calls.len());
+        let results = batch::send_batch_sequential(&private_key, network_obj, &calls, gas_strategy).await?;
+
+        let mut failed = 0;
+        for (call, result) in calls.iter().zip(&results) {
+            if result.receipt.status == Some(1u64.into()) {
+                println!("{} {} ({:#x})", "✓".green(), call.label, result.receipt.transaction_hash);
+                if let Some(data) = &result.return_data {
+                    print_outputs(call, data);
+                }
+            } else {
+                failed += 1;
+                println!("{} {} reverted ({:#x})", "✗".red(), call.label, result.receipt.transaction_hash);
+            }
+        }
+
+        if failed > 0 {
+            anyhow::bail!("{} of {} calls reverted", failed, results.len());
+        }
+        return Ok(());
+    }
+
+    println!("Sending atomic batch...");
+    let (results, receipt) = batch::send_batch_atomic(&private_key, network_obj, batcher, &calls, gas_strategy).await?;
+
+    for (call, result) in calls.iter().zip(&results) {
+        let status = if result.success { "✓".green() } else { "✗".red() };
+        println!("{} {}", status, call.label);
+        if result.success {
+            print_outputs(call, &result.return_data);
+        }
+    }
+
+    println!("\nTransaction successful!");
+    println!("TX Hash: {:#x}", receipt.transaction_hash);
+    println!("View on Explorer: {}/tx/{:#x}", network_obj.block_explorer.url, receipt.transaction_hash);
+    Ok(())
+}
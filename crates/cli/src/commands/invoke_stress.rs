@@ -1,6 +1,7 @@
 use x_core::config;
 use x_core::invoker::ContractInvoker;
 use x_core::invoker::Codec;
+use x_core::project;
 use x_core::stress::{StressExecutor, StressConfig};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -18,8 +19,8 @@ pub async fn handle_invoke_stress(
     let network_obj = x_core::networks::get_network_by_id(&networks, &network)
         .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
 
-    let deployments_file = format!("deployments/{}.json", network);
-    let invoker = ContractInvoker::new(&deployments_file, "artifacts");
+    let deployments_file = project::deployments_file(&network);
+    let invoker = ContractInvoker::new(&deployments_file, project::artifacts_dir());
     let contract_invoker = invoker.get_contract(&contract, &network)
         .or_else(|_| {
             eprintln!("Attempting to use contract address directly...");
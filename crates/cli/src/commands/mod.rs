@@ -2,9 +2,27 @@ pub mod sign;
 pub mod verify;
 pub mod transfer_eth;
 pub mod deploy;
+pub mod deploy_plan;
+pub mod deployments;
+pub mod check_deployments;
+pub mod proxy;
 pub mod compile_sc;
 pub mod gen_wallet;
 pub mod invoke_stress;
+pub mod invoke_batch;
+pub mod offline_tx;
+pub mod decode_tx;
+pub mod tx;
+pub mod abi;
+pub mod signatures;
+pub mod balance;
+pub mod erc20;
+pub mod nft;
+pub mod approvals;
+pub mod permit;
+pub mod network;
+
+mod helpers;
 
 use clap::{Parser, Subcommand};
 
@@ -15,6 +33,37 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Send every request to this RPC, e.g. a local anvil at http://127.0.0.1:8545
+    #[arg(long, global = true)]
+    pub rpc_url: Option<String>,
+
+    /// Chain id served by --rpc-url, queried from it when omitted; the declared chain id for `network add`
+    #[arg(long, global = true)]
+    pub chain_id: Option<u64>,
+
+    /// tx.toml profile supplying the default network, account, gas strategy and paths
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Named account from tx.toml to sign with, instead of the profile's
+    #[arg(long, global = true)]
+    pub account: Option<String>,
+}
+
+/// `--network`, or the network of the active tx.toml profile
+pub fn resolve_network(network: &Option<String>) -> anyhow::Result<String> {
+    let network = network
+        .clone()
+        .or_else(|| x_core::project::default_network().map(String::from))
+        .ok_or_else(|| anyhow::anyhow!("No network given, pass --network or set `network` in {}", x_core::project::PROJECT_CONFIG_FILE))?;
+    x_core::networks::check_rpc_override(&network)?;
+    Ok(network)
+}
+
+/// `--gas-strategy`, or the one of the active tx.toml profile
+pub fn resolve_gas_strategy(gas_strategy: &Option<String>) -> String {
+    gas_strategy.clone().unwrap_or_else(|| x_core::project::default_gas_strategy().to_string())
 }
 
 #[derive(Subcommand)]
@@ -40,7 +89,7 @@ pub enum Commands {
 
     TransferEth {
         #[arg(short, long)]
-        network: String,
+        network: Option<String>,
 
         #[arg(short, long)]
         amount: f64,
@@ -54,19 +103,457 @@ pub enum Commands {
 
     Deploy {
         #[arg(short, long)]
-        network: String,
+        network: Option<String>,
+
+        #[arg(short, long)]
+        contract: String,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    #[command(name = "deploy-plan")]
+    DeployPlan {
+        plan: String,
+
+        #[arg(short, long)]
+        network: Option<String>,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    #[command(name = "deploy-proxy")]
+    DeployProxy {
+        #[arg(short, long)]
+        network: Option<String>,
 
         #[arg(short, long)]
         contract: String,
 
-        #[arg(short, long, default_value = "standard")]
-        gas_strategy: String,
+        #[arg(short, long, default_value = "transparent")]
+        kind: String,
+
+        #[arg(short, long)]
+        init: Option<String>,
+
+        #[arg(short, long, default_value = "")]
+        args: String,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    Upgrade {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        #[arg(short, long)]
+        proxy: String,
+
+        #[arg(short, long)]
+        contract: String,
+
+        #[arg(short, long)]
+        kind: Option<String>,
+
+        #[arg(long)]
+        call: Option<String>,
+
+        #[arg(short, long, default_value = "")]
+        args: String,
+
+        #[arg(long)]
+        skip_storage_check: bool,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    #[command(name = "storage-check")]
+    StorageCheck {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        #[arg(short, long)]
+        proxy: String,
+
+        #[arg(short, long)]
+        contract: String,
+    },
+
+    #[command(name = "proxy-info")]
+    ProxyInfo {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        #[arg(short, long)]
+        proxy: String,
+    },
+
+    Deployments {
+        #[command(subcommand)]
+        action: deployments::DeploymentsAction,
+    },
+
+    #[command(name = "check-deployments")]
+    CheckDeployments {
+        #[arg(short, long)]
+        network: Option<String>,
     },
 
     #[command(name = "compile-sc")]
     CompileSc {
         #[arg(short, long)]
         contract: Option<String>,
+
+        #[arg(short, long, default_value = "forge")]
+        backend: String,
+
+        #[arg(short, long)]
+        watch: bool,
+    },
+
+    #[command(name = "build-tx")]
+    BuildTx {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        #[arg(long)]
+        from: Option<String>,
+
+        #[arg(short, long)]
+        to: Option<String>,
+
+        #[arg(short, long, default_value = "0")]
+        value: String,
+
+        #[arg(short, long)]
+        data: Option<String>,
+
+        #[arg(short, long)]
+        contract: Option<String>,
+
+        #[arg(short, long)]
+        function: Option<String>,
+
+        #[arg(short, long, default_value = "")]
+        args: String,
+
+        #[arg(long)]
+        deploy: Option<String>,
+
+        #[arg(long)]
+        legacy: bool,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+
+    #[command(name = "sign-tx")]
+    SignTx {
+        #[arg(short, long, default_value = "-")]
+        input: String,
+
+        #[arg(short, long)]
+        private_key: Option<String>,
+
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+
+    Broadcast {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        #[arg(short, long)]
+        raw: String,
+    },
+
+    #[command(name = "decode-tx")]
+    DecodeTx {
+        raw: String,
+    },
+
+    Tx {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        hash: String,
+
+        /// Follow the transaction until it has this many confirmations (1 when given without a value)
+        #[arg(short, long, num_args = 0..=1, default_missing_value = "1")]
+        wait: Option<u64>,
+    },
+
+    #[command(name = "abi-encode")]
+    AbiEncode {
+        signature: String,
+
+        #[arg(allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    #[command(name = "abi-decode")]
+    AbiDecode {
+        #[arg(short, long, conflicts_with = "output")]
+        input: Option<String>,
+
+        #[arg(short, long)]
+        output: Option<String>,
+
+        data: String,
+    },
+
+    Selector {
+        signature: String,
+    },
+
+    #[command(name = "calldata-decode")]
+    CalldataDecode {
+        #[arg(short, long)]
+        artifact: Option<String>,
+
+        data: String,
+    },
+
+    Signatures {
+        #[command(subcommand)]
+        action: signatures::SignaturesAction,
+    },
+
+    Network {
+        #[command(subcommand)]
+        action: network::NetworkAction,
+    },
+
+    #[command(name = "transfer-token")]
+    TransferToken {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// Token address or registered contract name
+        #[arg(short = 'k', long)]
+        token: String,
+
+        #[arg(short, long)]
+        to: String,
+
+        /// Amount in token units, e.g. 1.5
+        #[arg(short, long)]
+        amount: String,
+
+        /// Spend the allowance of another owner through transferFrom
+        #[arg(short, long)]
+        from: Option<String>,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    Approve {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// Token address or registered contract name
+        #[arg(short = 'k', long)]
+        token: String,
+
+        #[arg(short, long)]
+        spender: String,
+
+        /// Amount in token units, or `max` for an unlimited allowance
+        #[arg(short, long)]
+        amount: String,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    Allowance {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// Token address or registered contract name
+        #[arg(short = 'k', long)]
+        token: String,
+
+        /// Defaults to the configured account
+        #[arg(short, long)]
+        owner: Option<String>,
+
+        #[arg(short, long)]
+        spender: String,
+    },
+
+    /// Call safeMint(to) for every recipient, e.g. every address of a wallets file
+    #[command(name = "nft-mint")]
+    NftMint {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// NFT address or registered contract name
+        #[arg(short, long)]
+        contract: String,
+
+        #[arg(short, long, value_delimiter = ',')]
+        to: Vec<String>,
+
+        #[arg(short, long)]
+        wallets: Option<String>,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    /// ERC-721 or ERC-1155 safeTransferFrom; several ERC-1155 ids use safeBatchTransferFrom
+    #[command(name = "nft-transfer")]
+    NftTransfer {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// NFT address or registered contract name
+        #[arg(short, long)]
+        contract: String,
+
+        #[arg(short, long)]
+        to: String,
+
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        ids: Vec<String>,
+
+        /// ERC-1155 amounts, one per id
+        #[arg(short, long, value_delimiter = ',')]
+        amounts: Vec<String>,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    /// ERC-721 tokens held by an owner, or ERC-1155 balances of the given ids
+    #[command(name = "nft-owned")]
+    NftOwned {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// NFT address or registered contract name
+        #[arg(short, long)]
+        contract: String,
+
+        /// Defaults to the configured account
+        #[arg(short, long)]
+        owner: Option<String>,
+
+        #[arg(short, long, value_delimiter = ',')]
+        ids: Vec<String>,
+    },
+
+    #[command(name = "nft-uri")]
+    NftUri {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// NFT address or registered contract name
+        #[arg(short, long)]
+        contract: String,
+
+        #[arg(short, long)]
+        id: String,
+    },
+
+    /// List live ERC-20 allowances and NFT approvals granted by an address
+    Approvals {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// Owner to scan, defaults to the configured account
+        #[arg(short, long)]
+        address: Option<String>,
+
+        /// Defaults to 100000 blocks before --to-block
+        #[arg(short, long)]
+        from_block: Option<u64>,
+
+        /// Defaults to the latest block
+        #[arg(short, long)]
+        to_block: Option<u64>,
+
+        /// Only list approvals of this spender
+        #[arg(short, long)]
+        spender: Option<String>,
+
+        /// Revoke every listed approval
+        #[arg(short, long)]
+        revoke: bool,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
+    /// Sign an EIP-2612 permit or a Permit2 PermitSingle / PermitTransferFrom
+    Permit {
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// Token address or registered contract name
+        #[arg(short = 'k', long)]
+        token: String,
+
+        #[arg(short, long)]
+        spender: String,
+
+        /// Amount in token units, or `max`
+        #[arg(short, long)]
+        amount: String,
+
+        /// erc2612, permit2-single or permit2-transfer
+        #[arg(long, default_value = "erc2612")]
+        kind: String,
+
+        /// Seconds the signature stays valid
+        #[arg(short, long, default_value = "3600")]
+        deadline: u64,
+
+        /// Seconds a Permit2 PermitSingle allowance lasts
+        #[arg(short, long, default_value = "2592000")]
+        expiration: u64,
+
+        /// Defaults to the next nonce read from the token or Permit2
+        #[arg(long)]
+        nonce: Option<u64>,
+
+        /// Recipient of a permit2-transfer, defaults to the spender
+        #[arg(short, long)]
+        to: Option<String>,
+
+        #[arg(short, long)]
+        private_key: Option<String>,
+
+        /// Also print the eth_signTypedData_v4 JSON
+        #[arg(long)]
+        typed_data: bool,
+    },
+
+    Balance {
+        /// Address to inspect, defaults to the configured account
+        #[arg(conflicts_with = "wallets")]
+        address: Option<String>,
+
+        #[arg(short, long)]
+        wallets: Option<String>,
+
+        #[arg(short, long, conflicts_with = "all_networks")]
+        network: Option<String>,
+
+        #[arg(short, long)]
+        all_networks: bool,
+
+        /// ERC-20 address or registry name, shows token balances instead
+        #[arg(short, long)]
+        token: Option<String>,
     },
 
     #[command(name = "gen-wallet")]
@@ -78,13 +565,35 @@ pub enum Commands {
         filename: String,
     },
 
+    /// Send the calls listed in a TOML file as one atomic transaction
+    #[command(name = "invoke-batch")]
+    InvokeBatch {
+        /// TOML file with [[call]] entries
+        #[arg(short, long)]
+        file: String,
+
+        #[arg(short, long)]
+        network: Option<String>,
+
+        /// One transaction per call with pipelined nonces, instead of a single aggregate3Value
+        #[arg(short, long)]
+        sequential: bool,
+
+        /// aggregate3Value-compatible batcher, defaults to Multicall3
+        #[arg(short, long, conflicts_with = "sequential")]
+        batcher: Option<String>,
+
+        #[arg(short, long)]
+        gas_strategy: Option<String>,
+    },
+
     #[command(name = "invoke-stress")]
     InvokeStress {
         #[arg(short, long)]
         contract: String,
 
         #[arg(short, long)]
-        network: String,
+        network: Option<String>,
 
         #[arg(short, long)]
         function: String,
@@ -102,6 +611,21 @@ pub enum Commands {
 
 impl Cli {
     pub async fn execute(&self) -> anyhow::Result<()> {
+        x_core::project::activate(self.profile.as_deref(), self.account.as_deref())?;
+
+        let adds_network = matches!(&self.command, Commands::Network { action: network::NetworkAction::Add { .. } });
+        if self.chain_id.is_some() && self.rpc_url.is_none() && !adds_network {
+            anyhow::bail!("--chain-id requires --rpc-url");
+        }
+
+        if let Some(rpc_url) = &self.rpc_url {
+            let chain_id = match self.chain_id {
+                Some(chain_id) => chain_id,
+                None => x_core::network::fetch_chain_id(rpc_url).await?,
+            };
+            x_core::networks::set_rpc_override(rpc_url, chain_id)?;
+        }
+
         match &self.command {
             Commands::Sign {
                 message,
@@ -121,7 +645,7 @@ impl Cli {
                 notes,
             } => {
                 transfer_eth::handle_transfer_eth(
-                    network.clone(),
+                    resolve_network(network)?,
                     *amount,
                     address.clone(),
                     notes.clone(),
@@ -134,24 +658,249 @@ impl Cli {
                 gas_strategy,
             } => {
                 deploy::handle_deploy(
-                    network.clone(),
+                    resolve_network(network)?,
+                    contract.clone(),
+                    resolve_gas_strategy(gas_strategy),
+                ).await
+            }
+
+            Commands::DeployPlan {
+                plan,
+                network,
+                gas_strategy,
+            } => {
+                deploy_plan::handle_deploy_plan(
+                    plan.clone(),
+                    resolve_network(network)?,
+                    resolve_gas_strategy(gas_strategy),
+                ).await
+            }
+
+            Commands::DeployProxy {
+                network,
+                contract,
+                kind,
+                init,
+                args,
+                gas_strategy,
+            } => {
+                proxy::handle_deploy_proxy(
+                    resolve_network(network)?,
+                    contract.clone(),
+                    kind.clone(),
+                    init.clone(),
+                    args.clone(),
+                    resolve_gas_strategy(gas_strategy),
+                ).await
+            }
+
+            Commands::Upgrade {
+                network,
+                proxy,
+                contract,
+                kind,
+                call,
+                args,
+                skip_storage_check,
+                gas_strategy,
+            } => {
+                proxy::handle_upgrade(
+                    resolve_network(network)?,
+                    proxy.clone(),
+                    contract.clone(),
+                    kind.clone(),
+                    call.clone(),
+                    args.clone(),
+                    *skip_storage_check,
+                    resolve_gas_strategy(gas_strategy),
+                ).await
+            }
+
+            Commands::StorageCheck { network, proxy, contract } => {
+                proxy::handle_storage_check(resolve_network(network)?, proxy.clone(), contract.clone()).await
+            }
+
+            Commands::ProxyInfo { network, proxy } => {
+                proxy::handle_proxy_info(resolve_network(network)?, proxy.clone()).await
+            }
+
+            Commands::Deployments { action } => {
+                deployments::handle_deployments(action.clone()).await
+            }
+
+            Commands::CheckDeployments { network } => {
+                check_deployments::handle_check_deployments(resolve_network(network)?).await
+            }
+
+            Commands::CompileSc { contract, backend, watch } => {
+                compile_sc::handle_compile_sc(contract.clone(), backend.clone(), *watch).await
+            }
+
+            Commands::BuildTx {
+                network,
+                from,
+                to,
+                value,
+                data,
+                contract,
+                function,
+                args,
+                deploy,
+                legacy,
+                gas_strategy,
+                out,
+            } => {
+                let target = offline_tx::BuildTarget {
+                    to: to.clone(),
+                    value: value.clone(),
+                    data: data.clone(),
+                    contract: contract.clone(),
+                    function: function.clone(),
+                    args: args.clone(),
+                    deploy: deploy.clone(),
+                };
+                offline_tx::handle_build_tx(
+                    resolve_network(network)?,
+                    from.clone(),
+                    target,
+                    *legacy,
+                    resolve_gas_strategy(gas_strategy),
+                    out.clone(),
+                ).await
+            }
+
+            Commands::SignTx { input, private_key, out } => {
+                offline_tx::handle_sign_tx(input.clone(), private_key.clone(), out.clone()).await
+            }
+
+            Commands::Broadcast { network, raw } => {
+                offline_tx::handle_broadcast(resolve_network(network)?, raw.clone()).await
+            }
+
+            Commands::DecodeTx { raw } => {
+                decode_tx::handle_decode_tx(raw.clone()).await
+            }
+
+            Commands::Tx { network, hash, wait } => {
+                tx::handle_tx(resolve_network(network)?, hash.clone(), *wait).await
+            }
+
+            Commands::AbiEncode { signature, args } => {
+                abi::handle_abi_encode(signature.clone(), args.clone()).await
+            }
+
+            Commands::AbiDecode { input, output, data } => {
+                abi::handle_abi_decode(input.clone(), output.clone(), data.clone()).await
+            }
+
+            Commands::Selector { signature } => {
+                abi::handle_selector(signature.clone()).await
+            }
+
+            Commands::CalldataDecode { artifact, data } => {
+                abi::handle_calldata_decode(artifact.clone(), data.clone()).await
+            }
+
+            Commands::Signatures { action } => {
+                signatures::handle_signatures(action.clone()).await
+            }
+
+            Commands::Network { action } => {
+                network::handle_network(action.clone(), self.chain_id).await
+            }
+
+            Commands::TransferToken { network, token, to, amount, from, gas_strategy } => {
+                erc20::handle_transfer_token(
+                    resolve_network(network)?,
+                    token.clone(),
+                    to.clone(),
+                    amount.clone(),
+                    from.clone(),
+                    resolve_gas_strategy(gas_strategy),
+                ).await
+            }
+
+            Commands::Approve { network, token, spender, amount, gas_strategy } => {
+                erc20::handle_approve(resolve_network(network)?, token.clone(), spender.clone(), amount.clone(), resolve_gas_strategy(gas_strategy)).await
+            }
+
+            Commands::Allowance { network, token, owner, spender } => {
+                erc20::handle_allowance(resolve_network(network)?, token.clone(), owner.clone(), spender.clone()).await
+            }
+
+            Commands::NftMint { network, contract, to, wallets, gas_strategy } => {
+                nft::handle_nft_mint(resolve_network(network)?, contract.clone(), to.clone(), wallets.clone(), resolve_gas_strategy(gas_strategy)).await
+            }
+
+            Commands::NftTransfer { network, contract, to, ids, amounts, gas_strategy } => {
+                nft::handle_nft_transfer(
+                    resolve_network(network)?,
                     contract.clone(),
-                    gas_strategy.clone(),
+                    to.clone(),
+                    ids.clone(),
+                    amounts.clone(),
+                    resolve_gas_strategy(gas_strategy),
                 ).await
             }
 
-            Commands::CompileSc { contract } => {
-                compile_sc::handle_compile_sc(contract.clone()).await
+            Commands::NftOwned { network, contract, owner, ids } => {
+                nft::handle_nft_owned(resolve_network(network)?, contract.clone(), owner.clone(), ids.clone()).await
+            }
+
+            Commands::NftUri { network, contract, id } => {
+                nft::handle_nft_uri(resolve_network(network)?, contract.clone(), id.clone()).await
+            }
+
+            Commands::Approvals { network, address, from_block, to_block, spender, revoke, gas_strategy } => {
+                approvals::handle_approvals(
+                    resolve_network(network)?,
+                    address.clone(),
+                    *from_block,
+                    *to_block,
+                    spender.clone(),
+                    *revoke,
+                    resolve_gas_strategy(gas_strategy),
+                ).await
+            }
+
+            Commands::Permit { network, token, spender, amount, kind, deadline, expiration, nonce, to, private_key, typed_data } => {
+                permit::handle_permit(
+                    resolve_network(network)?,
+                    token.clone(),
+                    spender.clone(),
+                    amount.clone(),
+                    kind.clone(),
+                    *deadline,
+                    *expiration,
+                    *nonce,
+                    to.clone(),
+                    private_key.clone(),
+                    *typed_data,
+                ).await
+            }
+
+            Commands::Balance { address, wallets, network, all_networks, token } => {
+                balance::handle_balance(address.clone(), wallets.clone(), network.clone(), *all_networks, token.clone()).await
             }
 
             Commands::GenWallet { count, filename } => {
                 gen_wallet::handle_gen_wallet(*count, filename.clone()).await
             }
 
+            Commands::InvokeBatch { file, network, sequential, batcher, gas_strategy } => {
+                invoke_batch::handle_invoke_batch(
+                    file.clone(),
+                    resolve_network(network)?,
+                    *sequential,
+                    batcher.clone(),
+                    resolve_gas_strategy(gas_strategy),
+                ).await
+            }
+
             Commands::InvokeStress { contract, network, function, args, transactions, interval } => {
                 invoke_stress::handle_invoke_stress(
                     contract.clone(),
-                    network.clone(),
+                    resolve_network(network)?,
                     function.clone(),
                     args.clone(),
                     *transactions,
@@ -0,0 +1,205 @@
+use clap::Subcommand;
+use colored::Colorize;
+use std::path::PathBuf;
+use x_core::network::{probe_rpc, probe_ws};
+use x_core::networks::{self, BlockExplorer, Currency, Network, PROJECT_NETWORKS_FILE};
+
+#[derive(Subcommand, Clone)]
+pub enum NetworkAction {
+    /// Every configured network, built-in ones and overrides
+    List,
+
+    /// The merged configuration of one network
+    Show {
+        id: String,
+    },
+
+    /// Add or replace a network declared with the global --chain-id, checked against every RPC
+    Add {
+        id: String,
+
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        rpc: Vec<String>,
+
+        #[arg(short, long, value_delimiter = ',')]
+        ws_rpc: Vec<String>,
+
+        #[arg(short, long, default_value = "ETH")]
+        symbol: String,
+
+        #[arg(long, default_value = "Ether")]
+        currency_name: String,
+
+        #[arg(short, long, default_value = "18")]
+        decimals: u32,
+
+        #[arg(short, long, default_value = "")]
+        explorer: String,
+
+        /// Write to networks.json in the working directory instead of the user config
+        #[arg(short, long)]
+        project: bool,
+    },
+
+    /// Remove a network added with `network add`
+    Remove {
+        id: String,
+
+        #[arg(short, long)]
+        project: bool,
+    },
+
+    /// Check chain id, EIP-1559 support and reachability of every RPC and WebSocket endpoint
+    Test {
+        /// Network to test, every network when omitted
+        id: Option<String>,
+    },
+}
+
+fn target_file(project: bool) -> anyhow::Result<PathBuf> {
+    if project {
+        return Ok(PathBuf::from(PROJECT_NETWORKS_FILE));
+    }
+    networks::user_networks_path().ok_or_else(|| anyhow::anyhow!("Cannot locate the user config directory, use --project"))
+}
+
+/// Probes every endpoint of `network`, printing one line each; returns the number of failures
+async fn test_network(network: &Network) -> usize {
+    println!("{} ({}, chain id {})", network.id.bold(), network.name, network.chain_id);
+    let mut failed = 0;
+
+    for url in &network.rpc {
+        match probe_rpc(url).await {
+            Ok(probe) if probe.chain_id != network.chain_id => {
+                failed += 1;
+                println!("  {} {} serves chain id {}, expected {}", "✗".red(), url, probe.chain_id, network.chain_id);
+            }
+            Ok(probe) => println!(
+                "  {} {} block {} in {}ms, {}",
+                "✓".green(),
+                url,
+                probe.block_number,
+                probe.latency.as_millis(),
+                if probe.eip1559 { "EIP-1559" } else { "legacy gas" }
+            ),
+            Err(e) => {
+                failed += 1;
+                println!("  {} {} {}", "✗".red(), url, e);
+            }
+        }
+    }
+
+    for url in &network.ws_rpc {
+        match probe_ws(url).await {
+            Ok((chain_id, _)) if chain_id != network.chain_id => {
+                failed += 1;
+                println!("  {} {} serves chain id {}, expected {}", "✗".red(), url, chain_id, network.chain_id);
+            }
+            Ok((_, latency)) => println!("  {} {} reachable in {}ms", "✓".green(), url, latency.as_millis()),
+            Err(e) => {
+                failed += 1;
+                println!("  {} {} {}", "✗".red(), url, e);
+            }
+        }
+    }
+
+    failed
+}
+
+pub async fn handle_network(action: NetworkAction, chain_id: Option<u64>) -> anyhow::Result<()> {
+    match action {
+        NetworkAction::List => {
+            let networks = networks::load_networks()?;
+            let header = format!("{:<24} {:<32} {:>10} {}", "ID", "NAME", "CHAIN ID", "RPC");
+            println!("{}", header.bold());
+            for network in &networks {
+                println!(
+                    "{:<24} {:<32} {:>10} {}",
+                    network.id,
+                    network.name,
+                    network.chain_id,
+                    network.rpc.first().map(String::as_str).unwrap_or_default()
+                );
+            }
+        }
+        NetworkAction::Show { id } => {
+            let networks = networks::load_networks()?;
+            let network = networks::get_network_by_id(&networks, &id)
+                .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", id))?;
+            println!("Id: {}", network.id);
+            println!("Name: {}", network.name);
+            println!("Chain ID: {}", network.chain_id);
+            println!("RPC: {}", network.rpc.join(", "));
+            println!("WebSocket: {}", network.ws_rpc.join(", "));
+            println!("Currency: {} ({}, {} decimals)", network.currency.symbol, network.currency.name, network.currency.decimals);
+            println!("Explorer: {}", network.block_explorer.url);
+        }
+        NetworkAction::Add { id, name, rpc, ws_rpc, symbol, currency_name, decimals, explorer, project } => {
+            let chain_id = chain_id.ok_or_else(|| anyhow::anyhow!("Pass the network's --chain-id, every RPC is checked against it"))?;
+            let path = target_file(project)?;
+
+            for url in &rpc {
+                let probe = probe_rpc(url).await.map_err(|e| anyhow::anyhow!("{}: {}", url, e))?;
+                if probe.chain_id != chain_id {
+                    anyhow::bail!("{} serves chain id {}, expected {}", url, probe.chain_id, chain_id);
+                }
+                println!("{} {} chain id {}, {}", "✓".green(), url, probe.chain_id, if probe.eip1559 { "EIP-1559" } else { "legacy gas" });
+            }
+
+            for url in &ws_rpc {
+                match probe_ws(url).await {
+                    Ok((ws_chain_id, _)) if ws_chain_id != chain_id => {
+                        anyhow::bail!("{} serves chain id {}, expected {}", url, ws_chain_id, chain_id);
+                    }
+                    Ok(_) => println!("{} {} reachable", "✓".green(), url),
+                    Err(e) => println!("{} {} unreachable: {}", "⚠".yellow(), url, e),
+                }
+            }
+
+            let network = Network {
+                name: name.unwrap_or_else(|| id.clone()),
+                id,
+                chain_id,
+                rpc,
+                ws_rpc,
+                currency: Currency { name: currency_name, symbol, decimals },
+                block_explorer: BlockExplorer { url: explorer },
+            };
+            networks::save_network(&path, &network)?;
+            println!("Saved '{}' (chain id {}) to {}", network.id, chain_id, path.display());
+        }
+        NetworkAction::Remove { id, project } => {
+            let path = target_file(project)?;
+            if networks::remove_network(&path, &id)? {
+                println!("Removed '{}' from {}", id, path.display());
+                if networks::is_builtin_network(&id) {
+                    println!("'{}' is built in, its default configuration applies again", id);
+                }
+            } else if networks::is_builtin_network(&id) {
+                anyhow::bail!("'{}' is built in and can only be overridden, not removed", id);
+            } else {
+                anyhow::bail!("Network '{}' not found in {}", id, path.display());
+            }
+        }
+        NetworkAction::Test { id } => {
+            let networks = networks::load_networks()?;
+            let selected: Vec<&Network> = match &id {
+                Some(id) => vec![networks::get_network_by_id(&networks, id)
+                    .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", id))?],
+                None => networks.iter().collect(),
+            };
+
+            let mut failed = 0;
+            for network in selected {
+                failed += test_network(network).await;
+            }
+            if failed > 0 {
+                anyhow::bail!("{} endpoint(s) failed", failed);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use colored::Colorize;
+use ethers::types::U256;
+use x_core::config;
+use x_core::gas::GasStrategy;
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use x_transfer::nft::{self, NftStandard};
+
+use super::balance::load_wallet_addresses;
+use super::erc20::resolve_token;
+use super::helpers::{load_network, parse_address};
+
+fn parse_ids(values: &[String]) -> anyhow::Result<Vec<U256>> {
+    values
+        .iter()
+        .map(|v| U256::from_dec_str(v.trim()).map_err(|_| anyhow::anyhow!("Invalid number: {}", v)))
+        .collect()
+}
+
+async fn connect(network: &Network) -> anyhow::Result<HttpClient> {
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    HttpClient::new(rpc_url).await
+}
+
+pub async fn handle_nft_mint(
+    network: String,
+    contract: String,
+    to: Vec<String>,
+    wallets: Option<String>,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let network_obj = load_network(&network)?;
+    let nft = resolve_token(&network, &contract)?;
+
+    let mut recipients = to.iter().map(|a| parse_address(a)).collect::<anyhow::Result<Vec<_>>>()?;
+    if let Some(file) = wallets {
+        recipients.extend(load_wallet_addresses(&file)?);
+    }
+    if recipients.is_empty() {
+        anyhow::bail!("Pass at least one --to address or a --wallets file");
+    }
+
+    println!("Minting {} token(s) on {:#x}...", recipients.len(), nft);
+
+    let results = nft::mint_batch(&private_key, &network_obj, nft, &recipients, gas_strategy.parse::<GasStrategy>()?).await?;
+
+    for result in &results {
+        let ids: Vec<String> = result.token_ids.iter().map(|id| id.to_string()).collect();
+        if result.success {
+            println!("{} {:#x} token {} ({})", "✓".green(), result.recipient, ids.join(", "), result.tx_hash);
+        } else {
+            println!("{} {:#x} reverted ({})", "✗".red(), result.recipient, result.tx_hash);
+        }
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} mints reverted", failed, results.len());
+    }
+    Ok(())
+}
+
+pub async fn handle_nft_transfer(
+    network: String,
+    contract: String,
+    to: String,
+    ids: Vec<String>,
+    amounts: Vec<String>,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let network_obj = load_network(&network)?;
+    let nft = resolve_token(&network, &contract)?;
+    let to = parse_address(&to)?;
+
+    let (tx_hash, transfers) = nft::transfer_nft(
+        &private_key,
+        &network_obj,
+        nft,
+        to,
+        &parse_ids(&ids)?,
+        &parse_ids(&amounts)?,
+        gas_strategy.parse::<GasStrategy>()?,
+    )
+    .await?;
+
+    println!("Transaction successful!");
+    println!("TX Hash: {}", tx_hash);
+    for transfer in &transfers {
+        println!("  Token {} x{}: {:#x} -> {:#x}", transfer.id, transfer.amount, transfer.from, transfer.to);
+    }
+    println!("View on Explorer: {}/tx/{}", network_obj.block_explorer.url, tx_hash);
+    Ok(())
+}
+
+/// ERC-721 holdings through enumeration, or ERC-1155 balances of the given ids
+pub async fn handle_nft_owned(
+    network: String,
+    contract: String,
+    owner: Option<String>,
+    ids: Vec<String>,
+) -> anyhow::Result<()> {
+    let network_obj = load_network(&network)?;
+    let nft = resolve_token(&network, &contract)?;
+    let owner = match owner {
+        Some(owner) => parse_address(&owner)?,
+        None => x_signature::get_address_from_private_key(&config::load_private_key()?)?,
+    };
+    let client = connect(&network_obj).await?;
+
+    match nft::detect_standard(&client, nft).await? {
+        NftStandard::Erc721 => {
+            let owned = nft::owned_tokens(&client, nft, owner).await?;
+            println!("{:#x} owns {} token(s) of {:#x}", owner, owned.len(), nft);
+            for id in owned {
+                println!("  {}", id);
+            }
+        }
+        NftStandard::Erc1155 => {
+            if ids.is_empty() {
+                anyhow::bail!("ERC-1155 holdings cannot be enumerated, pass the token ids to check with --ids");
+            }
+            let ids = parse_ids(&ids)?;
+            let balances = nft::balance_of_batch(&client, nft, &vec![owner; ids.len()], &ids).await?;
+            println!("Balances of {:#x} on {:#x}:", owner, nft);
+            for (id, balance) in ids.iter().zip(balances) {
+                println!("  {}: {}", id, balance);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_nft_uri(network: String, contract: String, id: String) -> anyhow::Result<()> {
+    let network_obj = load_network(&network)?;
+    let nft = resolve_token(&network, &contract)?;
+    let id = U256::from_dec_str(id.trim()).map_err(|_| anyhow::anyhow!("Invalid token id: {}", id))?;
+    let client = connect(&network_obj).await?;
+
+    let uri = nft::token_uri(&client, nft, id).await?;
+    println!("{}", uri);
+    if let Some(path) = uri.strip_prefix("ipfs://") {
+        println!("Gateway: https://ipfs.io/ipfs/{}", path);
+    }
+    Ok(())
+}
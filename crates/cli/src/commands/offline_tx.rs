@@ -0,0 +1,200 @@
+use ethers::types::{Address, Bytes, U256};
+use ethers::utils::{parse_units, ParseUnits};
+use std::fs;
+use x_core::config;
+use x_core::gas::GasStrategy;
+use x_core::invoker::{Codec, DynAbiConstructor};
+use x_core::network::HttpClient;
+use x_core::project;
+use x_core::registry::DeploymentRegistry;
+use x_core::transaction::{TransactionBuilder, TransactionIntent, TxType, UnsignedTransaction};
+use super::helpers::{parse_hex, split_args};
+
+/// What `build-tx` should put in the transaction
+pub struct BuildTarget {
+    pub to: Option<String>,
+    pub value: String,
+    pub data: Option<String>,
+    pub contract: Option<String>,
+    pub function: Option<String>,
+    pub args: String,
+    pub deploy: Option<String>,
+}
+
+/// Parses an ether amount into wei, rejecting negative values
+fn parse_value(value: &str) -> anyhow::Result<U256> {
+    let value = value.trim();
+    match parse_units(value, "ether").map_err(|e| anyhow::anyhow!("Invalid value {}: {}", value, e))? {
+        ParseUnits::U256(wei) => Ok(wei),
+        ParseUnits::I256(_) => anyhow::bail!("Invalid value {}: must not be negative", value),
+    }
+}
+
+fn encode_constructor(artifact: &x_deploy::ContractArtifact, args: &[String]) -> anyhow::Result<Vec<u8>> {
+    let abi_str = artifact.abi.to_string();
+    let constructor = DynAbiConstructor::from_json_abi(&abi_str)?;
+    let inputs = constructor.get_inputs();
+
+    if args.len() != inputs.len() {
+        anyhow::bail!("Expected {} constructor arguments, got {}", inputs.len(), args.len());
+    }
+
+    let mut values = Vec::new();
+    for (i, (arg, (_, ty))) in args.iter().zip(&inputs).enumerate() {
+        let value = Codec::parse_value(arg, ty)
+            .map_err(|e| anyhow::anyhow!("Failed to parse argument {}: {}", i, e))?;
+        values.push(value);
+    }
+
+    constructor.encode_input(&values)
+}
+
+/// Resolves the recipient and calldata of a transfer, contract call or deployment
+fn resolve_target(network: &str, target: &BuildTarget) -> anyhow::Result<(Option<Address>, Bytes)> {
+    if let Some(contract) = &target.deploy {
+        if target.to.is_some() || target.contract.is_some() || target.data.is_some() {
+            anyhow::bail!("--deploy cannot be combined with --to, --contract or --data");
+        }
+        let artifact = x_deploy::ArtifactLoader::load_contract(project::artifacts_dir(), contract)?;
+        let mut init_code = x_deploy::ArtifactLoader::get_bytecode(&artifact)?.to_vec();
+        init_code.extend(encode_constructor(&artifact, &split_args(&target.args))?);
+        return Ok((None, Bytes::from(init_code)));
+    }
+
+    if let Some(contract) = &target.contract {
+        if target.to.is_some() || target.data.is_some() {
+            anyhow::bail!("--contract cannot be combined with --to or --data");
+        }
+        let function = target
+            .function
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("--contract requires --function"))?;
+
+        let registry = DeploymentRegistry::load(project::deployments_dir(), network)?;
+        let (_, entry) = registry.resolve(contract)?;
+        let artifact = x_deploy::ArtifactLoader::load_contract(project::artifacts_dir(), &entry.artifact)?;
+        let data = x_deploy::proxy::encode_function_call(&artifact, function, &split_args(&target.args))?;
+        let address = entry
+            .address
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid address registered for {}", contract))?;
+        return Ok((Some(address), Bytes::from(data)));
+    }
+
+    let to = target
+        .to
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Specify --to, --contract or --deploy"))?
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid recipient address"))?;
+    let data = target.data.as_deref().map(parse_hex).transpose()?.unwrap_or_default();
+    Ok((Some(to), Bytes::from(data)))
+}
+
+pub async fn handle_build_tx(
+    network: String,
+    from: Option<String>,
+    target: BuildTarget,
+    legacy: bool,
+    gas_strategy: String,
+    out: Option<String>,
+) -> anyhow::Result<()> {
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+    let strategy = gas_strategy.parse::<GasStrategy>()?;
+
+    let from: Address = match from {
+        Some(from) => from.parse().map_err(|_| anyhow::anyhow!("Invalid sender address"))?,
+        None => x_signature::get_address_from_private_key(&config::load_private_key()?)?,
+    };
+
+    let value = parse_value(&target.value)?;
+    let (to, data) = resolve_target(&network, &target)?;
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    let builder = TransactionBuilder::new(rpc_url, network_obj).await?;
+    let tx = builder
+        .build(
+            TransactionIntent { from, to, value, data },
+            legacy.then_some(TxType::Legacy),
+            strategy,
+        )
+        .await?;
+
+    let json = tx.to_json()?;
+    match out {
+        Some(path) => {
+            fs::write(&path, &json)?;
+            eprintln!("✓ Unsigned transaction written to {} (nonce {}, gas {})", path, tx.nonce, tx.gas);
+        }
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+/// Signs an unsigned transaction file, or stdin when `input` is `-`; needs no network access
+pub async fn handle_sign_tx(input: String, private_key: Option<String>, out: Option<String>) -> anyhow::Result<()> {
+    let json = if input == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        fs::read_to_string(&input).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", input, e))?
+    };
+
+    let tx = UnsignedTransaction::from_json(&json)?;
+    let private_key = match private_key {
+        Some(key) => key,
+        None => config::load_private_key()?,
+    };
+
+    let raw = format!("0x{}", hex::encode(tx.sign(&private_key)?));
+    match out {
+        Some(path) => {
+            fs::write(&path, &raw)?;
+            eprintln!("✓ Signed transaction written to {}", path);
+        }
+        None => println!("{}", raw),
+    }
+
+    Ok(())
+}
+
+pub async fn handle_broadcast(network: String, raw: String) -> anyhow::Result<()> {
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    // Accept a file written by `sign-tx --out` as well as the hex itself
+    let raw = match fs::read_to_string(&raw) {
+        Ok(content) => content,
+        Err(_) => raw,
+    };
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    let http_client = HttpClient::for_network(rpc_url, network_obj).await?;
+    let tx_hash = http_client.send_raw_transaction(&Bytes::from(parse_hex(&raw)?)).await?;
+
+    println!("✓ Transaction broadcast");
+    println!("TX Hash: {:#x}", tx_hash);
+    println!("View on Explorer: {}/tx/{:#x}", network_obj.block_explorer.url, tx_hash);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value_rejects_negative() {
+        assert_eq!(parse_value("1.5").unwrap(), U256::from(1_500_000_000_000_000_000u64));
+        assert_eq!(parse_value("0").unwrap(), U256::zero());
+        assert!(parse_value("-1").is_err());
+        assert!(parse_value(" -0.5").is_err());
+        assert!(parse_value("abc").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use ethers::types::{Address, Bytes, U256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use x_core::config;
+use x_core::network::HttpClient;
+use x_signature::permit::{self, Erc2612Permit, PermitSingle, PermitTransferFrom, SignedTypedData};
+use x_transfer::erc20;
+
+use super::erc20::resolve_token;
+use super::helpers::parse_address;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PermitKind {
+    /// EIP-2612 `permit` on the token itself
+    Erc2612,
+    /// Permit2 `PermitSingle` allowance
+    Permit2Single,
+    /// Permit2 `PermitTransferFrom` one-off transfer
+    Permit2Transfer,
+}
+
+fn parse_kind(kind: &str) -> anyhow::Result<PermitKind> {
+    match kind {
+        "erc2612" => Ok(PermitKind::Erc2612),
+        "permit2-single" => Ok(PermitKind::Permit2Single),
+        "permit2-transfer" => Ok(PermitKind::Permit2Transfer),
+        _ => Err(anyhow::anyhow!("Invalid permit kind: {} (expected erc2612, permit2-single or permit2-transfer)", kind)),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+fn print_signed(signed: &SignedTypedData, target: Address, calldata: &Bytes, typed_data: bool) -> anyhow::Result<()> {
+    let bytes = signed.signature.to_vec();
+
+    println!("Digest: {:#x}", signed.digest);
+    println!("v: {}", signed.signature.v);
+    println!("r: 0x{}", hex::encode(&bytes[..32]));
+    println!("s: 0x{}", hex::encode(&bytes[32..64]));
+    println!("Signature: 0x{}", hex::encode(&bytes));
+    println!("Target: {:#x}", target);
+    println!("Calldata: 0x{}", hex::encode(calldata));
+
+    if typed_data {
+        println!("\nTyped Data:\n{}", signed.typed_data_json()?);
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_permit(
+    network: String,
+    token: String,
+    spender: String,
+    amount: String,
+    kind: String,
+    deadline: u64,
+    expiration: u64,
+    nonce: Option<u64>,
+    to: Option<String>,
+    private_key: Option<String>,
+    typed_data: bool,
+) -> anyhow::Result<()> {
+    let kind = parse_kind(&kind)?;
+    let private_key = match private_key {
+        Some(key) => key,
+        None => config::load_private_key()?,
+    };
+    let owner = x_signature::get_address_from_private_key(&private_key)?;
+
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::for_network(rpc_url, network_obj).await?;
+
+    let token = resolve_token(&network, &token)?;
+    let spender = parse_address(&spender)?;
+    let info = erc20::token_info(&client, token).await?;
+    let value = info.parse_amount(&amount)?;
+    let deadline = U256::from(now() + deadline);
+
+    println!("Owner: {:#x}", owner);
+    println!("Token: {:#x} ({})", token, info.symbol);
+    println!("Spender: {:#x}", spender);
+    println!("Amount: {}", info.format_amount(value));
+    println!("Deadline: {}", deadline);
+
+    match kind {
+        PermitKind::Erc2612 => {
+            let domain = permit::token_domain(&client, token, network_obj.chain_id).await?;
+            let nonce = match nonce {
+                Some(nonce) => U256::from(nonce),
+                None => permit::token_nonce(&client, token, owner).await?,
+            };
+            println!("Nonce: {}", nonce);
+
+            let permit = Erc2612Permit { owner, spender, value, nonce, deadline };
+            let signed = permit::sign_typed_data(&private_key, permit.typed_data(domain))?;
+            print_signed(&signed, token, &permit.calldata(&signed.signature), typed_data)
+        }
+        PermitKind::Permit2Single => {
+            let max = (U256::one() << 160) - 1;
+            let nonce = match nonce {
+                Some(nonce) => nonce,
+                None => permit::permit2_allowance_nonce(&client, owner, token, spender).await?.as_u64(),
+            };
+            println!("Nonce: {}", nonce);
+
+            let permit = PermitSingle {
+                token,
+                amount: value.min(max),
+                expiration: now() + expiration,
+                nonce,
+                spender,
+                sig_deadline: deadline,
+            };
+            println!("Expiration: {}", permit.expiration);
+
+            let signed = permit::sign_typed_data(&private_key, permit.typed_data(network_obj.chain_id))?;
+            print_signed(&signed, permit::permit2_address(), &permit.calldata(owner, &signed.signature), typed_data)
+        }
+        PermitKind::Permit2Transfer => {
+            if value == U256::MAX {
+                anyhow::bail!("'max' is not valid for a transfer permit");
+            }
+            let nonce = match nonce {
+                Some(nonce) => U256::from(nonce),
+                None => permit::permit2_unused_nonce(&client, owner).await?,
+            };
+            println!("Nonce: {}", nonce);
+
+            let to = match to {
+                Some(to) => parse_address(&to)?,
+                None => spender,
+            };
+            let permit = PermitTransferFrom { token, amount: value, spender, nonce, deadline };
+
+            let signed = permit::sign_typed_data(&private_key, permit.typed_data(network_obj.chain_id))?;
+            print_signed(&signed, permit::permit2_address(), &permit.calldata(owner, to, &signed.signature), typed_data)
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use x_core::config;
+use x_core::gas::GasStrategy;
+use x_core::network::HttpClient;
+use x_core::project;
+use x_deploy::{MetadataManager, ProxyKind, ProxyManager};
+use super::helpers::split_args;
+
+fn encode_call(
+    artifact: &x_deploy::ContractArtifact,
+    function: Option<&str>,
+    args: &str,
+) -> anyhow::Result<Vec<u8>> {
+    match function {
+        Some(function) => x_deploy::proxy::encode_function_call(artifact, function, &split_args(args)),
+        None if args.is_empty() => Ok(Vec::new()),
+        None => Err(anyhow::anyhow!("--args given without --call")),
+    }
+}
+
+pub async fn handle_deploy_proxy(
+    network: String,
+    contract: String,
+    kind: String,
+    init: Option<String>,
+    args: String,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let strategy = gas_strategy.parse::<GasStrategy>()?;
+    let kind = ProxyKind::parse(&kind)?;
+
+    let artifact_path = x_core::artifacts::resolve_artifact(project::artifacts_dir(), &contract)?;
+    println!("Loading contract artifact from {}...", artifact_path.display());
+    let artifact = x_deploy::ArtifactLoader::load_artifact(&artifact_path)?;
+
+    let init_data = encode_call(&artifact, init.as_deref(), &args)?;
+
+    println!("Deploying {} behind a {} proxy on {} with {:?} strategy...", contract, kind, network_obj.name, strategy);
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    let manager = ProxyManager::new(rpc_url, &private_key, network_obj.clone()).await?;
+    let result = manager
+        .deploy_proxy(&contract, &artifact, kind, init_data, project::artifacts_dir(), strategy)
+        .await?;
+
+    println!("\n✓ Proxy deployment successful!");
+    println!("Proxy Address: {:#x}", result.proxy_address);
+    println!("Implementation Address: {:#x}", result.implementation_address);
+    if let Some(admin) = result.admin {
+        println!("Proxy Admin: {:#x}", admin);
+    }
+    println!("Proxy Transaction Hash: {:#x}", result.proxy_tx_hash);
+    println!("Implementation Transaction Hash: {:#x}", result.implementation_tx_hash);
+    println!("View Proxy: {}/address/{:#x}", network_obj.block_explorer.url, result.proxy_address);
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_upgrade(
+    network: String,
+    proxy: String,
+    contract: String,
+    kind: Option<String>,
+    call: Option<String>,
+    args: String,
+    skip_storage_check: bool,
+    gas_strategy: String,
+) -> anyhow::Result<()> {
+    let private_key = config::load_private_key()?;
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let strategy = gas_strategy.parse::<GasStrategy>()?;
+    let kind = kind.as_deref().map(ProxyKind::parse).transpose()?;
+    let proxy_address: ethers::types::Address = proxy
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid proxy address: {}", proxy))?;
+
+    let artifact_path = x_core::artifacts::resolve_artifact(project::artifacts_dir(), &contract)?;
+    println!("Loading contract artifact from {}...", artifact_path.display());
+    let artifact = x_deploy::ArtifactLoader::load_artifact(&artifact_path)?;
+
+    let call_data = encode_call(&artifact, call.as_deref(), &args)?;
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    let manager = ProxyManager::new(rpc_url, &private_key, network_obj.clone()).await?;
+
+    if skip_storage_check {
+        println!("⚠ Skipping storage layout check");
+    } else {
+        println!("Checking storage layout compatibility...");
+    }
+
+    println!("Upgrading proxy {:#x} to {} on {}...", proxy_address, contract, network_obj.name);
+    let result = manager
+        .upgrade(proxy_address, &contract, &artifact, kind, call_data, project::artifacts_dir(), !skip_storage_check, strategy)
+        .await?;
+
+    println!("\n✓ Upgrade successful!");
+    println!("Previous Implementation: {:#x}", result.previous_implementation);
+    println!("New Implementation: {:#x}", result.implementation_address);
+    println!("Implementation Transaction Hash: {:#x}", result.implementation_tx_hash);
+    println!("Upgrade Transaction Hash: {}", result.upgrade_tx_hash);
+    println!("View Transaction: {}/tx/{}", network_obj.block_explorer.url, result.upgrade_tx_hash);
+
+    Ok(())
+}
+
+pub async fn handle_proxy_info(network: String, proxy: String) -> anyhow::Result<()> {
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let proxy_address: ethers::types::Address = proxy
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid proxy address: {}", proxy))?;
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    let client = HttpClient::new(rpc_url).await?;
+
+    let implementation = x_deploy::proxy::read_implementation(&client, proxy_address).await?;
+    let admin = x_deploy::proxy::read_admin(&client, proxy_address).await?;
+
+    println!("Proxy: {:#x}", proxy_address);
+    println!("Implementation (EIP-1967 slot): {:#x}", implementation);
+    if !admin.is_zero() {
+        println!("Admin (EIP-1967 slot): {:#x}", admin);
+    }
+
+    match MetadataManager::find_proxy(&network, &format!("{:#x}", proxy_address))? {
+        Some(record) => {
+            println!("Kind: {}", record.kind);
+            println!("Implementation history:");
+            for (i, entry) in record.implementations.iter().enumerate() {
+                println!("  {}. {} at {} (tx {}, timestamp {})", i + 1, entry.contract_name, entry.address, entry.tx_hash, entry.timestamp);
+            }
+
+            let recorded = record.current_implementation().map(|i| i.address.to_lowercase());
+            if recorded.as_deref() != Some(format!("{:#x}", implementation).as_str()) {
+                println!("⚠ On-chain implementation differs from the last recorded one");
+            }
+        }
+        None => println!("Proxy is not tracked in {}/{}.proxies.json", project::deployments_dir(), network),
+    }
+
+    Ok(())
+}
+
+pub async fn handle_storage_check(network: String, proxy: String, contract: String) -> anyhow::Result<()> {
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let proxy_address: ethers::types::Address = proxy
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid proxy address: {}", proxy))?;
+
+    let artifact = x_deploy::ArtifactLoader::load_contract(project::artifacts_dir(), &contract)?;
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    let client = HttpClient::new(rpc_url).await?;
+    let diff = x_deploy::proxy::check_storage_layout(&client, &network_obj.id, proxy_address, &artifact, project::artifacts_dir()).await?;
+
+    println!("Storage layout of {} against proxy {:#x}:", contract, proxy_address);
+    println!("{}", diff);
+
+    if diff.is_compatible() {
+        println!("✓ Upgrade is storage compatible");
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("{} breaking storage change(s) found", diff.breaking_changes().len()))
+    }
+}
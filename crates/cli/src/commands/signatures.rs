@@ -0,0 +1,86 @@
+use clap::Subcommand;
+use std::fs;
+use x_core::artifacts::scan_artifacts;
+use x_core::invoker::{SignatureDb, DEFAULT_SIGNATURES_PATH};
+
+/// Compiled OpenZeppelin ABIs as shipped in its npm package, seeded besides the artifacts directory
+const OPENZEPPELIN_BUILD_DIR: &str = "lib/openzeppelin-contracts/build/contracts";
+
+#[derive(Subcommand, Clone)]
+pub enum SignaturesAction {
+    /// Add every function and event of the compiled ABIs in `artifacts/` and the OpenZeppelin build output
+    Seed,
+
+    /// Import a text dump, one signature per line, optionally preceded by its selector or topic
+    Import {
+        file: String,
+    },
+
+    /// Add a single signature, e.g. `transfer(address,uint256)` or `event Transfer(address indexed,address indexed,uint256)`
+    Add {
+        signature: String,
+    },
+
+    /// Candidate signatures for a 4-byte selector or a 32-byte event topic
+    Lookup {
+        hash: String,
+    },
+}
+
+pub async fn handle_signatures(action: SignaturesAction) -> anyhow::Result<()> {
+    let mut db = SignatureDb::load_default()?;
+
+    match action {
+        SignaturesAction::Seed => {
+            let dirs = [x_core::project::artifacts_dir(), OPENZEPPELIN_BUILD_DIR];
+            let mut added = 0;
+            let mut seeded = Vec::new();
+            for dir in dirs {
+                // Sources alone carry no ABI, only directories with compiled artifacts count
+                if scan_artifacts(dir).is_ok_and(|entries| !entries.is_empty()) {
+                    added += db.seed_from_dir(dir)?;
+                    seeded.push(dir);
+                }
+            }
+            if seeded.is_empty() {
+                anyhow::bail!("No compiled ABIs found in {}, run compile-sc first", dirs.join(" or "));
+            }
+
+            db.save()?;
+            println!("Added {} signature(s) from {}, {} in {}", added, seeded.join(", "), db.len(), DEFAULT_SIGNATURES_PATH);
+        }
+        SignaturesAction::Import { file } => {
+            let content = fs::read_to_string(&file).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file, e))?;
+            let added = db.import_text(&content);
+            db.save()?;
+            println!("Imported {} signature(s), {} in {}", added, db.len(), DEFAULT_SIGNATURES_PATH);
+        }
+        SignaturesAction::Add { signature } => {
+            if db.import_text(&signature) == 0 {
+                anyhow::bail!("Invalid or already known signature: {}", signature);
+            }
+            db.save()?;
+            println!("Added {}", signature);
+        }
+        SignaturesAction::Lookup { hash } => {
+            let bytes = hex::decode(hash.trim().trim_start_matches("0x"))
+                .map_err(|e| anyhow::anyhow!("Invalid hex: {}", e))?;
+
+            let candidates = match bytes.len() {
+                4 => db.lookup_function(bytes.as_slice().try_into()?),
+                32 => db.lookup_event(alloy_primitives::B256::from_slice(&bytes)),
+                n => anyhow::bail!("Expected a 4-byte selector or a 32-byte topic, got {} bytes", n),
+            };
+
+            if candidates.is_empty() {
+                println!("No known signature for {}", hash);
+            }
+            let prefix = if bytes.len() == 32 { "event " } else { "" };
+            for signature in candidates {
+                println!("{}{}", prefix, signature);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use alloy_primitives::B256;
+use colored::Colorize;
+use ethers::types::{TransactionReceipt, H256, U256};
+use ethers::utils::format_units;
+use std::time::Duration;
+use x_core::invoker::{CalldataDecoder, LogDecoder};
+use x_core::network::HttpClient;
+
+fn print_args(args: &[(String, String, String)]) {
+    for (name, ty, value) in args {
+        let name = if name.is_empty() { "_" } else { name.as_str() };
+        println!("    {} ({}): {}", name, ty, value);
+    }
+}
+
+/// `amount` in ether, or `n/a` when computing it overflowed
+fn format_ether(amount: Option<U256>, symbol: &str) -> anyhow::Result<String> {
+    match amount {
+        Some(amount) => Ok(format!("{} {}", format_units(amount, "ether")?, symbol)),
+        None => Ok("n/a".to_string()),
+    }
+}
+
+/// Consecutive polls a transaction may be unknown to the node before `--wait` gives up
+const MAX_MISSING_POLLS: u32 = 15;
+
+/// Polls until the receipt is `confirmations` blocks deep
+async fn wait_for_confirmations(
+    client: &HttpClient,
+    tx_hash: H256,
+    confirmations: u64,
+) -> anyhow::Result<TransactionReceipt> {
+    let mut last_reported = None;
+    let mut missing_polls = 0;
+
+    loop {
+        let receipt = client.get_transaction_receipt(tx_hash).await?;
+        let depth = match receipt.as_ref().and_then(|r| r.block_number) {
+            Some(block) => {
+                let latest = client.get_block_number().await?;
+                latest.saturating_sub(block).as_u64() + 1
+            }
+            None => 0,
+        };
+
+        if let Some(receipt) = receipt.filter(|_| depth >= confirmations) {
+            return Ok(receipt);
+        }
+
+        if depth == 0 && client.get_transaction(tx_hash).await?.is_none() {
+            missing_polls += 1;
+            if missing_polls >= MAX_MISSING_POLLS {
+                anyhow::bail!("Transaction {:#x} is unknown to the node, it was dropped or never broadcast", tx_hash);
+            }
+        } else {
+            missing_polls = 0;
+        }
+
+        if last_reported != Some(depth) {
+            if depth == 0 {
+                println!("{}", "Pending...".yellow());
+            } else {
+                println!("{}", format!("{}/{} confirmations", depth, confirmations).yellow());
+            }
+            last_reported = Some(depth);
+        }
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+}
+
+pub async fn handle_tx(network: String, hash: String, wait: Option<u64>) -> anyhow::Result<()> {
+    let networks = x_core::networks::load_networks()?;
+    let network_obj = x_core::networks::get_network_by_id(&networks, &network)
+        .ok_or_else(|| anyhow::anyhow!("Network '{}' not found", network))?;
+
+    let tx_hash: H256 = hash.trim().parse().map_err(|_| anyhow::anyhow!("Invalid transaction hash: {}", hash))?;
+
+    let rpc_url = network_obj.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::new(rpc_url).await?;
+
+    let receipt = match wait {
+        Some(confirmations) => Some(wait_for_confirmations(&client, tx_hash, confirmations.max(1)).await?),
+        None => client.get_transaction_receipt(tx_hash).await?,
+    };
+    let tx = client
+        .get_transaction(tx_hash)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Transaction {:#x} not found on {}", tx_hash, network_obj.name))?;
+
+    let symbol = &network_obj.currency.symbol;
+
+    println!("Hash: {:#x}", tx_hash);
+    match &receipt {
+        Some(receipt) => match receipt.status.map(|s| s.as_u64()) {
+            Some(1) => println!("Status: {}", "success".green().bold()),
+            Some(_) => println!("Status: {}", "reverted".red().bold()),
+            // Receipts before Byzantium carry a state root instead of a status
+            None => println!("Status: {}", "unknown".yellow().bold()),
+        },
+        None => println!("Status: {}", "pending".yellow().bold()),
+    }
+    if let Some(block) = receipt.as_ref().and_then(|r| r.block_number) {
+        let latest = client.get_block_number().await?;
+        println!("Block: {} ({} confirmations)", block, latest.saturating_sub(block).as_u64() + 1);
+    }
+    println!("From: {:#x}", tx.from);
+    match (tx.to, receipt.as_ref().and_then(|r| r.contract_address)) {
+        (Some(to), _) => println!("To: {:#x}", to),
+        (None, Some(created)) => println!("Created: {:#x}", created),
+        (None, None) => println!("To: (contract creation)"),
+    }
+    println!("Nonce: {}", tx.nonce);
+    println!("Value: {} {}", format_units(tx.value, "ether")?, symbol);
+
+    match receipt.as_ref().and_then(|r| r.gas_used) {
+        Some(gas_used) => {
+            match (u128::try_from(gas_used), u128::try_from(tx.gas)) {
+                (Ok(used), Ok(limit)) => {
+                    let percent = used as f64 * 100.0 / limit.max(1) as f64;
+                    println!("Gas Used: {} / {} ({:.1}%)", gas_used, tx.gas, percent);
+                }
+                _ => println!("Gas Used: {} / {}", gas_used, tx.gas),
+            }
+
+            let gas_price = receipt
+                .as_ref()
+                .and_then(|r| r.effective_gas_price)
+                .or(tx.gas_price)
+                .unwrap_or_default();
+            println!("Effective Gas Price: {} gwei", format_units(gas_price, "gwei")?);
+
+            let fee = gas_used.checked_mul(gas_price);
+            let total = fee.and_then(|fee| fee.checked_add(tx.value));
+            println!("Fee: {}", format_ether(fee, symbol)?);
+            println!("Total Cost: {}", format_ether(total, symbol)?);
+        }
+        None => println!("Gas Limit: {}", tx.gas),
+    }
+
+    if tx.to.is_some() && !tx.input.is_empty() {
+        let decoder = CalldataDecoder::from_artifacts(x_core::project::artifacts_dir())?;
+        match decoder.decode(&tx.input) {
+            Some(call) => {
+                println!("\nCall: {}", call);
+                print_args(&call.args);
+            }
+            None => println!("\nCall: unknown selector 0x{}", hex::encode(&tx.input[..tx.input.len().min(4)])),
+        }
+    }
+
+    if let Some(receipt) = &receipt
+        && !receipt.logs.is_empty()
+    {
+        let decoder = LogDecoder::from_artifacts(x_core::project::artifacts_dir())?;
+        println!("\nLogs:");
+        for (i, log) in receipt.logs.iter().enumerate() {
+            let topics: Vec<B256> = log.topics.iter().map(|t| B256::from(t.0)).collect();
+            match decoder.decode(&topics, &log.data) {
+                Some(decoded) => {
+                    println!("  [{}] {:#x} {}", i, log.address, decoded);
+                    print_args(&decoded.args);
+                }
+                None => {
+                    println!("  [{}] {:#x} unknown event", i, log.address);
+                    for topic in &log.topics {
+                        println!("    topic: {:#x}", topic);
+                    }
+                    if !log.data.is_empty() {
+                        println!("    data: 0x{}", hex::encode(&log.data));
+                    }
+                }
+            }
+        }
+    }
+
+    println!("\nView on Explorer: {}/tx/{:#x}", network_obj.block_explorer.url, tx_hash);
+    Ok(())
+}
@@ -19,9 +19,9 @@ mod invoker;
 #[path = "handlers/wallet.rs"]
 mod wallet;
 
-pub use utils::clear_screen;
+pub use utils::{clear_screen, print_diagnostics};
 pub use signature::{handle_sign, handle_verify};
-pub use transfer::handle_transfer_sepolia;
+pub use transfer::{handle_transfer_sepolia, handle_transfer_token};
 pub use compile::handle_compile_smart_contracts;
 pub use gate::{handle_gate_mainnet, handle_gate_sepolia, handle_gate_deploy};
 pub use invoker::handle_smart_contract_invoker;
@@ -1,7 +1,7 @@
 use colored::Colorize;
 use x_core::compiler::SmartContractCompiler;
 
-use super::utils::print_separator;
+use super::utils::{print_diagnostics, print_separator};
 use crate::ui::loading::{create_spinner, finish_spinner};
 
 pub fn handle_compile_smart_contracts() -> anyhow::Result<()> {
@@ -10,11 +10,27 @@ pub fn handle_compile_smart_contracts() -> anyhow::Result<()> {
 
     let spinner = create_spinner("Compiling all smart contracts...");
 
-    SmartContractCompiler::compile_all()?;
+    let output = SmartContractCompiler::compile_all()?;
 
     finish_spinner(spinner, "Compiling all smart contracts... ");
 
-    println!("\n{}", "✅ COMPILATION SUCCESSFUL".green().bold());
+    println!();
+    print_diagnostics(&output.diagnostics);
+
+    if output.has_errors() {
+        println!("{}", "❌ COMPILATION FAILED".red().bold());
+        print_separator();
+        return Err(anyhow::anyhow!(
+            "{} error(s), {} warning(s)",
+            output.errors(),
+            output.warnings()
+        ));
+    }
+
+    println!("{}", "✅ COMPILATION SUCCESSFUL".green().bold());
+    if output.warnings() > 0 {
+        println!("{}", format!("{} warning(s)", output.warnings()).yellow());
+    }
     print_separator();
     println!();
 
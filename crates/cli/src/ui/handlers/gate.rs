@@ -76,28 +76,27 @@ pub fn handle_gate_deploy(network_id: &str) -> anyhow::Result<()> {
         .prompt()
         .map_err(|_| anyhow::anyhow!("Contract selection cancelled"))?;
 
-    let selected_contract = if selected_display.contains("Back") {
-        "Back".to_string()
-    } else if selected_display.contains("Quit") {
-        "Quit".to_string()
-    } else {
-        selected_display
-            .split(". ")
-            .nth(1)
-            .unwrap_or("")
-            .to_string()
-    };
-
-    if selected_contract == "Back" {
+    let selected_index = selected_display
+        .split(". ")
+        .next()
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    if selected_index == back_num {
         return Err(anyhow::anyhow!("__BACK__"));
     }
     
-    if selected_contract == "Quit" {
+    if selected_index == quit_num {
         clear_screen();
         println!("{}", "👋 Goodbye!".green().bold());
         std::process::exit(0);
     }
 
+    let selected = contracts
+        .get(selected_index.wrapping_sub(1))
+        .ok_or_else(|| anyhow::anyhow!("Invalid contract selection"))?;
+    let selected_contract = selected.name.clone();
+
     let gas_strategy_str = Select::new(
         "Select gas strategy:",
         vec!["low", "standard", "fast", "instant"],
@@ -105,23 +104,15 @@ pub fn handle_gate_deploy(network_id: &str) -> anyhow::Result<()> {
     .prompt()
     .map_err(|_| anyhow::anyhow!("Gas strategy selection cancelled"))?;
 
-    let gas_strategy = match gas_strategy_str {
-        "low" => GasStrategy::Low,
-        "standard" => GasStrategy::Standard,
-        "fast" => GasStrategy::Fast,
-        "instant" => GasStrategy::Instant,
-        _ => GasStrategy::Standard,
-    };
+    let gas_strategy = gas_strategy_str.parse().unwrap_or(GasStrategy::Standard);
 
     let private_key = core::config::load_private_key()
         .map_err(|_| anyhow::anyhow!("Failed to load private key from .env"))?;
 
-    let artifact_path = format!("artifacts/{}.sol/{}.json", selected_contract, selected_contract);
-    
     print!("{}", "Loading contract artifact... ".cyan());
     std::io::Write::flush(&mut std::io::stdout())?;
     
-    let artifact = x_deploy::ArtifactLoader::load_artifact(&artifact_path)?;
+    let artifact = x_deploy::ArtifactLoader::load_artifact(&selected.path)?;
     println!("{}", "✓".green().bold());
 
     println!();
@@ -129,34 +120,44 @@ pub fn handle_gate_deploy(network_id: &str) -> anyhow::Result<()> {
     let spinner = create_spinner("Deploying contract...");
 
     let rt = tokio::runtime::Runtime::new()?;
-    let result = rt.block_on(async {
+    let (result, linked) = rt.block_on(async {
         let rpc_url = network.rpc.first()
             .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
 
         let deployer = x_deploy::ContractDeployer::new(rpc_url, &private_key, network.clone())
             .await?;
 
-        deployer.deploy(&artifact, None, gas_strategy).await
+        let linked = x_deploy::LibraryLinker::new(&deployer, x_core::project::artifacts_dir())
+            .link(&artifact, gas_strategy)
+            .await?;
+        let result = deployer.deploy(&linked.artifact, None, gas_strategy).await?;
+        Ok::<_, anyhow::Error>((result, linked))
     })?;
 
     finish_spinner(spinner, "Deploying contract... ");
 
     let deployer_address = x_signature::get_address_from_private_key(&private_key)?;
     
-    x_deploy::MetadataManager::save_deployment(
+    let version = x_deploy::MetadataManager::record_linked_deployment(
+        network_id,
         &selected_contract,
+        &selected.reference(),
+        &linked.artifact,
         &format!("{:#x}", result.contract_address),
-        network_id,
         &format!("{:#x}", result.tx_hash),
         &format!("{:#x}", deployer_address),
+        &linked.libraries,
     )?;
 
     println!("\n{}", "✅ DEPLOYMENT SUCCESSFUL".green().bold());
-    print_line("Contract", &selected_contract, |s| s.normal());
+    print_line("Contract", &format!("{}@{}", selected_contract, version), |s| s.normal());
     print_line("Network", &network.name, |s| s.cyan());
     print_line("Contract Address", &format!("{:#x}", result.contract_address), |s| s.yellow());
     print_line("Transaction Hash", &format!("{:#x}", result.tx_hash), |s| s.green());
     print_line("Gas Used", &result.gas_used.to_string(), |s| s.normal());
+    for (library, address) in &linked.libraries {
+        print_line("Library", &format!("{} at {}", library, address), |s| s.normal());
+    }
     print_line("Block Explorer", &format!("{}/tx/{:#x}", network.block_explorer.url, result.tx_hash), |s| s.blue());
     print_separator();
     println!();
@@ -13,8 +13,8 @@ pub fn handle_smart_contract_invoker(network_id: &str) -> anyhow::Result<()> {
     println!("{}", "📋 SMART CONTRACT INVOKER".cyan().bold());
     println!();
 
-    let deployments_file = format!("deployments/{}.json", network_id);
-    let artifact_dir = "artifacts";
+    let deployments_file = x_core::project::deployments_file(network_id);
+    let artifact_dir = x_core::project::artifacts_dir();
 
     print!("{}", "Loading deployed contracts... ".cyan());
     std::io::Write::flush(&mut std::io::stdout())?;
@@ -77,7 +77,13 @@ pub fn handle_smart_contract_invoker(network_id: &str) -> anyhow::Result<()> {
     println!("{}", format!("Selected: {} ({})", selected_record.contract_name, selected_record.address).cyan().bold());
     println!();
 
-    let function_type_options = vec!["1. Read (View/Pure)", "2. Write (State Changing)", "3. Stress Mode", "4. Back"];
+    let function_type_options = vec![
+        "1. Read (View/Pure)",
+        "2. Write (State Changing)",
+        "3. Call All View Functions",
+        "4. Stress Mode",
+        "5. Back",
+    ];
     let function_type_selected = Select::new("Select function type:", function_type_options)
         .prompt()
         .map_err(|_| anyhow::anyhow!("Function type selection cancelled"))?;
@@ -92,6 +98,11 @@ pub fn handle_smart_contract_invoker(network_id: &str) -> anyhow::Result<()> {
     let invoker = ContractInvoker::new(&deployments_file, artifact_dir);
     let contract_invoker = invoker.get_contract_by_address(&selected_record.contract_name, &selected_record.address, network_id)?;
 
+    if function_type_selected.contains("Call All") {
+        println!();
+        return handle_all_view_functions(&contract_invoker, selected_record, network_id);
+    }
+
     println!();
     let all_functions = contract_invoker.get_all_functions()?;
 
@@ -223,6 +234,57 @@ fn handle_read_function(
     Ok(())
 }
 
+fn handle_all_view_functions(
+    contract_invoker: &x_core::invoker::DeployedContractInvoker,
+    selected_record: &x_core::invoker::DeploymentRecord,
+    network_id: &str,
+) -> anyhow::Result<()> {
+    let networks = core::networks::load_networks()?;
+    let network = core::networks::get_network_by_id(&networks, network_id)
+        .ok_or_else(|| anyhow::anyhow!("Network not found"))?;
+
+    let private_key = core::config::load_private_key()
+        .map_err(|_| anyhow::anyhow!("Failed to load private key from .env"))?;
+
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+
+    if contract_invoker.get_view_functions()?.is_empty() {
+        anyhow::bail!("Contract has no view functions without arguments");
+    }
+
+    let spinner = create_spinner("Calling view functions...");
+
+    let rt = tokio::runtime::Runtime::new()?;
+    let results = rt.block_on(async {
+        contract_invoker.execute_view_functions(rpc_url, &private_key, network).await
+    })?;
+
+    finish_spinner(spinner, "Calling view functions... ");
+
+    println!();
+    println!("{}", "✅ VIEW FUNCTIONS".green().bold());
+    print_line("Contract", &selected_record.contract_name, |s| s.normal());
+    print_line("Address", &selected_record.address, |s| s.cyan());
+
+    for (function, result) in &results {
+        match result {
+            Ok(read) => {
+                println!("{}", function.yellow().bold());
+                for (name, val) in &read.outputs {
+                    println!("  - {}: {}", name, val.green());
+                }
+            }
+            Err(e) => println!("{} {}", function.yellow().bold(), format!("✗ {}", e).red()),
+        }
+    }
+
+    print_separator();
+    println!();
+
+    Ok(())
+}
+
 fn handle_write_function(
     contract_invoker: &x_core::invoker::DeployedContractInvoker,
     selected_record: &x_core::invoker::DeploymentRecord,
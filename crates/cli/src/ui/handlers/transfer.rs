@@ -1,5 +1,5 @@
 use colored::Colorize;
-use inquire::Text;
+use inquire::{Select, Text};
 use x_core as core;
 
 use super::utils::{print_separator, print_line};
@@ -59,3 +59,63 @@ pub fn handle_transfer_sepolia() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+pub fn handle_transfer_token() -> anyhow::Result<()> {
+    println!("{}", "🪙 TRANSFER TOKEN".cyan().bold());
+
+    let networks = core::networks::load_networks()?;
+    let names: Vec<String> = networks.iter().map(|n| n.name.clone()).collect();
+    let selected = Select::new("Choose a network:", names)
+        .with_page_size(10)
+        .prompt()
+        .map_err(|_| anyhow::anyhow!("__BACK__"))?;
+    let network = networks
+        .iter()
+        .find(|n| n.name == selected)
+        .ok_or_else(|| anyhow::anyhow!("Network not found"))?;
+
+    let token = Text::new("Enter token address or registered contract name:")
+        .prompt()
+        .map_err(|_| anyhow::anyhow!("Input cancelled"))?;
+    let token = crate::commands::erc20::resolve_token(&network.id, token.trim())?;
+
+    let to_address = Text::new("Enter recipient address:")
+        .prompt()
+        .map_err(|_| anyhow::anyhow!("Input cancelled"))?;
+    let to: ethers::types::Address = to_address.trim().parse()
+        .map_err(|_| anyhow::anyhow!("Invalid recipient address"))?;
+
+    let amount = Text::new("Enter amount in token units (e.g., 1.5):")
+        .prompt()
+        .map_err(|_| anyhow::anyhow!("Input cancelled"))?;
+
+    let key = core::config::load_private_key()
+        .map_err(|_| anyhow::anyhow!("Failed to load private key from .env"))?;
+
+    let spinner = create_spinner("Processing transfer...");
+
+    let rt = tokio::runtime::Runtime::new()?;
+    let (info, result) = rt.block_on(x_transfer::erc20::transfer_token_with_strategy_async(
+        &key,
+        network,
+        token,
+        to,
+        amount.trim(),
+        None,
+        core::gas::GasStrategy::Standard,
+    ))?;
+
+    finish_spinner(spinner, "Processing transfer... ");
+
+    println!("\n{}", "✅ TRANSFER SUCCESSFUL".green().bold());
+    print_line("Token", &format!("{:#x} ({})", token, info.symbol), |s| s.normal());
+    print_line("Amount", &format!("{} {}", amount.trim(), info.symbol), |s| s.normal());
+    print_line("To", &format!("{:#x}", to), |s| s.yellow());
+    print_line("Network", &network.name, |s| s.cyan());
+    print_line("Tx Hash", &result.tx_hash, |s| s.green());
+    print_line("Block Explorer", &format!("{}/tx/{}", network.block_explorer.url, result.tx_hash), |s| s.blue());
+    print_separator();
+    println!();
+
+    Ok(())
+}
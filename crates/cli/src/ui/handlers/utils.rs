@@ -1,5 +1,6 @@
 use colored::Colorize;
 use std::io::{self, Write};
+use x_core::compiler::{Diagnostic, Severity};
 
 pub const WIDTH: usize = 80;
 
@@ -24,3 +25,42 @@ pub fn read_input_line(prompt: &str) -> anyhow::Result<String> {
     io::stdin().read_line(&mut input)?;
     Ok(input.trim().to_string())
 }
+
+pub fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        let label = match &diagnostic.code {
+            Some(code) => format!("{}[{}]", diagnostic.severity, code),
+            None => diagnostic.severity.to_string(),
+        };
+        let label = match diagnostic.severity {
+            Severity::Error => label.red().bold(),
+            Severity::Warning => label.yellow().bold(),
+            Severity::Info => label.blue().bold(),
+        };
+        println!("{}: {}", label, diagnostic.message.bold());
+
+        if let Some(file) = &diagnostic.file {
+            let location = match (diagnostic.line, diagnostic.column) {
+                (Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+                _ => file.clone(),
+            };
+            println!("  {} {}", "-->".blue(), location);
+        }
+
+        if let (Some(snippet), Some(line)) = (&diagnostic.snippet, diagnostic.line) {
+            let gutter = line.to_string();
+            let pad = " ".repeat(gutter.len());
+            let marker = format!("{}{}", " ".repeat(snippet.start), "^".repeat(snippet.length));
+            let marker = match diagnostic.severity {
+                Severity::Error => marker.red().bold(),
+                Severity::Warning => marker.yellow().bold(),
+                Severity::Info => marker.blue().bold(),
+            };
+
+            println!("{} {}", pad, "|".blue());
+            println!("{} {} {}", gutter.blue(), "|".blue(), snippet.text);
+            println!("{} {} {}", pad, "|".blue(), marker);
+        }
+        println!();
+    }
+}
@@ -8,6 +8,7 @@ pub enum MainMenuItem {
     TheGate,
     Signatures,
     TransferEth,
+    TransferToken,
     Compile,
     GenerateWallet,
     Quit,
@@ -38,9 +39,10 @@ impl std::fmt::Display for MainMenuItem {
             MainMenuItem::TheGate => write!(f, "1. The Gate"),
             MainMenuItem::Signatures => write!(f, "2. Signatures"),
             MainMenuItem::TransferEth => write!(f, "3. Transfer ETH"),
-            MainMenuItem::Compile => write!(f, "4. Compile Smart Contracts"),
-            MainMenuItem::GenerateWallet => write!(f, "5. Generate Wallet"),
-            MainMenuItem::Quit => write!(f, "6. Quit"),
+            MainMenuItem::TransferToken => write!(f, "4. Transfer Token"),
+            MainMenuItem::Compile => write!(f, "5. Compile Smart Contracts"),
+            MainMenuItem::GenerateWallet => write!(f, "6. Generate Wallet"),
+            MainMenuItem::Quit => write!(f, "7. Quit"),
         }
     }
 }
@@ -342,14 +344,17 @@ fn gate_menu() -> anyhow::Result<()> {
 }
 
 pub fn run() -> anyhow::Result<()> {
+    // The default tx.toml profile picks the account and paths here too
+    x_core::project::activate(None, None)?;
+
     loop {
         clear_screen();
         print_banner();
 
-        let options = vec![MainMenuItem::TheGate, MainMenuItem::Signatures, MainMenuItem::TransferEth, MainMenuItem::Compile, MainMenuItem::GenerateWallet, MainMenuItem::Quit];
+        let options = vec![MainMenuItem::TheGate, MainMenuItem::Signatures, MainMenuItem::TransferEth, MainMenuItem::TransferToken, MainMenuItem::Compile, MainMenuItem::GenerateWallet, MainMenuItem::Quit];
 
         let selected = Select::new("Choose an option:", options)
-            .with_page_size(6)
+            .with_page_size(7)
             .prompt();
 
         match selected {
@@ -362,6 +367,24 @@ pub fn run() -> anyhow::Result<()> {
             Ok(MainMenuItem::TransferEth) => {
                 network_menu()?;
             }
+            Ok(MainMenuItem::TransferToken) => {
+                match handlers::handle_transfer_token() {
+                    Ok(_) => {
+                        println!();
+                        println!("Press Enter to continue...");
+                        std::io::stdin().read_line(&mut String::new())?;
+                    }
+                    Err(e) => {
+                        let err_msg = e.to_string();
+                        if err_msg != "__BACK__" {
+                            println!("{}", format!("❌ {}", e).red().bold());
+                            println!();
+                            println!("Press Enter to continue...");
+                            std::io::stdin().read_line(&mut String::new())?;
+                        }
+                    }
+                }
+            }
             Ok(MainMenuItem::Compile) => {
                 if let Err(e) = handlers::handle_compile_smart_contracts() {
                     println!("{}", format!("❌ {}", e).red().bold());
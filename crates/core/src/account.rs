@@ -0,0 +1,73 @@
+use crate::network::HttpClient;
+use crate::networks::Network;
+use anyhow::{anyhow, Result};
+use ethers::types::{Address, BlockNumber, U256};
+use tokio::task::JoinSet;
+
+/// Balance, nonces and code presence of an address on one network
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountState {
+    pub address: Address,
+    pub balance: U256,
+    pub nonce: U256,
+    /// Nonce including transactions still in the mempool
+    pub pending_nonce: U256,
+    pub is_contract: bool,
+}
+
+impl AccountState {
+    pub async fn fetch(client: &HttpClient, address: Address) -> Result<Self> {
+        let balance = client.get_balance(address).await?;
+        let nonce = client.get_transaction_count(address, BlockNumber::Latest).await?;
+        let pending_nonce = client.get_transaction_count(address, BlockNumber::Pending).await?;
+        let is_contract = !client.get_code(address).await?.is_empty();
+
+        Ok(AccountState { address, balance, nonce, pending_nonce, is_contract })
+    }
+
+    /// Transactions sent but not mined yet
+    pub fn pending_count(&self) -> U256 {
+        self.pending_nonce.saturating_sub(self.nonce)
+    }
+}
+
+/// Result of querying one address on one network
+#[derive(Debug)]
+pub struct AccountQuery {
+    pub network: Network,
+    pub address: Address,
+    pub state: Result<AccountState>,
+}
+
+/// Queries every address on every network concurrently, returning results network by network
+/// in the order given; a failing RPC only fails its own rows
+pub async fn fetch_accounts(networks: &[Network], addresses: &[Address]) -> Vec<AccountQuery> {
+    let mut tasks = JoinSet::new();
+
+    for (n, network) in networks.iter().enumerate() {
+        for (a, address) in addresses.iter().enumerate() {
+            let network = network.clone();
+            let address = *address;
+            tasks.spawn(async move {
+                let state = match network.rpc.first() {
+                    Some(rpc) => match HttpClient::new(rpc).await {
+                        Ok(client) => AccountState::fetch(&client, address).await,
+                        Err(e) => Err(e),
+                    },
+                    None => Err(anyhow!("No RPC URL available for network")),
+                };
+                ((n, a), AccountQuery { network, address, state })
+            });
+        }
+    }
+
+    let mut results = Vec::with_capacity(networks.len() * addresses.len());
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+
+    results.sort_by_key(|(order, _)| *order);
+    results.into_iter().map(|(_, query)| query).collect()
+}
@@ -0,0 +1,257 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_ARTIFACTS_DIR: &str = "artifacts";
+
+/// A contract reference, either `Name` or `path/File.sol:Name`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractRef {
+    pub source: Option<String>,
+    pub name: String,
+}
+
+impl ContractRef {
+    pub fn parse(reference: &str) -> Result<Self> {
+        let reference = reference.trim();
+        let (source, name) = match reference.rsplit_once(':') {
+            Some((source, name)) => (Some(source.to_string()), name),
+            None => (None, reference),
+        };
+
+        if name.is_empty() || source.as_deref().is_some_and(|s| !s.ends_with(".sol")) {
+            anyhow::bail!("Invalid contract reference: {} (expected Name or path/File.sol:Name)", reference);
+        }
+
+        Ok(ContractRef { source, name: name.to_string() })
+    }
+
+    /// Whether an artifact compiled from `source` matches this reference's source path
+    fn matches_source(&self, source: Option<&str>) -> bool {
+        match (&self.source, source) {
+            (None, _) => true,
+            (Some(wanted), Some(source)) => {
+                source == wanted || source.ends_with(&format!("/{}", wanted)) || wanted.ends_with(&format!("/{}", source))
+            }
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ContractRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{}:{}", source, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Contract name of a reference, `path/File.sol:Name` -> `Name`
+pub fn contract_name(reference: &str) -> &str {
+    reference.rsplit_once(':').map_or(reference, |(_, name)| name).trim()
+}
+
+/// One compiled contract found in the artifacts folder
+#[derive(Debug, Clone)]
+pub struct ArtifactEntry {
+    pub name: String,
+    /// Source file the contract was compiled from, e.g. `contracts/tokens/Token.sol`
+    pub source: Option<String>,
+    pub path: PathBuf,
+    /// Has creation bytecode, i.e. is not an interface or abstract contract
+    pub deployable: bool,
+}
+
+impl ArtifactEntry {
+    pub fn reference(&self) -> String {
+        match &self.source {
+            Some(source) => format!("{}:{}", source, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    pub(crate) fn from_file(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        let json: Value = serde_json::from_str(&content).ok()?;
+        json.get("abi")?;
+
+        let name = path.file_stem()?.to_str()?.to_string();
+        let source = source_path(&json, &name).or_else(|| {
+            path.parent()
+                .and_then(|p| p.file_name())
+                .and_then(|f| f.to_str())
+                .filter(|f| f.ends_with(".sol"))
+                .map(String::from)
+        });
+
+        let bytecode = match json.get("bytecode") {
+            Some(Value::String(code)) => code.as_str(),
+            Some(code) => code.get("object").and_then(|o| o.as_str()).unwrap_or_default(),
+            None => "",
+        };
+
+        Some(ArtifactEntry {
+            name,
+            source,
+            path: path.to_path_buf(),
+            deployable: !bytecode.trim_start_matches("0x").is_empty(),
+        })
+    }
+}
+
+impl std::fmt::Display for ArtifactEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{} ({})", self.name, source),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Source path from the compiler metadata (`compilationTarget`) or the AST
+fn source_path(json: &Value, name: &str) -> Option<String> {
+    let from_metadata = |metadata: &Value| {
+        metadata["settings"]["compilationTarget"]
+            .as_object()?
+            .iter()
+            .find(|(_, contract)| contract.as_str() == Some(name))
+            .map(|(source, _)| source.clone())
+    };
+
+    json.get("metadata")
+        .filter(|m| m.is_object())
+        .and_then(from_metadata)
+        .or_else(|| {
+            json.get("rawMetadata")
+                .and_then(|m| m.as_str())
+                .and_then(|m| serde_json::from_str::<Value>(m).ok())
+                .and_then(|m| from_metadata(&m))
+        })
+        .or_else(|| json["ast"]["absolutePath"].as_str().map(String::from))
+}
+
+/// Every contract artifact under `artifact_dir`, sorted by source path then name
+pub fn scan_artifacts<P: AsRef<Path>>(artifact_dir: P) -> Result<Vec<ArtifactEntry>> {
+    let artifact_dir = artifact_dir.as_ref();
+    if !artifact_dir.exists() {
+        return Err(anyhow!("Artifacts folder not found at {}", artifact_dir.display()));
+    }
+
+    let mut entries = Vec::new();
+    let mut pending = vec![artifact_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let hidden = path.file_name().and_then(|f| f.to_str()).is_some_and(|f| f.starts_with('.'));
+
+            if path.is_dir() {
+                // forge keeps whole-compilation dumps in build-info, they are not contract artifacts
+                if !hidden && path.file_name().and_then(|f| f.to_str()) != Some("build-info") {
+                    pending.push(path);
+                }
+            } else if !hidden && path.extension().and_then(|e| e.to_str()) == Some("json") {
+                entries.extend(ArtifactEntry::from_file(&path));
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| (&a.source, &a.name).cmp(&(&b.source, &b.name)));
+    Ok(entries)
+}
+
+/// Resolves `Name` or `path/File.sol:Name` to an artifact file
+pub fn resolve_artifact<P: AsRef<Path>>(artifact_dir: P, reference: &str) -> Result<PathBuf> {
+    let artifact_dir = artifact_dir.as_ref();
+    let contract = ContractRef::parse(reference)?;
+
+    // Common case, `contracts/Name.sol` compiled to `artifacts/Name.sol/Name.json`; a bare name
+    // always scans, another source may declare a contract of the same name
+    let file_name = contract
+        .source
+        .as_deref()
+        .and_then(|s| Path::new(s).file_name())
+        .and_then(|f| f.to_str())
+        .map(String::from)
+        .unwrap_or_else(|| format!("{}.sol", contract.name));
+    let direct = artifact_dir.join(&file_name).join(format!("{}.json", contract.name));
+    if contract.source.is_some()
+        && direct.exists()
+        && ArtifactEntry::from_file(&direct).is_some_and(|e| contract.matches_source(e.source.as_deref()))
+    {
+        return Ok(direct);
+    }
+
+    let candidates: Vec<ArtifactEntry> = scan_artifacts(artifact_dir)?
+        .into_iter()
+        .filter(|e| e.name == contract.name && contract.matches_source(e.source.as_deref()))
+        .collect();
+
+    match candidates.len() {
+        0 => Err(anyhow!("No artifact found for {} in {}", contract, artifact_dir.display())),
+        1 => Ok(candidates[0].path.clone()),
+        _ => Err(anyhow!(
+            "{} is ambiguous, use one of: {}",
+            contract,
+            candidates.iter().map(|c| c.reference()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_artifact(dir: &Path, file: &str, name: &str, source: &str, bytecode: &str) {
+        let path = dir.join(file);
+        fs::create_dir_all(&path).unwrap();
+        let artifact = serde_json::json!({
+            "abi": [],
+            "bytecode": { "object": bytecode },
+            "metadata": { "settings": { "compilationTarget": { source: name } } }
+        });
+        fs::write(path.join(format!("{}.json", name)), artifact.to_string()).unwrap();
+    }
+
+    #[test]
+    fn test_parse_reference() {
+        let simple = ContractRef::parse("Token").unwrap();
+        assert_eq!(simple, ContractRef { source: None, name: "Token".to_string() });
+
+        let full = ContractRef::parse("contracts/tokens/Token.sol:Token").unwrap();
+        assert_eq!(full.source.as_deref(), Some("contracts/tokens/Token.sol"));
+        assert_eq!(full.to_string(), "contracts/tokens/Token.sol:Token");
+
+        assert!(ContractRef::parse("contracts/Token:Token").is_err());
+    }
+
+    #[test]
+    fn test_scan_and_resolve_nested_and_multi_contract_files() {
+        let dir = std::env::temp_dir().join(format!("tx-artifacts-{}", std::process::id()));
+        write_artifact(&dir, "Tokens.sol", "TokenA", "contracts/tokens/Tokens.sol", "0x6080");
+        write_artifact(&dir, "Tokens.sol", "IToken", "contracts/tokens/Tokens.sol", "0x");
+        write_artifact(&dir, "Vault.sol", "Vault", "contracts/Vault.sol", "0x6080");
+        write_artifact(&dir, "other/Vault.sol", "Vault", "contracts/legacy/Vault.sol", "0x6080");
+
+        let entries = scan_artifacts(&dir).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert!(!entries.iter().find(|e| e.name == "IToken").unwrap().deployable);
+
+        let token = resolve_artifact(&dir, "TokenA").unwrap();
+        assert!(token.ends_with("Tokens.sol/TokenA.json"));
+
+        write_artifact(&dir, "a/Lib.sol", "Helper", "contracts/a/Lib.sol", "0x6080");
+        write_artifact(&dir, "b/Lib.sol", "Helper", "contracts/b/Lib.sol", "0x6080");
+        assert!(resolve_artifact(&dir, "Helper").is_err());
+        assert!(resolve_artifact(&dir, "contracts/b/Lib.sol:Helper").unwrap().ends_with("b/Lib.sol/Helper.json"));
+
+        let legacy = resolve_artifact(&dir, "contracts/legacy/Vault.sol:Vault").unwrap();
+        assert!(legacy.ends_with("other/Vault.sol/Vault.json"));
+        let current = resolve_artifact(&dir, "contracts/Vault.sol:Vault").unwrap();
+        assert!(current.ends_with("Vault.sol/Vault.json") && !current.to_string_lossy().contains("other"));
+        assert!(resolve_artifact(&dir, "Vault").is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
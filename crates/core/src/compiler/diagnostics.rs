@@ -0,0 +1,292 @@
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// Source line a diagnostic points at, with the highlighted range
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    pub text: String,
+    /// 0-based byte offset and length of the highlighted range within `text`
+    pub start: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<String>,
+    pub message: String,
+    pub file: Option<String>,
+    /// 1-based line and column of the start of the reported range
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub snippet: Option<Snippet>,
+    /// Compiler's own rendering, including the source excerpt
+    pub formatted: Option<String>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}", file)?;
+            if let (Some(line), Some(column)) = (self.line, self.column) {
+                write!(f, ":{}:{}", line, column)?;
+            }
+            write!(f, ": ")?;
+        }
+
+        write!(f, "{}", self.severity)?;
+        if let Some(code) = &self.code {
+            write!(f, "[{}]", code)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CompileOutput {
+    /// Root sources that were recompiled, empty when everything was up to date
+    pub compiled_sources: Vec<String>,
+    pub artifacts: Vec<PathBuf>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CompileOutput {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error).count()
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning).count()
+    }
+
+    pub fn is_up_to_date(&self) -> bool {
+        self.compiled_sources.is_empty() && self.diagnostics.is_empty()
+    }
+}
+
+/// Diagnostics from the `errors` array of Standard JSON output (solc, `forge build --json`)
+pub fn parse_solc_errors(output: &Value, read_source: impl Fn(&str) -> Option<String>) -> Vec<Diagnostic> {
+    let Some(errors) = output.get("errors").and_then(|e| e.as_array()) else {
+        return Vec::new();
+    };
+
+    errors
+        .iter()
+        .map(|error| {
+            let severity = match error["severity"].as_str() {
+                Some("error") => Severity::Error,
+                Some("warning") => Severity::Warning,
+                _ => Severity::Info,
+            };
+
+            let location = error.get("sourceLocation");
+            let file = location.and_then(|l| l["file"].as_str()).map(String::from);
+            let start = location.and_then(|l| l["start"].as_i64()).filter(|s| *s >= 0);
+            let end = location.and_then(|l| l["end"].as_i64()).filter(|e| *e >= 0);
+
+            let mut diagnostic = Diagnostic {
+                severity,
+                code: error["errorCode"].as_str().map(String::from),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+                file,
+                line: None,
+                column: None,
+                snippet: None,
+                formatted: error["formattedMessage"].as_str().map(String::from),
+            };
+
+            if let (Some(file), Some(start)) = (&diagnostic.file, start)
+                && let Some(content) = read_source(file)
+            {
+                let (line, column) = line_column(&content, start as usize);
+                let length = end.map_or(1, |end| (end - start).max(1) as usize);
+                diagnostic.line = Some(line);
+                diagnostic.column = Some(column);
+                diagnostic.snippet = snippet(&content, line, column, length);
+            }
+
+            diagnostic
+        })
+        .collect()
+}
+
+/// Diagnostics from human readable compiler output, as printed by `forge build` and `solc`
+///
+/// ```text
+/// Error (7576): Undeclared identifier.
+///   --> contracts/HelloWorld.sol:10:9:
+///    |
+/// 10 |         foo();
+///    |         ^^^
+/// ```
+pub fn parse_compiler_text(output: &str, read_source: impl Fn(&str) -> Option<String>) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        if let Some(diagnostic) = parse_header(trimmed) {
+            diagnostics.push(diagnostic);
+        } else if let Some(location) = trimmed.strip_prefix("-->") {
+            let Some(diagnostic) = diagnostics.last_mut() else { continue };
+            let mut parts = location.trim().trim_end_matches(':').rsplitn(3, ':');
+            let column = parts.next().and_then(|c| c.parse().ok());
+            let line = parts.next().and_then(|l| l.parse().ok());
+            let file = parts.next();
+
+            if let (Some(file), Some(line), Some(column)) = (file, line, column) {
+                diagnostic.file = Some(file.to_string());
+                diagnostic.line = Some(line);
+                diagnostic.column = Some(column);
+                diagnostic.snippet = read_source(file).and_then(|content| snippet(&content, line, column, 1));
+            }
+        } else if let Some(carets) = trimmed.strip_prefix('|').map(str::trim)
+            && !carets.is_empty()
+            && carets.chars().all(|c| c == '^')
+            && let Some(snippet) = diagnostics.last_mut().and_then(|d| d.snippet.as_mut())
+        {
+            snippet.length = carets.len();
+        }
+    }
+
+    diagnostics
+}
+
+fn parse_header(line: &str) -> Option<Diagnostic> {
+    let (head, message) = line.split_once(": ")?;
+    let (kind, code) = match head.split_once(" (") {
+        Some((kind, code)) => (kind, Some(code.strip_suffix(')')?.to_string())),
+        None => (head, None),
+    };
+
+    let severity = match kind {
+        "Error" => Severity::Error,
+        "Warning" => Severity::Warning,
+        "Info" => Severity::Info,
+        _ if kind.ends_with("Error") => Severity::Error,
+        _ => return None,
+    };
+
+    // forge's summary line rather than a diagnostic
+    if message.starts_with("Compiler run failed") {
+        return None;
+    }
+
+    Some(Diagnostic {
+        severity,
+        code,
+        message: message.to_string(),
+        file: None,
+        line: None,
+        column: None,
+        snippet: None,
+        formatted: None,
+    })
+}
+
+fn snippet(content: &str, line: usize, column: usize, length: usize) -> Option<Snippet> {
+    let text = content.lines().nth(line.checked_sub(1)?)?.to_string();
+    let start = column.saturating_sub(1).min(text.len());
+    let length = length.min(text.len() - start).max(1);
+    Some(Snippet { text, start, length })
+}
+
+/// 1-based line and column of a byte offset
+pub fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content.as_bytes()[..offset.min(content.len())];
+    let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+    let column = before.iter().rev().take_while(|b| **b != b'\n').count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_solc_errors_with_location() {
+        let output = json!({
+            "errors": [
+                {
+                    "severity": "error",
+                    "errorCode": "7576",
+                    "type": "DeclarationError",
+                    "message": "Undeclared identifier.",
+                    "formattedMessage": "DeclarationError: Undeclared identifier.",
+                    "sourceLocation": { "file": "contracts/A.sol", "start": 35, "end": 38 }
+                },
+                { "severity": "warning", "message": "SPDX license identifier not provided." }
+            ]
+        });
+
+        let source = "pragma solidity ^0.8;\ncontract A { foo(); }\n";
+        let diagnostics = parse_solc_errors(&output, |_| Some(source.to_string()));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (Some(2), Some(14)));
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "contracts/A.sol:2:14: error[7576]: Undeclared identifier."
+        );
+        assert_eq!(
+            diagnostics[0].snippet,
+            Some(Snippet { text: "contract A { foo(); }".to_string(), start: 13, length: 3 })
+        );
+        assert_eq!(diagnostics[1].to_string(), "warning: SPDX license identifier not provided.");
+    }
+
+    #[test]
+    fn test_parse_compiler_text() {
+        let output = "\
+Error: Compiler run failed:
+Error (7576): Undeclared identifier.
+  --> contracts/A.sol:2:14:
+   |
+ 2 | contract A { foo(); }
+   |              ^^^
+
+Warning (2072): Unused local variable.
+";
+
+        let source = "pragma solidity ^0.8;\ncontract A { foo(); }\n";
+        let diagnostics = parse_compiler_text(output, |_| Some(source.to_string()));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("7576"));
+        assert_eq!(diagnostics[0].file.as_deref(), Some("contracts/A.sol"));
+        assert_eq!(diagnostics[0].snippet.as_ref().map(|s| (s.start, s.length)), Some((13, 3)));
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert!(diagnostics[1].file.is_none());
+    }
+
+    #[test]
+    fn test_line_column() {
+        let content = "line one\nline two\n";
+        assert_eq!(line_column(content, 0), (1, 1));
+        assert_eq!(line_column(content, 9), (2, 1));
+        assert_eq!(line_column(content, 14), (2, 6));
+    }
+}
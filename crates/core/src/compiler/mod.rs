@@ -0,0 +1,86 @@
+pub mod diagnostics;
+pub mod solc;
+pub mod watch;
+
+pub use diagnostics::{CompileOutput, Diagnostic, Severity, Snippet};
+pub use solc::{SolcCompiler, SolcConfig};
+pub use watch::SourceWatcher;
+
+use anyhow::Result;
+use diagnostics::{parse_compiler_text, parse_solc_errors};
+use serde_json::Value;
+use std::fs;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompilerBackend {
+    /// `forge build`
+    Forge,
+    /// Local `solc` through Standard JSON
+    Solc,
+}
+
+impl CompilerBackend {
+    pub fn parse(backend: &str) -> Result<Self> {
+        match backend.to_lowercase().as_str() {
+            "forge" => Ok(CompilerBackend::Forge),
+            "solc" => Ok(CompilerBackend::Solc),
+            _ => Err(anyhow::anyhow!("Invalid compiler backend: {} (expected forge or solc)", backend)),
+        }
+    }
+}
+
+pub struct SmartContractCompiler;
+
+impl SmartContractCompiler {
+    pub fn compile_all() -> Result<CompileOutput> {
+        Self::forge_build(&[])
+    }
+
+    pub fn compile_contract(contract_name: &str) -> Result<CompileOutput> {
+        Self::forge_build(&["--skip".to_string(), format!("!{}", contract_name)])
+    }
+
+    /// Runs `forge build --json` and collects its diagnostics, falling back to the text output
+    fn forge_build(args: &[String]) -> Result<CompileOutput> {
+        let mut command = Command::new("forge");
+        command.arg("build").arg("--json").arg("--color").arg("never").args(args);
+
+        // Paths set in tx.toml win over foundry.toml
+        if let Some(settings) = crate::project::active_profile().map(|p| &p.settings) {
+            if let Some(contracts) = &settings.contracts {
+                command.arg("--contracts").arg(contracts);
+            }
+            if let Some(artifacts) = &settings.artifacts {
+                command.arg("--out").arg(artifacts);
+            }
+        }
+
+        let output = command
+            .output()
+            .map_err(|e| anyhow::anyhow!("Failed to run forge: {}", e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let read_source = |file: &str| fs::read_to_string(file).ok();
+
+        let mut result = CompileOutput::default();
+        match serde_json::from_str::<Value>(stdout.trim()) {
+            Ok(json) => {
+                result.diagnostics = parse_solc_errors(&json, read_source);
+                if let Some(sources) = json.get("sources").and_then(|s| s.as_object()) {
+                    result.compiled_sources = sources.keys().cloned().collect();
+                }
+            }
+            Err(_) => {
+                result.diagnostics = parse_compiler_text(&format!("{}\n{}", stdout, stderr), read_source);
+            }
+        }
+
+        if !output.status.success() && !result.has_errors() {
+            return Err(anyhow::anyhow!("Compilation failed:\n{}{}", stdout, stderr));
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,484 @@
+use super::diagnostics::{parse_solc_errors, CompileOutput};
+use crate::artifacts::ArtifactEntry;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Source hashes of the last successful build, kept next to the artifacts
+const CACHE_FILE: &str = ".solc-cache.json";
+
+/// Project layout and optimizer settings, read from `foundry.toml` and `remappings.txt`
+#[derive(Debug, Clone)]
+pub struct SolcConfig {
+    pub src: String,
+    pub out: String,
+    pub libs: Vec<String>,
+    pub optimizer: bool,
+    pub optimizer_runs: u64,
+    pub remappings: Vec<String>,
+}
+
+impl Default for SolcConfig {
+    fn default() -> Self {
+        SolcConfig {
+            src: "contracts".to_string(),
+            out: "artifacts".to_string(),
+            libs: vec!["lib".to_string()],
+            optimizer: true,
+            optimizer_runs: 200,
+            remappings: Vec::new(),
+        }
+    }
+}
+
+impl SolcConfig {
+    /// Layout from foundry.toml, with the tx.toml paths taking precedence
+    pub fn load(root: &Path) -> Result<Self> {
+        let mut config = SolcConfig::default();
+
+        let foundry_path = root.join("foundry.toml");
+        if foundry_path.exists() {
+            let content = fs::read_to_string(&foundry_path)?;
+            let value: toml::Value = toml::from_str(&content)
+                .map_err(|e| anyhow!("Failed to parse {}: {}", foundry_path.display(), e))?;
+
+            if let Some(profile) = value.get("profile").and_then(|p| p.get("default")) {
+                if let Some(src) = profile.get("src").and_then(|v| v.as_str()) {
+                    config.src = src.to_string();
+                }
+                if let Some(out) = profile.get("out").and_then(|v| v.as_str()) {
+                    config.out = out.to_string();
+                }
+                if let Some(libs) = profile.get("libs").and_then(|v| v.as_array()) {
+                    config.libs = libs.iter().filter_map(|l| l.as_str().map(String::from)).collect();
+                }
+                if let Some(optimizer) = profile.get("optimizer").and_then(|v| v.as_bool()) {
+                    config.optimizer = optimizer;
+                }
+                if let Some(runs) = profile.get("optimizer_runs").and_then(|v| v.as_integer()) {
+                    config.optimizer_runs = runs as u64;
+                }
+            }
+        }
+
+        if let Some(settings) = crate::project::active_profile().map(|p| &p.settings) {
+            if let Some(contracts) = &settings.contracts {
+                config.src = contracts.clone();
+            }
+            if let Some(artifacts) = &settings.artifacts {
+                config.out = artifacts.clone();
+            }
+        }
+
+        let remappings_path = root.join("remappings.txt");
+        if remappings_path.exists() {
+            config.remappings = fs::read_to_string(&remappings_path)?
+                .lines()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(String::from)
+                .collect();
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BuildCache {
+    solc_version: String,
+    settings_hash: String,
+    /// Root source -> hash of every file it was compiled from (itself and its imports)
+    sources: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Compiles contracts with a local `solc` binary through the Standard JSON interface
+pub struct SolcCompiler {
+    solc: String,
+    root: PathBuf,
+    config: SolcConfig,
+}
+
+impl SolcCompiler {
+    /// Uses the `SOLC` environment variable as the compiler binary, falling back to `solc` on PATH
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let config = SolcConfig::load(&root)?;
+        let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_string());
+
+        Ok(SolcCompiler { solc, root, config })
+    }
+
+    pub fn with_solc(mut self, solc: &str) -> Self {
+        self.solc = solc.to_string();
+        self
+    }
+
+    pub fn config(&self) -> &SolcConfig {
+        &self.config
+    }
+
+    pub fn version(&self) -> Result<String> {
+        let output = Command::new(&self.solc)
+            .arg("--version")
+            .output()
+            .map_err(|e| anyhow!("Failed to run {}: {} (install solc or set SOLC)", self.solc, e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .lines()
+            .find_map(|l| l.strip_prefix("Version: "))
+            .map(|v| v.trim().to_string())
+            .ok_or_else(|| anyhow!("Unexpected output from {} --version", self.solc))
+    }
+
+    pub fn compile_all(&self) -> Result<CompileOutput> {
+        self.compile(None)
+    }
+
+    /// Compiles the source files named `<contract_name>.sol`, wherever they are under `src`
+    pub fn compile_contract(&self, contract_name: &str) -> Result<CompileOutput> {
+        self.compile(Some(contract_name))
+    }
+
+    fn compile(&self, contract_name: Option<&str>) -> Result<CompileOutput> {
+        let mut roots = find_sources(&self.root, &self.config.src)?;
+        if let Some(name) = contract_name {
+            roots.retain(|r| Path::new(r).file_stem().and_then(|s| s.to_str()) == Some(name));
+            if roots.is_empty() {
+                anyhow::bail!("No source file {}.sol found in {}", name, self.config.src);
+            }
+        }
+
+        let version = self.version()?;
+        let settings = self.settings();
+        let settings_hash = hash_bytes(format!("{}{}", version, settings).as_bytes());
+
+        let cache_path = self.root.join(&self.config.out).join(CACHE_FILE);
+        let mut cache: BuildCache = fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        if cache.solc_version != version || cache.settings_hash != settings_hash {
+            cache = BuildCache {
+                solc_version: version,
+                settings_hash,
+                sources: BTreeMap::new(),
+            };
+        }
+
+        let dirty: Vec<String> = roots
+            .into_iter()
+            .filter(|r| cache.sources.get(r).is_none_or(|files| self.is_stale(files)))
+            .collect();
+
+        if dirty.is_empty() {
+            return Ok(CompileOutput::default());
+        }
+
+        let mut sources = serde_json::Map::new();
+        for source in &dirty {
+            let content = fs::read_to_string(self.root.join(source))?;
+            sources.insert(source.clone(), json!({ "content": content }));
+        }
+
+        let input = json!({
+            "language": "Solidity",
+            "sources": sources,
+            "settings": settings,
+        });
+
+        let output = self.run(&input)?;
+        let diagnostics = parse_solc_errors(&output, |file| self.read_source(file));
+
+        let mut result = CompileOutput {
+            compiled_sources: dirty.clone(),
+            artifacts: Vec::new(),
+            diagnostics,
+        };
+        if result.has_errors() {
+            return Ok(result);
+        }
+
+        let out_dir = self.root.join(&self.config.out);
+        if let Some(files) = output.get("contracts").and_then(|c| c.as_object()) {
+            for (file, contracts) in files {
+                let dir = artifact_dir(&out_dir, file);
+                for (name, contract) in contracts.as_object().into_iter().flatten() {
+                    fs::create_dir_all(&dir)?;
+                    let path = dir.join(format!("{}.json", name));
+                    fs::write(&path, serde_json::to_string_pretty(&to_artifact(contract))?)?;
+                    result.artifacts.push(path);
+                }
+            }
+        }
+
+        let imports = import_graph(&output);
+        for source in &dirty {
+            let files = import_closure(source, &imports)
+                .into_iter()
+                .filter_map(|file| self.read_source(&file).map(|content| (file, hash_bytes(content.as_bytes()))))
+                .collect();
+            cache.sources.insert(source.clone(), files);
+        }
+
+        fs::create_dir_all(&out_dir)?;
+        fs::write(&cache_path, serde_json::to_string_pretty(&cache)?)?;
+
+        Ok(result)
+    }
+
+    fn settings(&self) -> Value {
+        json!({
+            "remappings": self.config.remappings,
+            "optimizer": {
+                "enabled": self.config.optimizer,
+                "runs": self.config.optimizer_runs,
+            },
+            "outputSelection": {
+                "*": {
+                    "": ["ast"],
+                    "*": ["abi", "evm.bytecode", "evm.deployedBytecode", "storageLayout", "metadata"],
+                },
+            },
+        })
+    }
+
+    fn run(&self, input: &Value) -> Result<Value> {
+        let mut command = Command::new(&self.solc);
+        command
+            .current_dir(&self.root)
+            .arg("--standard-json")
+            .arg("--base-path")
+            .arg(".")
+            .arg("--allow-paths")
+            .arg(".");
+        for lib in &self.config.libs {
+            if self.root.join(lib).exists() {
+                command.arg("--include-path").arg(lib);
+            }
+        }
+
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("Failed to run {}: {} (install solc or set SOLC)", self.solc, e))?;
+
+        child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("Failed to open solc stdin"))?
+            .write_all(input.to_string().as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if output.stdout.is_empty() {
+            return Err(anyhow!(
+                "solc produced no output:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .map_err(|e| anyhow!("Failed to parse solc output: {}", e))
+    }
+
+    /// Reads a source by the path solc reports, trying the project root then each library dir
+    fn read_source(&self, file: &str) -> Option<String> {
+        std::iter::once(self.root.join(file))
+            .chain(self.config.libs.iter().map(|lib| self.root.join(lib).join(file)))
+            .find_map(|path| fs::read_to_string(path).ok())
+    }
+
+    fn is_stale(&self, files: &BTreeMap<String, String>) -> bool {
+        files.iter().any(|(file, hash)| {
+            self.read_source(file)
+                .is_none_or(|content| hash_bytes(content.as_bytes()) != *hash)
+        })
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    hex::encode(Keccak256::digest(bytes))
+}
+
+/// `.sol` files under `src`, as `/`-separated paths relative to the project root
+fn find_sources(root: &Path, src: &str) -> Result<Vec<String>> {
+    let src_dir = root.join(src);
+    if !src_dir.exists() {
+        anyhow::bail!("Contracts folder not found at {}", src_dir.display());
+    }
+
+    let mut sources = Vec::new();
+    let mut pending = vec![src_dir];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|e| e.to_str()) == Some("sol") {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                sources.push(
+                    relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                );
+            }
+        }
+    }
+
+    sources.sort();
+    Ok(sources)
+}
+
+/// Artifact folder of `file`: `out/File.sol` like forge, or `out/<path>/File.sol` when that folder
+/// already holds artifacts of another source with the same file name
+fn artifact_dir(out_dir: &Path, file: &str) -> PathBuf {
+    let file_name = Path::new(file).file_name().and_then(|f| f.to_str()).unwrap_or(file);
+    let flat = out_dir.join(file_name);
+
+    let taken = fs::read_dir(&flat)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| ArtifactEntry::from_file(&entry.path()))
+        .any(|entry| entry.source.is_some_and(|source| source != file));
+
+    if taken { out_dir.join(file) } else { flat }
+}
+
+/// Converts one contract of the Standard JSON output to the artifact layout forge writes
+fn to_artifact(contract: &Value) -> Value {
+    let bytecode = |key: &str| {
+        let code = &contract["evm"][key];
+        let object = code["object"].as_str().unwrap_or_default();
+        let mut artifact = json!({
+            "object": format!("0x{}", object),
+            "sourceMap": code["sourceMap"],
+            "linkReferences": code.get("linkReferences").cloned().unwrap_or_else(|| json!({})),
+        });
+        if let Some(immutables) = code.get("immutableReferences") {
+            artifact["immutableReferences"] = immutables.clone();
+        }
+        artifact
+    };
+
+    let mut artifact = json!({
+        "abi": contract.get("abi").cloned().unwrap_or_else(|| json!([])),
+        "bytecode": bytecode("bytecode"),
+        "deployedBytecode": bytecode("deployedBytecode"),
+    });
+    if let Some(layout) = contract.get("storageLayout") {
+        artifact["storageLayout"] = layout.clone();
+    }
+    if let Some(metadata) = contract.get("metadata").and_then(|m| m.as_str()) {
+        artifact["rawMetadata"] = json!(metadata);
+    }
+    artifact
+}
+
+/// Direct imports of every compiled source, taken from the AST output
+fn import_graph(output: &Value) -> BTreeMap<String, Vec<String>> {
+    let mut graph = BTreeMap::new();
+
+    for (file, source) in output.get("sources").and_then(|s| s.as_object()).into_iter().flatten() {
+        let imports = source["ast"]["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|node| node["nodeType"] == "ImportDirective")
+            .filter_map(|node| node["absolutePath"].as_str().map(String::from))
+            .collect();
+        graph.insert(file.clone(), imports);
+    }
+
+    graph
+}
+
+fn import_closure(root: &str, graph: &BTreeMap<String, Vec<String>>) -> BTreeSet<String> {
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![root.to_string()];
+
+    while let Some(file) = pending.pop() {
+        if seen.insert(file.clone()) {
+            pending.extend(graph.get(&file).into_iter().flatten().cloned());
+        }
+    }
+
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_matches_forge_layout() {
+        let contract = json!({
+            "abi": [{ "type": "constructor", "inputs": [] }],
+            "evm": {
+                "bytecode": { "object": "6080", "sourceMap": "1:2:0", "linkReferences": {} },
+                "deployedBytecode": {
+                    "object": "6081",
+                    "sourceMap": "",
+                    "linkReferences": {},
+                    "immutableReferences": { "3": [{ "start": 1, "length": 32 }] }
+                }
+            },
+            "storageLayout": { "storage": [], "types": null },
+            "metadata": "{}"
+        });
+
+        let artifact = to_artifact(&contract);
+        assert_eq!(artifact["bytecode"]["object"], "0x6080");
+        assert_eq!(artifact["deployedBytecode"]["object"], "0x6081");
+        assert!(artifact["deployedBytecode"]["immutableReferences"].is_object());
+        assert!(artifact["storageLayout"]["storage"].is_array());
+    }
+
+    #[test]
+    fn test_same_file_names_get_separate_artifacts() {
+        let out_dir = std::env::temp_dir().join(format!("tx-solc-out-{}", std::process::id()));
+        let write = |file: &str| {
+            let metadata = json!({ "settings": { "compilationTarget": { file: "Token" } } }).to_string();
+            let dir = artifact_dir(&out_dir, file);
+            fs::create_dir_all(&dir).unwrap();
+            let artifact = to_artifact(&json!({ "abi": [], "evm": {}, "metadata": metadata }));
+            fs::write(dir.join("Token.json"), artifact.to_string()).unwrap();
+            dir
+        };
+
+        assert_eq!(write("contracts/a/Token.sol"), out_dir.join("Token.sol"));
+        assert_eq!(write("contracts/b/Token.sol"), out_dir.join("contracts/b/Token.sol"));
+        assert_eq!(write("contracts/a/Token.sol"), out_dir.join("Token.sol"));
+
+        let b = crate::artifacts::resolve_artifact(&out_dir, "contracts/b/Token.sol:Token").unwrap();
+        assert!(b.starts_with(out_dir.join("contracts/b")));
+        assert!(crate::artifacts::resolve_artifact(&out_dir, "Token").is_err());
+        fs::remove_dir_all(&out_dir).ok();
+    }
+
+    #[test]
+    fn test_import_closure_follows_transitive_imports() {
+        let output = json!({
+            "sources": {
+                "contracts/A.sol": { "ast": { "nodes": [{ "nodeType": "ImportDirective", "absolutePath": "contracts/B.sol" }] } },
+                "contracts/B.sol": { "ast": { "nodes": [{ "nodeType": "ImportDirective", "absolutePath": "lib/C.sol" }] } },
+                "lib/C.sol": { "ast": { "nodes": [{ "nodeType": "ContractDefinition" }] } }
+            }
+        });
+
+        let closure = import_closure("contracts/A.sol", &import_graph(&output));
+        assert_eq!(closure.len(), 3);
+        assert!(closure.contains("lib/C.sol"));
+    }
+}
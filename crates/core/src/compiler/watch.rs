@@ -0,0 +1,81 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Polls a source folder for added, removed or modified `.sol` files
+pub struct SourceWatcher {
+    dir: PathBuf,
+    snapshot: BTreeMap<PathBuf, (SystemTime, u64)>,
+}
+
+impl SourceWatcher {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let snapshot = snapshot(&dir)?;
+        Ok(SourceWatcher { dir, snapshot })
+    }
+
+    /// Files that changed since the previous call
+    pub fn changed(&mut self) -> Result<Vec<PathBuf>> {
+        let current = snapshot(&self.dir)?;
+
+        let mut changed: Vec<PathBuf> = current
+            .iter()
+            .filter(|(path, stamp)| self.snapshot.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(self.snapshot.keys().filter(|p| !current.contains_key(*p)).cloned());
+
+        self.snapshot = current;
+        Ok(changed)
+    }
+}
+
+fn snapshot(dir: &Path) -> Result<BTreeMap<PathBuf, (SystemTime, u64)>> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|e| e.to_str()) == Some("sol") {
+                let metadata = entry.metadata()?;
+                files.insert(path, (metadata.modified()?, metadata.len()));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_added_modified_and_removed_sources() {
+        let dir = std::env::temp_dir().join(format!("tx-watch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("A.sol"), "contract A {}").unwrap();
+
+        let mut watcher = SourceWatcher::new(&dir).unwrap();
+        assert!(watcher.changed().unwrap().is_empty());
+
+        fs::write(dir.join("nested/B.sol"), "contract B {}").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        assert_eq!(watcher.changed().unwrap(), vec![dir.join("nested/B.sol")]);
+
+        fs::write(dir.join("A.sol"), "contract A { uint x; }").unwrap();
+        assert_eq!(watcher.changed().unwrap(), vec![dir.join("A.sol")]);
+
+        fs::remove_file(dir.join("nested/B.sol")).unwrap();
+        assert_eq!(watcher.changed().unwrap(), vec![dir.join("nested/B.sol")]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -3,11 +3,16 @@ use anyhow::Result;
 /// Loads the Ethereum private key from Docker secrets or .env file
 ///
 /// Priority order:
+/// 0. Account selected by the tx.toml profile or `--account`
 /// 1. Docker secret file (ETH_PRIVATE_KEY_FILE)
 /// 2. Environment variable (ETH_PRIVATE_KEY)
 /// 3. Fallback environment variable (PRIVATE_KEY)
 /// The private key can be with or without `0x` prefix.
 pub fn load_private_key() -> Result<String> {
+    if let Some((name, account)) = crate::project::active_account() {
+        return account.private_key().map_err(|e| anyhow::anyhow!("Account '{}': {}", name, e));
+    }
+
     // Try Docker secret first
     if let Ok(secret_path) = std::env::var("ETH_PRIVATE_KEY_FILE") {
         let key = std::fs::read_to_string(&secret_path)
@@ -10,6 +10,20 @@ pub enum GasStrategy {
     Instant,
 }
 
+impl std::str::FromStr for GasStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "low" => Ok(GasStrategy::Low),
+            "standard" => Ok(GasStrategy::Standard),
+            "fast" => Ok(GasStrategy::Fast),
+            "instant" => Ok(GasStrategy::Instant),
+            _ => Err(anyhow::anyhow!("Invalid gas strategy: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GasEstimate {
     pub gas_price: U256,
@@ -111,6 +125,46 @@ impl GasCalculator {
             invoker,
         })
     }
+
+    /// Fee fields for `strategy` without a gas limit, for transactions that are built now and sent later
+    pub async fn suggest_fees<M: Middleware>(client: &M, strategy: GasStrategy, legacy: bool) -> Result<GasEstimate> {
+        if legacy {
+            let gas_price = client
+                .get_gas_price()
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch gas price: {}", e))?;
+
+            return Ok(GasEstimate {
+                gas_price: apply_strategy_multiplier(gas_price, strategy),
+                gas_limit: U256::zero(),
+                max_priority_fee: None,
+                max_fee_per_gas: None,
+                invoker: None,
+            });
+        }
+
+        let fee_history = client
+            .fee_history(10u64, BlockNumber::Latest, &[50.0])
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch fee history: {}", e))?;
+
+        let base_fee = fee_history
+            .base_fee_per_gas
+            .last()
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("No base fee available"))?;
+
+        let priority_fee = calculate_priority_fee(&fee_history, strategy);
+
+        Ok(GasEstimate {
+            gas_price: U256::zero(),
+            gas_limit: U256::zero(),
+            max_priority_fee: Some(priority_fee),
+            // Leaves room for the base fee to double before the transaction is sent
+            max_fee_per_gas: Some(base_fee * 2 + priority_fee),
+            invoker: None,
+        })
+    }
 }
 
 fn calculate_priority_fee(fee_history: &FeeHistory, strategy: GasStrategy) -> U256 {
@@ -1,5 +1,5 @@
 use alloy_dyn_abi::{DynSolValue, JsonAbiExt, FunctionExt};
-use alloy_json_abi::Function;
+use alloy_json_abi::{Constructor, Function};
 use alloy_primitives::Bytes;
 use anyhow::{anyhow, Result};
 use serde_json::Value;
@@ -57,6 +57,23 @@ impl DynAbiFunction {
 
 
 
+    /// Decodes call arguments, with or without the leading selector
+    pub fn decode_input(&self, data: &[u8]) -> Result<Vec<DynSolValue>> {
+        let args = data.strip_prefix(self.function.selector().as_slice()).unwrap_or(data);
+        self.function
+            .abi_decode_input(args, false)
+            .map_err(|e| anyhow!("Failed to decode input: {:?}", e))
+    }
+
+    pub fn selector(&self) -> [u8; 4] {
+        self.function.selector().0
+    }
+
+    /// Canonical signature, e.g. `transfer(address,uint256)`
+    pub fn signature(&self) -> String {
+        self.function.signature()
+    }
+
     pub fn decode_output(&self, data: &[u8]) -> Result<Vec<DynSolValue>> {
         self.function
             .abi_decode_output(data, false)
@@ -90,6 +107,57 @@ impl DynAbiFunction {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct DynAbiConstructor {
+    constructor: Option<Constructor>,
+}
+
+impl DynAbiConstructor {
+    pub fn from_json_abi(abi: &str) -> Result<Self> {
+        let abi_json: Vec<Value> = serde_json::from_str(abi)
+            .map_err(|e| anyhow!("Failed to parse ABI JSON: {}", e))?;
+
+        for item in abi_json {
+            if item.get("type").and_then(|t| t.as_str()) == Some("constructor") {
+                let constructor: Constructor = serde_json::from_value(item)
+                    .map_err(|e| anyhow!("Failed to parse constructor: {}", e))?;
+
+                return Ok(DynAbiConstructor { constructor: Some(constructor) });
+            }
+        }
+
+        Ok(DynAbiConstructor { constructor: None })
+    }
+
+    /// Encodes constructor arguments to append to the creation bytecode
+    ///
+    /// Contracts without an explicit constructor accept no arguments.
+    pub fn encode_input(&self, args: &[DynSolValue]) -> Result<Vec<u8>> {
+        match &self.constructor {
+            Some(constructor) => constructor
+                .abi_encode_input(args)
+                .map_err(|e| anyhow!("Failed to encode constructor arguments: {:?}", e)),
+            None if args.is_empty() => Ok(Vec::new()),
+            None => Err(anyhow!(
+                "Contract has no constructor but {} argument(s) were given",
+                args.len()
+            )),
+        }
+    }
+
+    pub fn get_inputs(&self) -> Vec<(String, String)> {
+        self.constructor
+            .as_ref()
+            .map(|c| {
+                c.inputs
+                    .iter()
+                    .map(|p| (p.name.clone(), p.ty.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
 pub struct DynAbiInvoker;
 
 impl DynAbiInvoker {
@@ -165,6 +233,24 @@ mod tests {
         assert!(calldata.len() >= 4);
     }
 
+    #[test]
+    fn test_decode_input_with_or_without_selector() {
+        use alloy_primitives::U256;
+
+        let dyn_func = DynAbiFunction::from_signature("transfer(address,uint256)").unwrap();
+        assert_eq!(dyn_func.selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(dyn_func.signature(), "transfer(address,uint256)");
+
+        let args = vec![
+            DynSolValue::Address("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826".parse().unwrap()),
+            DynSolValue::Uint(U256::from(100u64), 256),
+        ];
+        let calldata = dyn_func.encode_input(&args).unwrap();
+
+        assert_eq!(dyn_func.decode_input(&calldata).unwrap(), args);
+        assert_eq!(dyn_func.decode_input(&calldata[4..]).unwrap(), args);
+    }
+
     #[test]
     fn test_function_info() {
         let sig = "function swap(uint256 amount0Out, uint256 amount1Out, address to, bytes data) external";
@@ -175,4 +261,26 @@ mod tests {
         assert_eq!(inputs[0].0, "amount0Out");
         assert_eq!(inputs[1].0, "amount1Out");
     }
+
+    #[test]
+    fn test_constructor_encoding() {
+        use alloy_primitives::U256;
+
+        let abi = r#"[{"type":"constructor","inputs":[{"name":"supply","type":"uint256","internalType":"uint256"}],"stateMutability":"nonpayable"}]"#;
+        let constructor = DynAbiConstructor::from_json_abi(abi).unwrap();
+        assert_eq!(constructor.get_inputs(), vec![("supply".to_string(), "uint256".to_string())]);
+
+        let encoded = constructor
+            .encode_input(&[DynSolValue::Uint(U256::from(1u64), 256)])
+            .unwrap();
+        assert_eq!(encoded.len(), 32);
+        assert_eq!(encoded[31], 1);
+    }
+
+    #[test]
+    fn test_missing_constructor_rejects_args() {
+        let constructor = DynAbiConstructor::from_json_abi("[]").unwrap();
+        assert!(constructor.encode_input(&[]).unwrap().is_empty());
+        assert!(constructor.encode_input(&[DynSolValue::Bool(true)]).is_err());
+    }
 }
@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Result};
+use ethers::types::{Address, Bytes, U256};
+use ethers::utils::ParseUnits;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use super::abi::DynAbiFunction;
+use super::codec::Codec;
+use super::invoker_impl::ContractInvoker;
+use super::multicall::Call3Value;
+
+/// A list of contract calls to send together, loaded from a TOML file
+///
+/// ```toml
+/// [[call]]
+/// contract = "HelloWorld"
+/// function = "setMessage"
+/// args = ["hello"]
+///
+/// [[call]]
+/// contract = "0x..."
+/// function = "deposit()"
+/// value = "0.1"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchFile {
+    #[serde(rename = "call", default)]
+    pub calls: Vec<BatchCall>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchCall {
+    /// Deployed contract name or address
+    pub contract: String,
+    /// Function name from the contract's ABI, or a full signature such as `deposit(uint256)`
+    pub function: String,
+    #[serde(default)]
+    pub args: Vec<toml::Value>,
+    /// Native currency sent along, in ether
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// A batch call with its target, value and calldata resolved
+#[derive(Debug, Clone)]
+pub struct EncodedCall {
+    /// `Contract.function`, for display
+    pub label: String,
+    pub target: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub function: DynAbiFunction,
+}
+
+impl EncodedCall {
+    /// Named, formatted outputs of this call's return data
+    pub fn decode_output(&self, data: &[u8]) -> Result<Vec<(String, String)>> {
+        let decoded = self.function.decode_output(data)?;
+        Ok(Codec::format_values(&decoded, &self.function.get_outputs()))
+    }
+
+    pub fn to_call3_value(&self, allow_failure: bool) -> Call3Value {
+        Call3Value {
+            target: self.target,
+            allow_failure,
+            value: self.value,
+            call_data: self.data.clone(),
+        }
+    }
+}
+
+impl BatchFile {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read batch file {}: {}", path.display(), e))?;
+
+        Self::from_toml(&content)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let batch: BatchFile = toml::from_str(content)
+            .map_err(|e| anyhow!("Failed to parse batch file: {}", e))?;
+
+        if batch.calls.is_empty() {
+            anyhow::bail!("Batch file has no calls");
+        }
+        Ok(batch)
+    }
+
+    /// Resolves every call against the deployments of `network` and encodes its arguments
+    pub fn encode(&self, invoker: &ContractInvoker, network: &str) -> Result<Vec<EncodedCall>> {
+        self.calls
+            .iter()
+            .enumerate()
+            .map(|(i, call)| call.encode(invoker, network).map_err(|e| anyhow!("Call {} ({}): {}", i + 1, call.function, e)))
+            .collect()
+    }
+}
+
+impl BatchCall {
+    pub fn encode(&self, invoker: &ContractInvoker, network: &str) -> Result<EncodedCall> {
+        let is_signature = self.function.contains('(');
+
+        let (label, target, function) = if self.contract.starts_with("0x") {
+            if !is_signature {
+                anyhow::bail!("Calls to a raw address need a full signature such as {}(uint256)", self.function);
+            }
+            let target = self.contract.parse().map_err(|_| anyhow!("Invalid address: {}", self.contract))?;
+            (self.contract.clone(), target, DynAbiFunction::from_signature(&self.function)?)
+        } else {
+            let contract = invoker.get_contract(&self.contract, network)?;
+            let target = Address::from_slice(contract.address()?.as_slice());
+            let function = if is_signature {
+                DynAbiFunction::from_signature(&self.function)?
+            } else {
+                contract.get_function_abi(&self.function)?
+            };
+            (contract.contract_name().to_string(), target, function)
+        };
+
+        let inputs = function.get_inputs();
+        if self.args.len() != inputs.len() {
+            anyhow::bail!("Expected {} arguments, got {}", inputs.len(), self.args.len());
+        }
+
+        let mut args = Vec::with_capacity(inputs.len());
+        for (i, (arg, (_, ty))) in self.args.iter().zip(&inputs).enumerate() {
+            let arg = match arg {
+                toml::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let value = Codec::parse_value(&arg, ty)
+                .map_err(|e| anyhow!("Failed to parse argument {}: {}", i, e))?;
+            args.push(value);
+        }
+
+        let value = match &self.value {
+            Some(value) => match ethers::utils::parse_units(value.trim(), "ether")
+                .map_err(|e| anyhow!("Invalid value {}: {}", value, e))?
+            {
+                ParseUnits::U256(wei) => wei,
+                ParseUnits::I256(_) => anyhow::bail!("Invalid value {}: must not be negative", value),
+            },
+            None => U256::zero(),
+        };
+
+        let name = self.function.split('(').next().unwrap_or_default();
+        Ok(EncodedCall {
+            label: format!("{}.{}", label, name),
+            target,
+            value,
+            data: function.encode_input(&args)?.to_vec().into(),
+            function,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_raw_address_calls() {
+        let batch = BatchFile::from_toml(
+            r#"
+            [[call]]
+            contract = "0x00000000000000000000000000000000000000aa"
+            function = "transfer(address to, uint256 amount)"
+            args = ["0x0000000000000000000000000000000000000001", 5]
+
+            [[call]]
+            contract = "0x00000000000000000000000000000000000000bb"
+            function = "deposit()"
+            value = "0.5"
+            "#,
+        )
+        .unwrap();
+        let invoker = ContractInvoker::new("deployments/none.json", "artifacts");
+
+        let calls = batch.encode(&invoker, "none").unwrap();
+        assert_eq!(calls[0].label, "0x00000000000000000000000000000000000000aa.transfer");
+        assert_eq!(&calls[0].data[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(calls[0].data[67], 5);
+        assert!(calls[0].value.is_zero());
+        assert_eq!(calls[1].value, U256::exp10(17) * 5);
+        assert_eq!(calls[1].data.len(), 4);
+
+        let missing_args = BatchFile::from_toml("[[call]]\ncontract = \"0x00000000000000000000000000000000000000aa\"\nfunction = \"approve(address,uint256)\"").unwrap();
+        assert!(missing_args.encode(&invoker, "none").is_err());
+        assert!(BatchFile::from_toml("").is_err());
+    }
+
+    #[test]
+    fn test_negative_value_rejected() {
+        let batch = BatchFile::from_toml(
+            r#"
+            [[call]]
+            contract = "0x00000000000000000000000000000000000000bb"
+            function = "deposit()"
+            value = "-1"
+            "#,
+        )
+        .unwrap();
+        let invoker = ContractInvoker::new("deployments/none.json", "artifacts");
+
+        let err = batch.encode(&invoker, "none").unwrap_err();
+        assert!(err.to_string().contains("must not be negative"));
+    }
+}
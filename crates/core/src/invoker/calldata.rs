@@ -0,0 +1,169 @@
+use super::codec::Codec;
+use super::signatures::SignatureDb;
+use crate::artifacts::scan_artifacts;
+use alloy_dyn_abi::JsonAbiExt;
+use alloy_json_abi::{Function, JsonAbi};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Widely used functions, to decode calls to contracts we have no artifact for
+pub const KNOWN_SIGNATURES: &[&str] = &[
+    "function transfer(address to, uint256 amount)",
+    "function transferFrom(address from, address to, uint256 amount)",
+    "function approve(address spender, uint256 amount)",
+    "function safeTransferFrom(address from, address to, uint256 tokenId)",
+    "function safeTransferFrom(address from, address to, uint256 tokenId, bytes data)",
+    "function setApprovalForAll(address operator, bool approved)",
+    "function safeTransferFrom(address from, address to, uint256 id, uint256 amount, bytes data)",
+    "function safeBatchTransferFrom(address from, address to, uint256[] ids, uint256[] amounts, bytes data)",
+    "function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s)",
+    "function deposit()",
+    "function withdraw(uint256 amount)",
+    "function multicall(bytes[] data)",
+    "function aggregate3((address,bool,bytes)[] calls)",
+    "function upgradeToAndCall(address newImplementation, bytes data)",
+    "function upgradeAndCall(address proxy, address implementation, bytes data)",
+    "function transferOwnership(address newOwner)",
+    "function renounceOwnership()",
+];
+
+/// A decoded function call
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedCall {
+    /// Artifact the function was found in, `None` for known signatures
+    pub contract: Option<String>,
+    pub signature: String,
+    /// `(name, type, value)` of every argument
+    pub args: Vec<(String, String, String)>,
+}
+
+impl std::fmt::Display for DecodedCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(contract) = &self.contract {
+            write!(f, "{}.", contract)?;
+        }
+        write!(f, "{}", self.signature)
+    }
+}
+
+/// Maps 4-byte selectors to candidate functions and decodes calldata with them
+#[derive(Debug, Clone, Default)]
+pub struct CalldataDecoder {
+    functions: HashMap<[u8; 4], Vec<(Option<String>, Function)>>,
+    signatures: SignatureDb,
+}
+
+impl CalldataDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every function of the artifacts under `artifact_dir`, then the known signatures and the signature database
+    pub fn from_artifacts<P: AsRef<Path>>(artifact_dir: P) -> Result<Self> {
+        let mut decoder = Self::new().with_signatures(SignatureDb::load_default()?);
+
+        if artifact_dir.as_ref().exists() {
+            for entry in scan_artifacts(artifact_dir)? {
+                // Artifacts with an ABI alloy cannot parse are skipped rather than failing the whole scan
+                decoder.add_artifact(&entry.name, &entry.path).ok();
+            }
+        }
+
+        for signature in KNOWN_SIGNATURES {
+            decoder.add_signature(signature)?;
+        }
+
+        Ok(decoder)
+    }
+
+    /// Every function of the artifact file at `path`
+    pub fn add_artifact<P: AsRef<Path>>(&mut self, contract: &str, path: P) -> Result<()> {
+        let content = fs::read_to_string(path.as_ref())?;
+        let json: serde_json::Value = serde_json::from_str(&content)?;
+        let abi: JsonAbi = serde_json::from_value(json["abi"].clone())
+            .map_err(|e| anyhow::anyhow!("Invalid ABI in {}: {}", path.as_ref().display(), e))?;
+        self.add_abi(contract, &abi);
+        Ok(())
+    }
+
+    pub fn add_abi(&mut self, contract: &str, abi: &JsonAbi) {
+        for function in abi.functions() {
+            self.insert(Some(contract.to_string()), function.clone());
+        }
+    }
+
+    pub fn add_signature(&mut self, signature: &str) -> Result<()> {
+        let function = Function::parse(signature)
+            .map_err(|e| anyhow::anyhow!("Invalid function signature {}: {:?}", signature, e))?;
+        self.insert(None, function);
+        Ok(())
+    }
+
+    fn insert(&mut self, contract: Option<String>, function: Function) {
+        let candidates = self.functions.entry(function.selector().0).or_default();
+        if !candidates.iter().any(|(c, f)| c == &contract && f.signature() == function.signature()) {
+            candidates.push((contract, function));
+        }
+    }
+
+    /// Decodes with the first candidate whose argument types fit the calldata,
+    /// falling back to the signature database
+    pub fn decode(&self, calldata: &[u8]) -> Option<DecodedCall> {
+        let selector: [u8; 4] = calldata.get(..4)?.try_into().ok()?;
+
+        let from_abis = self.functions.get(&selector).and_then(|candidates| {
+            candidates.iter().find_map(|(contract, function)| {
+                let values = function.abi_decode_input(&calldata[4..], true).ok()?;
+                let args = function
+                    .inputs
+                    .iter()
+                    .zip(&values)
+                    .map(|(param, value)| (param.name.clone(), param.ty.clone(), Codec::format_value(value, &param.ty)))
+                    .collect();
+
+                Some(DecodedCall {
+                    contract: contract.clone(),
+                    signature: function.signature(),
+                    args,
+                })
+            })
+        });
+
+        from_abis.or_else(|| self.signatures.decode_call(calldata).into_iter().next())
+    }
+
+    pub fn with_signatures(mut self, signatures: SignatureDb) -> Self {
+        self.signatures = signatures;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_known_signature() {
+        let mut decoder = CalldataDecoder::new();
+        for signature in KNOWN_SIGNATURES {
+            decoder.add_signature(signature).unwrap();
+        }
+
+        let calldata = hex::decode(concat!(
+            "a9059cbb",
+            "000000000000000000000000cd2a3d9f938e13cd947ec05abc7fe734df8dd826",
+            "0000000000000000000000000000000000000000000000000000000000000064"
+        ))
+        .unwrap();
+
+        let call = decoder.decode(&calldata).unwrap();
+        assert_eq!(call.signature, "transfer(address,uint256)");
+        assert_eq!(call.args[0].2, "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826");
+        assert_eq!(call.args[1], ("amount".to_string(), "uint256".to_string(), "100".to_string()));
+
+        assert!(decoder.decode(&calldata[..20]).is_none());
+        assert!(decoder.decode(&[0xde, 0xad, 0xbe, 0xef]).is_none());
+    }
+}
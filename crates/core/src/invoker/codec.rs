@@ -1,4 +1,4 @@
-use alloy_dyn_abi::{DynSolValue, Word};
+use alloy_dyn_abi::{DynSolType, DynSolValue, Word};
 use alloy_primitives::{Address, U256, I256, Sign};
 use anyhow::{anyhow, Result};
 
@@ -8,6 +8,16 @@ impl Codec {
     pub fn parse_value(input: &str, type_str: &str) -> Result<DynSolValue> {
         let trimmed = input.trim();
 
+        // Arrays, tuples and sized integers need the exact type, alloy's parser handles them
+        if type_str.contains('[') || type_str.starts_with('(') || type_str.starts_with("uint") || type_str.starts_with("int") {
+            let ty: DynSolType = type_str
+                .parse()
+                .map_err(|e| anyhow!("Invalid type {}: {}", type_str, e))?;
+            return ty
+                .coerce_str(trimmed)
+                .map_err(|_| anyhow!("Invalid value for {}: {}", type_str, trimmed));
+        }
+
         if type_str.contains("string") {
             Ok(DynSolValue::String(trimmed.to_string()))
         } else if type_str.contains("bytes32") {
@@ -126,6 +136,23 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_sized_arrays_and_tuples() {
+        assert_eq!(Codec::parse_value("255", "uint8").unwrap(), DynSolValue::Uint(U256::from(255u64), 8));
+        assert!(Codec::parse_value("256", "uint8").is_err());
+        assert_eq!(
+            Codec::parse_value("0x10", "uint256").unwrap(),
+            DynSolValue::Uint(U256::from(16u64), 256)
+        );
+        assert!(matches!(Codec::parse_value("-5", "int24").unwrap(), DynSolValue::Int(_, 24)));
+
+        let array = Codec::parse_value("[1, 2, 3]", "uint256[]").unwrap();
+        assert_eq!(array.as_array().map(|a| a.len()), Some(3));
+
+        let tuple = Codec::parse_value("(0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826, true)", "(address,bool)").unwrap();
+        assert_eq!(tuple.as_tuple().map(|t| t.len()), Some(2));
+    }
+
     #[test]
     fn test_parse_bool() {
         let result = Codec::parse_value("true", "bool");
@@ -1,12 +1,17 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::path::Path;
 use super::abi::DynAbiFunction;
+use crate::artifacts::resolve_artifact;
+use crate::registry::{DeploymentRegistry, RegistryEntry};
 use alloy_primitives::Address;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeploymentRecord {
     pub contract_name: String,
+    pub version: u32,
+    pub artifact: String,
     pub address: String,
     pub network: String,
     pub tx_hash: String,
@@ -14,6 +19,21 @@ pub struct DeploymentRecord {
     pub timestamp: u64,
 }
 
+impl DeploymentRecord {
+    fn from_entry(name: &str, network: &str, entry: &RegistryEntry) -> Self {
+        DeploymentRecord {
+            contract_name: name.to_string(),
+            version: entry.version,
+            artifact: entry.artifact.clone(),
+            address: entry.address.clone(),
+            network: network.to_string(),
+            tx_hash: entry.tx_hash.clone(),
+            deployer: entry.deployer.clone(),
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractArtifact {
     pub abi: serde_json::Value,
@@ -30,27 +50,34 @@ pub struct DeployedContract {
 pub struct DeploymentManager;
 
 impl DeploymentManager {
-    pub fn load_deployments(file_path: &str) -> Result<Vec<DeploymentRecord>> {
-        let content = fs::read_to_string(file_path)
-            .map_err(|e| anyhow!("Failed to read deployment file: {}", e))?;
+    fn load_registry(file_path: &str) -> Result<DeploymentRegistry> {
+        let network = Path::new(file_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("Invalid deployment file path: {}", file_path))?;
+
+        DeploymentRegistry::load_file(file_path, network)
+    }
 
-        let records: Vec<DeploymentRecord> = serde_json::from_str(&content)
-            .map_err(|e| anyhow!("Failed to parse deployment file: {}", e))?;
+    pub fn load_deployments(file_path: &str) -> Result<Vec<DeploymentRecord>> {
+        let registry = Self::load_registry(file_path)?;
 
-        Ok(records)
+        Ok(registry
+            .entries()
+            .into_iter()
+            .map(|(name, entry)| DeploymentRecord::from_entry(name, &registry.network, entry))
+            .collect())
     }
 
+    /// Loads an artifact by `Name` or `path/File.sol:Name`
     pub fn load_artifact(
         artifact_dir: &str,
         contract_name: &str,
     ) -> Result<ContractArtifact> {
-        let artifact_path = format!(
-            "{}/{}.sol/{}.json",
-            artifact_dir, contract_name, contract_name
-        );
+        let artifact_path = resolve_artifact(artifact_dir, contract_name)?;
 
         let content = fs::read_to_string(&artifact_path)
-            .map_err(|e| anyhow!("Failed to read artifact file {}: {}", artifact_path, e))?;
+            .map_err(|e| anyhow!("Failed to read artifact file {}: {}", artifact_path.display(), e))?;
 
         let artifact: ContractArtifact = serde_json::from_str(&content)
             .map_err(|e| anyhow!("Failed to parse artifact: {}", e))?;
@@ -58,26 +85,21 @@ impl DeploymentManager {
         Ok(artifact)
     }
 
+    /// Looks up a deployment by registry reference, e.g. `HelloWorld`, `HelloWorld@2` or `HelloWorld@stable`
     pub fn get_deployed_contract(
         deployments_file: &str,
         artifact_dir: &str,
         contract_name: &str,
         network: &str,
     ) -> Result<DeployedContract> {
-        let records = Self::load_deployments(deployments_file)?;
+        let registry = Self::load_registry(deployments_file)?;
 
-        let record = records
-            .into_iter()
-            .find(|r| r.contract_name == contract_name && r.network == network)
-            .ok_or_else(|| {
-                anyhow!(
-                    "Deployment not found for {} on network {}",
-                    contract_name,
-                    network
-                )
-            })?;
+        let (name, entry) = registry.resolve(contract_name).map_err(|e| {
+            anyhow!("Deployment not found for {} on network {}: {}", contract_name, network, e)
+        })?;
 
-        let artifact = Self::load_artifact(artifact_dir, contract_name)?;
+        let record = DeploymentRecord::from_entry(name, network, entry);
+        let artifact = Self::load_artifact(artifact_dir, &record.artifact)?;
 
         Ok(DeployedContract { record, artifact })
     }
@@ -89,11 +111,10 @@ impl DeploymentManager {
         address: &str,
         network: &str,
     ) -> Result<DeployedContract> {
-        let records = Self::load_deployments(deployments_file)?;
-
-        let record = records
+        let record = Self::load_deployments(deployments_file)?
             .into_iter()
-            .find(|r| r.contract_name == contract_name && r.address == address && r.network == network)
+            .filter(|r| r.contract_name == contract_name && r.address.eq_ignore_ascii_case(address))
+            .max_by_key(|r| r.version)
             .ok_or_else(|| {
                 anyhow!(
                     "Deployment not found for {} at address {} on network {}",
@@ -103,7 +124,7 @@ impl DeploymentManager {
                 )
             })?;
 
-        let artifact = Self::load_artifact(artifact_dir, contract_name)?;
+        let artifact = Self::load_artifact(artifact_dir, &record.artifact)?;
 
         Ok(DeployedContract { record, artifact })
     }
@@ -154,6 +175,25 @@ impl DeployedContract {
         Ok(functions)
     }
 
+    /// View and pure functions that take no arguments, the ones readable without input
+    pub fn get_view_functions(&self) -> Result<Vec<String>> {
+        let functions = self
+            .artifact
+            .abi
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid ABI format"))?
+            .iter()
+            .filter(|item| {
+                item.get("type").and_then(|t| t.as_str()) == Some("function")
+                    && matches!(item.get("stateMutability").and_then(|m| m.as_str()), Some("view" | "pure"))
+                    && item.get("inputs").and_then(|i| i.as_array()).is_none_or(|i| i.is_empty())
+            })
+            .filter_map(|item| item.get("name").and_then(|n| n.as_str()).map(|s| s.to_string()))
+            .collect();
+
+        Ok(functions)
+    }
+
     pub fn get_function_info(&self, function_name: &str) -> Result<(Vec<(String, String)>, Vec<(String, String)>)> {
         let abi_str = serde_json::to_string(&self.artifact.abi)
             .map_err(|e| anyhow!("Failed to serialize ABI: {}", e))?;
@@ -0,0 +1,159 @@
+use super::signatures::{decode_event, SignatureDb};
+use crate::artifacts::scan_artifacts;
+use alloy_json_abi::{Event, JsonAbi};
+use alloy_primitives::B256;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Widely used events, to decode logs of contracts we have no artifact for
+pub const KNOWN_EVENTS: &[&str] = &[
+    "event Transfer(address indexed from, address indexed to, uint256 value)",
+    "event Transfer(address indexed from, address indexed to, uint256 indexed tokenId)",
+    "event Approval(address indexed owner, address indexed spender, uint256 value)",
+    "event Approval(address indexed owner, address indexed approved, uint256 indexed tokenId)",
+    "event ApprovalForAll(address indexed owner, address indexed operator, bool approved)",
+    "event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value)",
+    "event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values)",
+    "event Deposit(address indexed dst, uint256 wad)",
+    "event Withdrawal(address indexed src, uint256 wad)",
+    "event Upgraded(address indexed implementation)",
+    "event AdminChanged(address previousAdmin, address newAdmin)",
+    "event Initialized(uint64 version)",
+    "event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)",
+];
+
+/// Topic 0 of an event signature, e.g. `Transfer(address,address,uint256)`
+pub fn event_topic(signature: &str) -> Result<B256> {
+    let signature = signature.trim();
+    let signature = if signature.starts_with("event ") { signature.to_string() } else { format!("event {}", signature) };
+    let event = Event::parse(&signature).map_err(|e| anyhow::anyhow!("Invalid event signature: {:?}", e))?;
+    Ok(event.selector())
+}
+
+/// A decoded event log
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedLog {
+    /// Artifact the event was found in, `None` for known events
+    pub contract: Option<String>,
+    pub signature: String,
+    /// `(name, type, value)` of every parameter, in declaration order
+    pub args: Vec<(String, String, String)>,
+}
+
+impl std::fmt::Display for DecodedLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(contract) = &self.contract {
+            write!(f, "{}.", contract)?;
+        }
+        write!(f, "{}", self.signature)
+    }
+}
+
+/// Maps event topics to candidate events and decodes logs with them
+#[derive(Debug, Clone, Default)]
+pub struct LogDecoder {
+    events: HashMap<B256, Vec<(Option<String>, Event)>>,
+    signatures: SignatureDb,
+}
+
+impl LogDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every event of the artifacts under `artifact_dir`, then the known events and the signature database
+    pub fn from_artifacts<P: AsRef<Path>>(artifact_dir: P) -> Result<Self> {
+        let mut decoder = Self::new().with_signatures(SignatureDb::load_default()?);
+
+        if artifact_dir.as_ref().exists() {
+            for entry in scan_artifacts(artifact_dir)? {
+                let Ok(content) = fs::read_to_string(&entry.path) else { continue };
+                let Some(abi) = serde_json::from_str::<serde_json::Value>(&content)
+                    .ok()
+                    .and_then(|json| serde_json::from_value::<JsonAbi>(json.get("abi")?.clone()).ok())
+                else {
+                    continue;
+                };
+                decoder.add_abi(&entry.name, &abi);
+            }
+        }
+
+        for signature in KNOWN_EVENTS {
+            decoder.add_signature(signature)?;
+        }
+
+        Ok(decoder)
+    }
+
+    pub fn add_abi(&mut self, contract: &str, abi: &JsonAbi) {
+        for event in abi.events().filter(|e| !e.anonymous) {
+            self.insert(Some(contract.to_string()), event.clone());
+        }
+    }
+
+    pub fn add_signature(&mut self, signature: &str) -> Result<()> {
+        let event = Event::parse(signature)
+            .map_err(|e| anyhow::anyhow!("Invalid event signature {}: {:?}", signature, e))?;
+        self.insert(None, event);
+        Ok(())
+    }
+
+    fn insert(&mut self, contract: Option<String>, event: Event) {
+        let candidates = self.events.entry(event.selector()).or_default();
+        let indexed = |e: &Event| e.inputs.iter().map(|i| i.indexed).collect::<Vec<_>>();
+        if !candidates
+            .iter()
+            .any(|(c, e)| c == &contract && e.signature() == event.signature() && indexed(e) == indexed(&event))
+        {
+            candidates.push((contract, event));
+        }
+    }
+
+    /// Decodes with the first candidate whose indexed parameters and data fit the log,
+    /// falling back to the signature database
+    pub fn decode(&self, topics: &[B256], data: &[u8]) -> Option<DecodedLog> {
+        let from_abis = self.events.get(topics.first()?).and_then(|candidates| {
+            candidates.iter().find_map(|(contract, event)| {
+                let decoded = decode_event(event, topics, data)?;
+                Some(DecodedLog { contract: contract.clone(), ..decoded })
+            })
+        });
+
+        from_abis.or_else(|| self.signatures.decode_log(topics, data).into_iter().next())
+    }
+
+    pub fn with_signatures(mut self, signatures: SignatureDb) -> Self {
+        self.signatures = signatures;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_distinguishes_erc20_and_erc721_transfers() {
+        let mut decoder = LogDecoder::new();
+        for signature in KNOWN_EVENTS {
+            decoder.add_signature(signature).unwrap();
+        }
+
+        let topic0: B256 = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef".parse().unwrap();
+        let from = B256::left_padding_from(&[0x11; 20]);
+        let to = B256::left_padding_from(&[0x22; 20]);
+        let amount = B256::left_padding_from(&[0x64]);
+
+        let erc20 = decoder.decode(&[topic0, from, to], amount.as_slice()).unwrap();
+        assert_eq!(erc20.signature, "Transfer(address,address,uint256)");
+        assert_eq!(erc20.args[2], ("value".to_string(), "uint256".to_string(), "100".to_string()));
+
+        let erc721 = decoder.decode(&[topic0, from, to, amount], &[]).unwrap();
+        assert_eq!(erc721.args[2].0, "tokenId");
+        assert_eq!(erc721.args[1].2, format!("0x{}", "22".repeat(20)));
+
+        assert!(decoder.decode(&[B256::ZERO], &[]).is_none());
+    }
+}
@@ -8,6 +8,7 @@ use ethers::types::transaction::eip2718::TypedTransaction;
 
 use super::abi::DynAbiFunction;
 use super::codec::Codec;
+use super::multicall::{self, Call3};
 
 pub struct ContractExecutor {
     http_client: HttpClient,
@@ -32,7 +33,7 @@ impl ContractExecutor {
         private_key: &str,
         network: Network,
     ) -> Result<Self> {
-        let http_client = HttpClient::new(rpc_url).await?;
+        let http_client = HttpClient::for_network(rpc_url, &network).await?;
 
         let ws_client = if !network.ws_rpc.is_empty() {
             Some(WebSocketClient::new(&network.ws_rpc[0]))
@@ -90,6 +91,37 @@ impl ContractExecutor {
         })
     }
 
+    /// Calls argument-less read functions in one Multicall3 round-trip, in the order given;
+    /// a revert only fails its own entry
+    pub async fn call_read_functions(
+        &self,
+        contract_address: Address,
+        functions: &[DynAbiFunction],
+    ) -> Result<Vec<Result<ReadResult>>> {
+        let to_addr = H160::from_slice(&contract_address.to_vec()[..20]);
+
+        let calls = functions
+            .iter()
+            .map(|function| Ok(Call3::new(to_addr, function.encode_input(&[])?.to_vec())))
+            .collect::<Result<Vec<_>>>()?;
+
+        let results = multicall::aggregate(&self.http_client, self.network.chain_id, &calls).await?;
+
+        Ok(functions
+            .iter()
+            .zip(results)
+            .map(|(function, result)| {
+                if !result.success {
+                    return Err(anyhow!("Call to {} reverted", function.signature()));
+                }
+                let decoded_outputs = function.decode_output(&result.return_data)?;
+                Ok(ReadResult {
+                    outputs: Codec::format_values(&decoded_outputs, &function.get_outputs()),
+                })
+            })
+            .collect())
+    }
+
     pub async fn call_write_function(
         &self,
         contract_address: Address,
@@ -146,7 +178,7 @@ impl ContractExecutor {
         }
 
         let receipt = pending_tx
-            .confirmations(1)
+            .confirmations(crate::project::confirmations())
             .await
             .map_err(|e| anyhow!("Failed to confirm transaction: {}", e))?
             .ok_or_else(|| anyhow!("Transaction confirmation timeout"))?;
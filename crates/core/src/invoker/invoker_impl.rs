@@ -88,6 +88,10 @@ impl DeployedContractInvoker {
         self.contract.get_all_functions()
     }
 
+    pub fn get_view_functions(&self) -> Result<Vec<String>> {
+        self.contract.get_view_functions()
+    }
+
     pub fn get_deployed_record(&self) -> &super::deployment::DeploymentRecord {
         &self.contract.record
     }
@@ -115,6 +119,25 @@ impl DeployedContractInvoker {
         executor.call_read_function(contract_address, &dyn_func, args, function_name).await
     }
 
+    /// Reads every argument-less view function in one round-trip, paired with its name
+    pub async fn execute_view_functions(
+        &self,
+        rpc_url: &str,
+        private_key: &str,
+        network: &Network,
+    ) -> Result<Vec<(String, Result<ReadResult>)>> {
+        let executor = ContractExecutor::new(rpc_url, private_key, network.clone()).await?;
+        let names = self.contract.get_view_functions()?;
+        let functions = names
+            .iter()
+            .map(|name| self.contract.get_function_abi(name))
+            .collect::<Result<Vec<_>>>()?;
+        let contract_address = self.contract.address()?;
+
+        let results = executor.call_read_functions(contract_address, &functions).await?;
+        Ok(names.into_iter().zip(results).collect())
+    }
+
     pub async fn execute_write_function(
         &self,
         rpc_url: &str,
@@ -1,11 +1,21 @@
 pub mod abi;
+pub mod batch;
+pub mod calldata;
 pub mod codec;
 pub mod deployment;
+pub mod events;
 pub mod invoker_impl;
 pub mod executor;
+pub mod multicall;
+pub mod signatures;
 
-pub use abi::DynAbiFunction;
+pub use abi::{DynAbiConstructor, DynAbiFunction};
+pub use batch::{BatchCall, BatchFile, EncodedCall};
+pub use calldata::{CalldataDecoder, DecodedCall};
 pub use codec::Codec;
+pub use events::{event_topic, DecodedLog, LogDecoder};
 pub use deployment::{DeploymentManager, DeployedContract, DeploymentRecord};
 pub use invoker_impl::{ContractInvoker, DeployedContractInvoker};
+pub use multicall::{Call3, Call3Value, CallResult, Multicall, MULTICALL3_ADDRESS};
+pub use signatures::{SignatureDb, DEFAULT_SIGNATURES_PATH};
 pub use executor::{ContractExecutor, ExecutionResult, ReadResult};
@@ -0,0 +1,285 @@
+use crate::artifacts::resolve_artifact;
+use crate::network::HttpClient;
+use anyhow::{anyhow, Result};
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::keccak256;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Canonical Multicall3, deployed at the same address on most chains
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// Calls per `aggregate3`, keeps each `eth_call` under common RPC gas and payload limits
+pub const MAX_CALLS_PER_BATCH: usize = 500;
+
+/// Dev chains (anvil, hardhat, ganache) where a missing Multicall3 is installed on the fly
+const LOCAL_CHAIN_IDS: [u64; 2] = [31337, 1337];
+
+pub fn multicall3_address() -> Address {
+    MULTICALL3_ADDRESS.parse().expect("valid Multicall3 address")
+}
+
+pub fn is_local_chain(chain_id: u64) -> bool {
+    LOCAL_CHAIN_IDS.contains(&chain_id)
+}
+
+/// One call of an `aggregate3` batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call3 {
+    pub target: Address,
+    /// When false a revert of this call reverts the whole batch
+    pub allow_failure: bool,
+    pub call_data: Bytes,
+}
+
+impl Call3 {
+    /// A call whose failure is reported in its result instead of failing the batch
+    pub fn new(target: Address, call_data: impl Into<Bytes>) -> Self {
+        Call3 { target, allow_failure: true, call_data: call_data.into() }
+    }
+}
+
+/// One call of an `aggregate3Value` batch, forwarding `value` wei to the target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call3Value {
+    pub target: Address,
+    pub allow_failure: bool,
+    pub value: U256,
+    pub call_data: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallResult {
+    pub success: bool,
+    /// Return data, or revert data when the call failed
+    pub return_data: Bytes,
+}
+
+pub fn encode_aggregate3(calls: &[Call3]) -> Bytes {
+    let calls = calls
+        .iter()
+        .map(|c| {
+            Token::Tuple(vec![Token::Address(c.target), Token::Bool(c.allow_failure), Token::Bytes(c.call_data.to_vec())])
+        })
+        .collect();
+
+    let mut data = keccak256("aggregate3((address,bool,bytes)[])")[..4].to_vec();
+    data.extend(abi::encode(&[Token::Array(calls)]));
+    data.into()
+}
+
+/// Calldata for `aggregate3Value`, the transaction must carry the sum of the call values
+pub fn encode_aggregate3_value(calls: &[Call3Value]) -> Bytes {
+    let calls = calls
+        .iter()
+        .map(|c| {
+            Token::Tuple(vec![
+                Token::Address(c.target),
+                Token::Bool(c.allow_failure),
+                Token::Uint(c.value),
+                Token::Bytes(c.call_data.to_vec()),
+            ])
+        })
+        .collect();
+
+    let mut data = keccak256("aggregate3Value((address,bool,uint256,bytes)[])")[..4].to_vec();
+    data.extend(abi::encode(&[Token::Array(calls)]));
+    data.into()
+}
+
+/// Decodes the `Result[]` returned by `aggregate3` and `aggregate3Value`
+pub fn decode_aggregate3(data: &[u8]) -> Result<Vec<CallResult>> {
+    let result = ParamType::Tuple(vec![ParamType::Bool, ParamType::Bytes]);
+    let tokens = abi::decode(&[ParamType::Array(Box::new(result))], data)
+        .map_err(|e| anyhow!("Failed to decode aggregate3 result: {}", e))?;
+
+    let Some(Token::Array(results)) = tokens.into_iter().next() else {
+        return Err(anyhow!("Unexpected aggregate3 result"));
+    };
+
+    results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(success), Token::Bytes(data)] => Ok(CallResult { success: *success, return_data: data.clone().into() }),
+                _ => Err(anyhow!("Unexpected aggregate3 result")),
+            },
+            _ => Err(anyhow!("Unexpected aggregate3 result")),
+        })
+        .collect()
+}
+
+/// Runs many view calls through Multicall3, one `eth_call` per [`MAX_CALLS_PER_BATCH`] calls
+pub struct Multicall {
+    client: HttpClient,
+    address: Address,
+}
+
+impl Multicall {
+    pub fn new(client: &HttpClient, address: Address) -> Self {
+        Multicall { client: client.clone(), address }
+    }
+
+    /// Multicall3 at its canonical address, installed first on local chains that lack it
+    pub async fn connect(client: &HttpClient, chain_id: u64) -> Result<Self> {
+        let address = ensure_multicall(client, chain_id, crate::project::artifacts_dir()).await?;
+        Ok(Multicall::new(client, address))
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Results in the order of `calls`
+    pub async fn call(&self, calls: &[Call3]) -> Result<Vec<CallResult>> {
+        let mut results = Vec::with_capacity(calls.len());
+
+        for batch in calls.chunks(MAX_CALLS_PER_BATCH) {
+            let tx: TypedTransaction = TransactionRequest::new().to(self.address).data(encode_aggregate3(batch)).into();
+            let output = self
+                .client
+                .get_provider()
+                .call(&tx, None)
+                .await
+                .map_err(|e| anyhow!("Multicall failed: {}", e))?;
+
+            let decoded = decode_aggregate3(&output)?;
+            if decoded.len() != batch.len() {
+                anyhow::bail!("Multicall returned {} results for {} calls", decoded.len(), batch.len());
+            }
+            results.extend(decoded);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Address of a working Multicall3; on local chains without one, the runtime code compiled
+/// from `contracts/Multicall3.sol` is installed at the canonical address
+pub async fn ensure_multicall<P: AsRef<Path>>(client: &HttpClient, chain_id: u64, artifact_dir: P) -> Result<Address> {
+    let address = multicall3_address();
+    if !client.get_code(address).await?.is_empty() {
+        return Ok(address);
+    }
+
+    if !is_local_chain(chain_id) {
+        anyhow::bail!("Multicall3 is not deployed at {} on chain {}", MULTICALL3_ADDRESS, chain_id);
+    }
+
+    let code = deployed_bytecode(artifact_dir.as_ref())?;
+    client.set_code(address, &code).await?;
+    Ok(address)
+}
+
+fn deployed_bytecode(artifact_dir: &Path) -> Result<Bytes> {
+    let path = resolve_artifact(artifact_dir, "Multicall3")
+        .map_err(|e| anyhow!("{}, compile contracts/Multicall3.sol first", e))?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let json: Value = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+
+    let code = match json.get("deployedBytecode") {
+        Some(Value::String(code)) => code.as_str(),
+        Some(code) => code.get("object").and_then(|o| o.as_str()).unwrap_or_default(),
+        None => "",
+    };
+    let code = hex::decode(code.trim_start_matches("0x"))
+        .map_err(|e| anyhow!("Invalid deployed bytecode in {}: {}", path.display(), e))?;
+    if code.is_empty() {
+        anyhow::bail!("No deployed bytecode in {}", path.display());
+    }
+    Ok(code.into())
+}
+
+/// Runs `calls` in one round-trip through Multicall3 when the chain has it (or is a local
+/// chain it can be installed on), otherwise one `eth_call` each
+///
+/// Either way a reverting call only marks its own result unsuccessful, RPC errors are returned.
+pub async fn aggregate(client: &HttpClient, chain_id: u64, calls: &[Call3]) -> Result<Vec<CallResult>> {
+    let has_multicall = !client.get_code(multicall3_address()).await?.is_empty();
+    if has_multicall || is_local_chain(chain_id) {
+        return Multicall::connect(client, chain_id).await?.call(calls).await;
+    }
+
+    let mut results = Vec::with_capacity(calls.len());
+    for call in calls {
+        let tx: TypedTransaction = TransactionRequest::new().to(call.target).data(call.call_data.clone()).into();
+        let result = match client.get_provider().call(&tx, None).await {
+            Ok(data) => CallResult { success: true, return_data: data },
+            Err(e) => match RpcError::as_error_response(&e).and_then(|r| r.as_revert_data()) {
+                Some(revert_data) if call.allow_failure => CallResult { success: false, return_data: revert_data },
+                _ => return Err(anyhow!("Call to {:#x} failed: {}", call.target, e)),
+            },
+        };
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_aggregate3() {
+        let target = Address::repeat_byte(0xaa);
+        let data = encode_aggregate3(&[Call3::new(target, vec![0x70, 0xa0, 0x82, 0x31])]);
+
+        assert_eq!(&data[..4], &[0x82, 0xad, 0x56, 0xcb]);
+        let tokens = abi::decode(
+            &[ParamType::Array(Box::new(ParamType::Tuple(vec![ParamType::Address, ParamType::Bool, ParamType::Bytes])))],
+            &data[4..],
+        )
+        .unwrap();
+        assert_eq!(
+            tokens,
+            vec![Token::Array(vec![Token::Tuple(vec![
+                Token::Address(target),
+                Token::Bool(true),
+                Token::Bytes(vec![0x70, 0xa0, 0x82, 0x31]),
+            ])])]
+        );
+    }
+
+    #[test]
+    fn test_encode_aggregate3_value() {
+        let call = Call3Value {
+            target: Address::repeat_byte(0xaa),
+            allow_failure: false,
+            value: U256::from(7),
+            call_data: Bytes::from(vec![0xd0, 0xe3, 0x0d, 0xb0]),
+        };
+        let data = encode_aggregate3_value(&[call]);
+
+        assert_eq!(&data[..4], &[0x17, 0x4d, 0xea, 0x71]);
+        let tokens = abi::decode(
+            &[ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::Bool,
+                ParamType::Uint(256),
+                ParamType::Bytes,
+            ])))],
+            &data[4..],
+        )
+        .unwrap();
+        assert!(matches!(&tokens[0], Token::Array(calls) if matches!(&calls[0], Token::Tuple(f) if f[2] == Token::Uint(7.into()))));
+    }
+
+    #[test]
+    fn test_decode_aggregate3() {
+        let output = abi::encode(&[Token::Array(vec![
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(vec![0u8; 32])]),
+            Token::Tuple(vec![Token::Bool(false), Token::Bytes(vec![])]),
+        ])]);
+
+        let results = decode_aggregate3(&output).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].success);
+        assert_eq!(results[0].return_data.len(), 32);
+        assert_eq!(results[1], CallResult { success: false, return_data: Bytes::new() });
+    }
+}
@@ -0,0 +1,316 @@
+use super::calldata::DecodedCall;
+use super::codec::Codec;
+use super::events::DecodedLog;
+use crate::artifacts::scan_artifacts;
+use alloy_dyn_abi::{EventExt, JsonAbiExt};
+use alloy_json_abi::{Event, EventParam, Function, JsonAbi};
+use alloy_primitives::{keccak256, B256};
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_SIGNATURES_PATH: &str = ".signatures.txt";
+
+/// Local database of function and event signatures, keyed by selector and topic
+///
+/// Stored as text, one `function name(types)` or `event Name(types)` per line. Events keep
+/// their `indexed` markers when known; without them every split of the parameters is tried.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureDb {
+    functions: BTreeMap<[u8; 4], BTreeSet<String>>,
+    events: BTreeMap<B256, BTreeSet<String>>,
+    path: Option<PathBuf>,
+}
+
+impl SignatureDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads `path`, starting empty when it does not exist yet
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut db = SignatureDb { path: Some(path.to_path_buf()), ..Default::default() };
+
+        if path.exists() {
+            let content = fs::read_to_string(path)
+                .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+            db.import_text(&content);
+        }
+
+        Ok(db)
+    }
+
+    pub fn load_default() -> Result<Self> {
+        Self::load(DEFAULT_SIGNATURES_PATH)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = self.path.as_ref().ok_or_else(|| anyhow!("Signature database has no file"))?;
+
+        let mut lines: Vec<String> = Vec::new();
+        lines.extend(self.functions.values().flatten().map(|s| format!("function {}", s)));
+        lines.extend(self.events.values().flatten().map(|s| format!("event {}", s)));
+        lines.sort();
+        lines.dedup();
+
+        fs::write(path, lines.join("\n") + "\n")
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn len(&self) -> usize {
+        self.functions.values().map(|s| s.len()).sum::<usize>() + self.events.values().map(|s| s.len()).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn add_function(&mut self, function: &Function) -> bool {
+        self.functions.entry(function.selector().0).or_default().insert(function.signature())
+    }
+
+    pub fn add_event(&mut self, event: &Event) -> bool {
+        if event.anonymous {
+            return false;
+        }
+        self.events.entry(event.selector()).or_default().insert(event_signature(event))
+    }
+
+    pub fn add_abi(&mut self, abi: &JsonAbi) -> usize {
+        let functions = abi.functions().filter(|f| self.add_function(f)).count();
+        functions + abi.events().filter(|e| self.add_event(e)).count()
+    }
+
+    /// Adds every ABI found under `dir` (forge artifacts, truffle/hardhat builds); a missing folder adds nothing
+    pub fn seed_from_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize> {
+        if !dir.as_ref().exists() {
+            return Ok(0);
+        }
+
+        let mut added = 0;
+        for entry in scan_artifacts(dir)? {
+            let Ok(content) = fs::read_to_string(&entry.path) else { continue };
+            let Some(abi) = serde_json::from_str::<serde_json::Value>(&content)
+                .ok()
+                .and_then(|json| serde_json::from_value::<JsonAbi>(json.get("abi")?.clone()).ok())
+            else {
+                continue;
+            };
+            added += self.add_abi(&abi);
+        }
+
+        Ok(added)
+    }
+
+    /// Imports a text dump and returns how many new signatures it added
+    ///
+    /// Accepts one signature per line, optionally preceded by its selector or topic
+    /// (`0xa9059cbb transfer(address,uint256)`). Lines whose hash does not match are skipped.
+    pub fn import_text(&mut self, content: &str) -> usize {
+        let mut added = 0;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (hash, signature) = match line.split_once(|c: char| c.is_whitespace() || c == ',') {
+                Some((hash, rest)) if hash.starts_with("0x") => (Some(hash.to_lowercase()), rest.trim()),
+                _ => (None, line),
+            };
+
+            let is_event = signature.starts_with("event ") || hash.as_ref().is_some_and(|h| h.len() == 66);
+            if is_event {
+                let text = if signature.starts_with("event ") { signature.to_string() } else { format!("event {}", signature) };
+                let Ok(event) = Event::parse(&text) else { continue };
+                if hash.is_some_and(|h| h != format!("{:#x}", event.selector())) {
+                    continue;
+                }
+                added += self.add_event(&event) as usize;
+            } else {
+                let text = signature.strip_prefix("function ").unwrap_or(signature);
+                let Ok(function) = Function::parse(text) else { continue };
+                if hash.is_some_and(|h| h != format!("0x{}", hex::encode(function.selector()))) {
+                    continue;
+                }
+                added += self.add_function(&function) as usize;
+            }
+        }
+
+        added
+    }
+
+    /// Candidate function signatures for a 4-byte selector
+    pub fn lookup_function(&self, selector: [u8; 4]) -> Vec<String> {
+        self.functions.get(&selector).map(|s| s.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Candidate event signatures for a topic
+    pub fn lookup_event(&self, topic: B256) -> Vec<String> {
+        self.events.get(&topic).map(|s| s.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Every candidate that decodes `calldata`; exact re-encodings come first
+    pub fn decode_call(&self, calldata: &[u8]) -> Vec<DecodedCall> {
+        let Some(selector) = calldata.get(..4).and_then(|s| <[u8; 4]>::try_from(s).ok()) else {
+            return Vec::new();
+        };
+
+        let mut decoded: Vec<(bool, DecodedCall)> = self
+            .lookup_function(selector)
+            .iter()
+            .filter_map(|signature| {
+                let function = Function::parse(signature).ok()?;
+                let values = function.abi_decode_input(&calldata[4..], true).ok()?;
+                let exact = function.abi_encode_input(&values).ok()? == calldata;
+
+                let args = function
+                    .inputs
+                    .iter()
+                    .zip(&values)
+                    .map(|(param, value)| (param.name.clone(), param.ty.clone(), Codec::format_value(value, &param.ty)))
+                    .collect();
+                Some((exact, DecodedCall { contract: None, signature: function.signature(), args }))
+            })
+            .collect();
+
+        decoded.sort_by_key(|(exact, _)| !exact);
+        decoded.into_iter().map(|(_, call)| call).collect()
+    }
+
+    /// Every candidate that decodes the log
+    pub fn decode_log(&self, topics: &[B256], data: &[u8]) -> Vec<DecodedLog> {
+        let Some(topic) = topics.first() else {
+            return Vec::new();
+        };
+
+        self.lookup_event(*topic)
+            .iter()
+            .filter_map(|signature| Event::parse(&format!("event {}", signature)).ok())
+            .flat_map(|event| indexed_variants(&event, topics.len() - 1))
+            .filter_map(|event| decode_event(&event, topics, data))
+            .collect()
+    }
+}
+
+/// `Name(type indexed,type)`, keeping the indexed markers needed to decode topics
+fn event_signature(event: &Event) -> String {
+    let params: Vec<String> = event
+        .inputs
+        .iter()
+        .map(|p| if p.indexed { format!("{} indexed", p.selector_type()) } else { p.selector_type().into_owned() })
+        .collect();
+    format!("{}({})", event.name, params.join(","))
+}
+
+/// `event` itself when it marks indexed parameters, otherwise every way of indexing `count` of them,
+/// starting with the leading parameters as that is how events are usually declared
+fn indexed_variants(event: &Event, count: usize) -> Vec<Event> {
+    if event.inputs.iter().any(|p| p.indexed) || count == 0 {
+        return vec![event.clone()];
+    }
+    if count > event.inputs.len() {
+        return Vec::new();
+    }
+
+    let mut variants = Vec::new();
+    let mut chosen = Vec::new();
+    choose(event.inputs.len(), count, 0, &mut chosen, &mut |indices| {
+        let mut variant = event.clone();
+        for (i, param) in variant.inputs.iter_mut().enumerate() {
+            param.indexed = indices.contains(&i);
+        }
+        variants.push(variant);
+    });
+    variants
+}
+
+fn choose(n: usize, k: usize, start: usize, chosen: &mut Vec<usize>, f: &mut impl FnMut(&[usize])) {
+    if chosen.len() == k {
+        f(chosen);
+        return;
+    }
+    for i in start..n {
+        chosen.push(i);
+        choose(n, k, i + 1, chosen, f);
+        chosen.pop();
+    }
+}
+
+pub(crate) fn decode_event(event: &Event, topics: &[B256], data: &[u8]) -> Option<DecodedLog> {
+    let decoded = event.decode_log_parts(topics.iter().copied(), data, true).ok()?;
+
+    let mut indexed = decoded.indexed.into_iter();
+    let mut body = decoded.body.into_iter();
+    let args = event
+        .inputs
+        .iter()
+        .map(|param: &EventParam| {
+            let value = if param.indexed { indexed.next() } else { body.next() }?;
+            Some((param.name.clone(), param.ty.clone(), Codec::format_value(&value, &param.ty)))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(DecodedLog { contract: None, signature: event.signature(), args })
+}
+
+/// Topic of an event signature string, as stored in the database
+pub fn topic_of(signature: &str) -> B256 {
+    keccak256(signature.replace(" indexed", "").as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_lookup_and_disambiguate() {
+        let mut db = SignatureDb::new();
+        let added = db.import_text(
+            "# dump\n\
+             0xa9059cbb transfer(address,uint256)\n\
+             0xa9059cbb wrong(uint256)\n\
+             function approve(address,uint256)\n\
+             event Transfer(address,address,uint256)\n",
+        );
+        assert_eq!(added, 3);
+        assert_eq!(db.lookup_function([0xa9, 0x05, 0x9c, 0xbb]), vec!["transfer(address,uint256)"]);
+
+        let calldata = hex::decode(concat!(
+            "a9059cbb",
+            "000000000000000000000000cd2a3d9f938e13cd947ec05abc7fe734df8dd826",
+            "0000000000000000000000000000000000000000000000000000000000000064"
+        ))
+        .unwrap();
+        assert_eq!(db.decode_call(&calldata)[0].args[1].2, "100");
+
+        // Without indexed markers every split with the right topic count is tried, leading parameters first
+        let topic = topic_of("Transfer(address,address,uint256)");
+        let from = B256::left_padding_from(&[0x11; 20]);
+        let to = B256::left_padding_from(&[0x22; 20]);
+        let logs = db.decode_log(&[topic, from, to], B256::left_padding_from(&[0x64]).as_slice());
+        assert_eq!(logs.len(), 3);
+        assert_eq!(logs[0].args[0].2, format!("0x{}", "11".repeat(20)));
+        assert_eq!(logs[0].args[2].2, "100");
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let path = std::env::temp_dir().join(format!("tx-signatures-{}.txt", std::process::id()));
+        let mut db = SignatureDb::load(&path).unwrap();
+        db.import_text("event Approval(address indexed,address indexed,uint256)\nbalanceOf(address)");
+        db.save().unwrap();
+
+        let reloaded = SignatureDb::load(&path).unwrap();
+        assert_eq!(reloaded.len(), 2);
+        assert_eq!(
+            reloaded.lookup_event(topic_of("Approval(address,address,uint256)")),
+            vec!["Approval(address indexed,address indexed,uint256)"]
+        );
+
+        fs::remove_file(&path).ok();
+    }
+}
@@ -6,3 +6,8 @@ pub mod compiler;
 pub mod invoker;
 pub mod network;
 pub mod stress;
+pub mod registry;
+pub mod artifacts;
+pub mod transaction;
+pub mod account;
+pub mod project;
@@ -2,10 +2,18 @@ use ethers::prelude::*;
 use ethers::types::transaction::eip2718::TypedTransaction;
 use ethers::providers::Middleware;
 use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::networks::Network;
+
+/// `eth_chainId` of every RPC URL used so far, each endpoint is asked once per process
+static CHAIN_IDS: OnceLock<Mutex<HashMap<String, u64>>> = OnceLock::new();
 
 #[derive(Clone)]
 pub struct HttpClient {
     provider: Provider<Http>,
+    rpc_url: String,
 }
 
 impl HttpClient {
@@ -13,7 +21,52 @@ impl HttpClient {
         let provider = Provider::<Http>::try_from(rpc_url)
             .map_err(|e| anyhow!("Failed to create HTTP provider: {}", e))?;
 
-        Ok(HttpClient { provider })
+        Ok(HttpClient { provider, rpc_url: rpc_url.to_string() })
+    }
+
+    /// Client for signing on `network`, fails unless `rpc_url` serves the network's chain id
+    /// so nothing signed for one chain is broadcast to another
+    pub async fn for_network(rpc_url: &str, network: &Network) -> Result<Self> {
+        let client = Self::new(rpc_url).await?;
+        client.ensure_chain_id(network).await?;
+        Ok(client)
+    }
+
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    /// Chain id served by the endpoint, cached after the first request
+    pub async fn chain_id(&self) -> Result<u64> {
+        let cache = CHAIN_IDS.get_or_init(|| Mutex::new(HashMap::new()));
+        if let Some(chain_id) = cache.lock().ok().and_then(|ids| ids.get(&self.rpc_url).copied()) {
+            return Ok(chain_id);
+        }
+
+        let chain_id = Middleware::get_chainid(&self.provider)
+            .await
+            .map_err(|e| anyhow!("Failed to get chain id from {}: {}", self.rpc_url, e))?
+            .as_u64();
+
+        if let Ok(mut ids) = cache.lock() {
+            ids.insert(self.rpc_url.clone(), chain_id);
+        }
+        Ok(chain_id)
+    }
+
+    /// Fails unless the endpoint serves the chain `network` is configured with
+    async fn ensure_chain_id(&self, network: &Network) -> Result<()> {
+        let served = self.chain_id().await?;
+        if served != network.chain_id {
+            anyhow::bail!(
+                "Chain id mismatch on network '{}': configured {} but {} serves {}, refusing to sign",
+                network.id,
+                network.chain_id,
+                self.rpc_url,
+                served
+            );
+        }
+        Ok(())
     }
 
     pub fn get_provider(&self) -> &Provider<Http> {
@@ -38,6 +91,87 @@ impl HttpClient {
             .map_err(|e| anyhow!("Failed to get transaction receipt: {}", e))
     }
 
+    pub async fn get_transaction(&self, tx_hash: H256) -> Result<Option<Transaction>> {
+        Middleware::get_transaction(&self.provider, tx_hash)
+            .await
+            .map_err(|e| anyhow!("Failed to get transaction: {}", e))
+    }
+
+    pub async fn get_block_number(&self) -> Result<U64> {
+        Middleware::get_block_number(&self.provider)
+            .await
+            .map_err(|e| anyhow!("Failed to get block number: {}", e))
+    }
+
+    pub async fn get_balance(&self, address: Address) -> Result<U256> {
+        Middleware::get_balance(&self.provider, address, None)
+            .await
+            .map_err(|e| anyhow!("Failed to get balance: {}", e))
+    }
+
+    /// Nonce of `address` at `block`, e.g. `BlockNumber::Pending` to include queued transactions
+    pub async fn get_transaction_count(&self, address: Address, block: BlockNumber) -> Result<U256> {
+        Middleware::get_transaction_count(&self.provider, address, Some(block.into()))
+            .await
+            .map_err(|e| anyhow!("Failed to get nonce: {}", e))
+    }
+
+    pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
+        Middleware::get_logs(&self.provider, filter)
+            .await
+            .map_err(|e| anyhow!("Failed to get logs: {}", e))
+    }
+
+    /// Logs matching `filter` between two blocks, queried `chunk_size` blocks at a time
+    ///
+    /// Chunks the RPC rejects (too many results or too wide a range) are split in half and retried.
+    pub async fn get_logs_in_range(&self, filter: &Filter, from_block: u64, to_block: u64, chunk_size: u64) -> Result<Vec<Log>> {
+        let mut logs = Vec::new();
+        let mut start = from_block;
+        let mut size = chunk_size.max(1);
+
+        while start <= to_block {
+            let end = start.saturating_add(size - 1).min(to_block);
+            let chunk = filter.clone().from_block(start).to_block(end);
+
+            match self.get_logs(&chunk).await {
+                Ok(found) => {
+                    logs.extend(found);
+                    start = end + 1;
+                    size = chunk_size.max(1);
+                }
+                Err(_) if size > 1 => size /= 2,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(logs)
+    }
+
+    pub async fn get_storage_at(&self, address: Address, slot: H256) -> Result<H256> {
+        Middleware::get_storage_at(&self.provider, address, slot, None)
+            .await
+            .map_err(|e| anyhow!("Failed to read storage slot: {}", e))
+    }
+
+    pub async fn get_code(&self, address: Address) -> Result<Bytes> {
+        Middleware::get_code(&self.provider, address, None)
+            .await
+            .map_err(|e| anyhow!("Failed to get code: {}", e))
+    }
+
+    /// Replaces the code at `address` through the anvil, hardhat or ganache dev RPCs
+    pub async fn set_code(&self, address: Address, code: &Bytes) -> Result<()> {
+        let mut last_error = None;
+        for method in ["anvil_setCode", "hardhat_setCode", "evm_setAccountCode"] {
+            match self.provider.request::<_, ()>(method, (address, code.clone())).await {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(anyhow!("Failed to set code: {}", last_error.map(|e| e.to_string()).unwrap_or_default()))
+    }
+
     pub async fn send_raw_transaction(&self, tx: &Bytes) -> Result<TxHash> {
         Middleware::send_raw_transaction(&self.provider, tx.clone())
             .await
@@ -45,3 +179,37 @@ impl HttpClient {
             .map_err(|e| anyhow!("Failed to send raw transaction: {}", e))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::networks::{BlockExplorer, Currency};
+
+    #[test]
+    fn test_chain_id_mismatch_is_refused() {
+        let rpc_url = "http://127.0.0.1:1/chain-id-test";
+        CHAIN_IDS.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap().insert(rpc_url.to_string(), 1);
+
+        let mut network = Network {
+            id: "testnet_sepolia".to_string(),
+            name: "Sepolia".to_string(),
+            chain_id: 11155111,
+            rpc: vec![rpc_url.to_string()],
+            ws_rpc: Vec::new(),
+            currency: Currency { name: "Ether".to_string(), symbol: "ETH".to_string(), decimals: 18 },
+            block_explorer: BlockExplorer { url: String::new() },
+        };
+
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        rt.block_on(async {
+            let err = HttpClient::for_network(rpc_url, &network).await.err().unwrap();
+            assert_eq!(
+                err.to_string(),
+                "Chain id mismatch on network 'testnet_sepolia': configured 11155111 but http://127.0.0.1:1/chain-id-test serves 1, refusing to sign"
+            );
+
+            network.chain_id = 1;
+            assert!(HttpClient::for_network(rpc_url, &network).await.is_ok());
+        });
+    }
+}
@@ -1,5 +1,7 @@
 pub mod http_client;
+pub mod probe;
 pub mod websocket_client;
 
 pub use http_client::HttpClient;
+pub use probe::{fetch_chain_id, probe_rpc, probe_ws, RpcProbe};
 pub use websocket_client::WebSocketClient;
@@ -0,0 +1,71 @@
+use ethers::prelude::*;
+use anyhow::{anyhow, Result};
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+/// How long a single probe may take before the endpoint counts as unreachable
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What an HTTP RPC endpoint reported when probed
+#[derive(Debug, Clone)]
+pub struct RpcProbe {
+    pub url: String,
+    pub chain_id: u64,
+    pub block_number: u64,
+    /// Latest block carries a base fee
+    pub eip1559: bool,
+    pub latency: Duration,
+}
+
+/// `eth_chainId` of the endpoint
+pub async fn fetch_chain_id(url: &str) -> Result<u64> {
+    let provider = Provider::<Http>::try_from(url)
+        .map_err(|e| anyhow!("Invalid RPC URL {}: {}", url, e))?;
+
+    let chain_id = timeout(PROBE_TIMEOUT, provider.get_chainid())
+        .await
+        .map_err(|_| anyhow!("{} did not answer within {}s", url, PROBE_TIMEOUT.as_secs()))?
+        .map_err(|e| anyhow!("Failed to get chain id from {}: {}", url, e))?;
+
+    Ok(chain_id.as_u64())
+}
+
+pub async fn probe_rpc(url: &str) -> Result<RpcProbe> {
+    let provider = Provider::<Http>::try_from(url)
+        .map_err(|e| anyhow!("Invalid RPC URL {}: {}", url, e))?;
+
+    let start = Instant::now();
+    let probe = async {
+        let chain_id = provider.get_chainid().await?;
+        let block = provider.get_block(BlockNumber::Latest).await?;
+        Ok::<_, ProviderError>((chain_id, block))
+    };
+    let (chain_id, block) = timeout(PROBE_TIMEOUT, probe)
+        .await
+        .map_err(|_| anyhow!("no answer within {}s", PROBE_TIMEOUT.as_secs()))?
+        .map_err(|e| anyhow!("{}", e))?;
+    let block = block.ok_or_else(|| anyhow!("no latest block"))?;
+
+    Ok(RpcProbe {
+        url: url.to_string(),
+        chain_id: chain_id.as_u64(),
+        block_number: block.number.unwrap_or_default().as_u64(),
+        eip1559: block.base_fee_per_gas.is_some(),
+        latency: start.elapsed(),
+    })
+}
+
+/// Connects to a WebSocket endpoint and asks for its chain id, returning the round-trip time
+pub async fn probe_ws(url: &str) -> Result<(u64, Duration)> {
+    let start = Instant::now();
+    let probe = async {
+        let provider = Provider::<Ws>::connect(url).await?;
+        provider.get_chainid().await
+    };
+    let chain_id = timeout(PROBE_TIMEOUT, probe)
+        .await
+        .map_err(|_| anyhow!("no answer within {}s", PROBE_TIMEOUT.as_secs()))?
+        .map_err(|e| anyhow!("{}", e))?;
+
+    Ok((chain_id.as_u64(), start.elapsed()))
+}
@@ -1,6 +1,44 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fs;
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use anyhow::{anyhow, Result};
+
+/// Default network list, compiled into the binary so it works wherever it is installed
+const DEFAULT_NETWORKS: &str = include_str!("../../../data/networks.json");
+
+/// Project-local overrides, next to tx.toml in the working directory
+pub const PROJECT_NETWORKS_FILE: &str = "networks.json";
+
+/// Path to a networks file, or the JSON array itself
+pub const NETWORKS_ENV: &str = "TX_NETWORKS";
+
+/// Id of the network standing for `--rpc-url` when the requested id is not configured
+pub const CUSTOM_NETWORK_ID: &str = "custom";
+
+static RPC_OVERRIDE: OnceLock<RpcOverride> = OnceLock::new();
+
+/// An endpoint given on the command line that replaces every configured RPC
+#[derive(Debug, Clone)]
+pub struct RpcOverride {
+    pub rpc_url: String,
+    pub chain_id: u64,
+}
+
+/// Points every network at `rpc_url` for the rest of the process
+pub fn set_rpc_override(rpc_url: &str, chain_id: u64) -> Result<()> {
+    if !rpc_url.starts_with("http://") && !rpc_url.starts_with("https://") {
+        anyhow::bail!("RPC URL {} is not http(s)", rpc_url);
+    }
+    RPC_OVERRIDE
+        .set(RpcOverride { rpc_url: rpc_url.to_string(), chain_id })
+        .map_err(|_| anyhow!("RPC override is already set"))
+}
+
+pub fn rpc_override() -> Option<&'static RpcOverride> {
+    RPC_OVERRIDE.get()
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Network {
@@ -28,20 +66,298 @@ pub struct BlockExplorer {
     pub url: String,
 }
 
-pub fn load_networks() -> Result<Vec<Network>> {
-    let manifest_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
-    let project_root = manifest_dir.parent().unwrap().parent().unwrap();
-    let networks_path = project_root.join("data/networks.json");
-    
-    let content = fs::read_to_string(&networks_path)
-        .map_err(|e| anyhow::anyhow!("Failed to read networks.json at {}: {}", networks_path.display(), e))?;
+impl Network {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("name is empty");
+        }
+        if self.chain_id == 0 {
+            anyhow::bail!("chainId must not be 0");
+        }
+        if self.rpc.is_empty() {
+            anyhow::bail!("no rpc URL");
+        }
+        if let Some(url) = self.rpc.iter().find(|u| !u.starts_with("http://") && !u.starts_with("https://")) {
+            anyhow::bail!("rpc URL {} is not http(s)", url);
+        }
+        if let Some(url) = self.ws_rpc.iter().find(|u| !u.starts_with("ws://") && !u.starts_with("wss://")) {
+            anyhow::bail!("wsRpc URL {} is not ws(s)", url);
+        }
+        Ok(())
+    }
+}
+
+/// User-wide overrides, `$XDG_CONFIG_HOME/tx-tx-tx/networks.json` (or under `~/.config`)
+pub fn user_networks_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("tx-tx-tx").join("networks.json"))
+}
+
+/// Override layers in increasing priority, as `(source, JSON)`
+fn override_layers() -> Result<Vec<(String, String)>> {
+    let mut layers = Vec::new();
+
+    let files = user_networks_path().into_iter().chain([PathBuf::from(PROJECT_NETWORKS_FILE)]);
+    for path in files {
+        if path.exists() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+            layers.push((path.display().to_string(), content));
+        }
+    }
+
+    if let Ok(value) = std::env::var(NETWORKS_ENV)
+        && !value.trim().is_empty()
+    {
+        if value.trim_start().starts_with('[') {
+            layers.push((NETWORKS_ENV.to_string(), value));
+        } else {
+            let content = fs::read_to_string(&value)
+                .map_err(|e| anyhow!("Failed to read {} file {}: {}", NETWORKS_ENV, value, e))?;
+            layers.push((format!("{} ({})", NETWORKS_ENV, value), content));
+        }
+    }
+
+    Ok(layers)
+}
+
+/// Merges network layers by `id`: later entries override fields of earlier ones with the same id,
+/// new ids are appended
+pub fn merge_networks(layers: &[(String, String)]) -> Result<Vec<Network>> {
+    // Each entry remembers the last layer that touched it, for error messages
+    let mut merged: Vec<(String, serde_json::Map<String, Value>)> = Vec::new();
+
+    for (source, content) in layers {
+        let entries: Vec<Value> = serde_json::from_str(content)
+            .map_err(|e| anyhow!("Failed to parse networks in {}: {}", source, e))?;
+
+        for (i, entry) in entries.into_iter().enumerate() {
+            let Value::Object(entry) = entry else {
+                anyhow::bail!("Invalid network #{} in {}: expected an object", i + 1, source);
+            };
+            let id = match entry.get("id").and_then(|id| id.as_str()) {
+                Some(id) if !id.trim().is_empty() => id.to_string(),
+                _ => anyhow::bail!("Invalid network #{} in {}: missing id", i + 1, source),
+            };
+
+            match merged.iter_mut().find(|(_, existing)| existing.get("id").and_then(|v| v.as_str()) == Some(&id)) {
+                Some((existing_source, existing)) => {
+                    existing.extend(entry);
+                    *existing_source = source.clone();
+                }
+                None => merged.push((source.clone(), entry)),
+            }
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(source, entry)| {
+            let id = entry.get("id").and_then(|id| id.as_str()).unwrap_or_default().to_string();
+            let network: Network = serde_json::from_value(Value::Object(entry))
+                .map_err(|e| anyhow!("Invalid network '{}' in {}: {}", id, source, e))?;
+            network.validate().map_err(|e| anyhow!("Invalid network '{}' in {}: {}", id, source, e))?;
+            Ok(network)
+        })
+        .collect()
+}
+
+/// Sends the networks of the override's chain to its endpoint and adds the `custom` network
+/// for unlisted ids; networks of other chains keep their own endpoints
+fn apply_rpc_override(networks: &mut Vec<Network>, rpc_override: &RpcOverride) {
+    for network in networks.iter_mut().filter(|n| n.chain_id == rpc_override.chain_id) {
+        network.rpc = vec![rpc_override.rpc_url.clone()];
+        network.ws_rpc.clear();
+    }
+
+    if !networks.iter().any(|n| n.id == CUSTOM_NETWORK_ID) {
+        networks.push(Network {
+            id: CUSTOM_NETWORK_ID.to_string(),
+            name: format!("Custom RPC ({})", rpc_override.rpc_url),
+            chain_id: rpc_override.chain_id,
+            rpc: vec![rpc_override.rpc_url.clone()],
+            ws_rpc: Vec::new(),
+            currency: Currency { name: "Ether".to_string(), symbol: "ETH".to_string(), decimals: 18 },
+            block_explorer: BlockExplorer { url: String::new() },
+        });
+    }
+}
 
-    let networks: Vec<Network> = serde_json::from_str(&content)
-        .map_err(|e| anyhow::anyhow!("Failed to parse networks.json: {}", e))?;
+/// Built-in networks, overridden by the user config, then `networks.json` in the working
+/// directory, then `TX_NETWORKS`; a `--rpc-url` override applies on top
+pub fn load_networks() -> Result<Vec<Network>> {
+    let mut layers = vec![("built-in networks".to_string(), DEFAULT_NETWORKS.to_string())];
+    layers.extend(override_layers()?);
+    let mut networks = merge_networks(&layers)?;
 
+    if let Some(rpc_override) = rpc_override() {
+        apply_rpc_override(&mut networks, rpc_override);
+    }
     Ok(networks)
 }
 
+/// Looks a network up by id; with a `--rpc-url` override any unlisted id gets the `custom` network,
+/// and networks of another chain than the override are not found
 pub fn get_network_by_id<'a>(networks: &'a [Network], id: &str) -> Option<&'a Network> {
-    networks.iter().find(|n| n.id == id)
+    match (networks.iter().find(|n| n.id == id), rpc_override()) {
+        (Some(network), Some(rpc_override)) if network.chain_id != rpc_override.chain_id => None,
+        (Some(network), _) => Some(network),
+        (None, Some(_)) => networks.iter().find(|n| n.id == CUSTOM_NETWORK_ID),
+        (None, None) => None,
+    }
+}
+
+/// Fails when `--rpc-url` serves another chain than the configured network `id`, so nothing meant
+/// for that network is sent to or recorded against the wrong chain
+pub fn check_rpc_override(id: &str) -> Result<()> {
+    let Some(rpc_override) = rpc_override() else {
+        return Ok(());
+    };
+    if let Some(network) = load_networks()?.iter().find(|n| n.id == id)
+        && network.chain_id != rpc_override.chain_id
+    {
+        anyhow::bail!(
+            "Network '{}' has chain id {} but --rpc-url {} serves {}, use --network {} or another id",
+            id,
+            network.chain_id,
+            rpc_override.rpc_url,
+            rpc_override.chain_id,
+            CUSTOM_NETWORK_ID
+        );
+    }
+    Ok(())
+}
+
+/// Whether `id` ships with the binary, such entries can be overridden but not removed
+pub fn is_builtin_network(id: &str) -> bool {
+    serde_json::from_str::<Vec<Network>>(DEFAULT_NETWORKS)
+        .map(|networks| networks.iter().any(|n| n.id == id))
+        .unwrap_or(false)
+}
+
+fn read_networks_file(path: &Path) -> Result<Vec<Value>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse networks in {}: {}", path.display(), e))
+}
+
+fn write_networks_file(path: &Path, entries: &[Value]) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| anyhow!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(entries)?;
+    fs::write(path, content + "\n").map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Adds `network` to the networks file at `path`, replacing an entry with the same id
+pub fn save_network(path: &Path, network: &Network) -> Result<()> {
+    network.validate().map_err(|e| anyhow!("Invalid network '{}': {}", network.id, e))?;
+
+    let mut entries = read_networks_file(path)?;
+    let entry = serde_json::to_value(network)?;
+    match entries.iter_mut().find(|e| e.get("id").and_then(|id| id.as_str()) == Some(&network.id)) {
+        Some(existing) => *existing = entry,
+        None => entries.push(entry),
+    }
+    write_networks_file(path, &entries)
+}
+
+/// Removes the entry with `id` from the networks file at `path`, false when it had none
+pub fn remove_network(path: &Path, id: &str) -> Result<bool> {
+    let mut entries = read_networks_file(path)?;
+    let before = entries.len();
+    entries.retain(|e| e.get("id").and_then(|v| v.as_str()) != Some(id));
+    if entries.len() == before {
+        return Ok(false);
+    }
+    write_networks_file(path, &entries)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(source: &str, content: &str) -> (String, String) {
+        (source.to_string(), content.to_string())
+    }
+
+    #[test]
+    fn test_merge_overrides_by_id() {
+        let local = r#"[
+            { "id": "testnet_sepolia", "rpc": ["http://127.0.0.1:8545"] },
+            {
+                "id": "anvil", "name": "Anvil", "chainId": 31337,
+                "rpc": ["http://127.0.0.1:8545"], "wsRpc": [],
+                "currency": { "name": "Ether", "symbol": "ETH", "decimals": 18 },
+                "blockExplorer": { "url": "" }
+            }
+        ]"#;
+
+        let networks = merge_networks(&[layer("built-in", DEFAULT_NETWORKS), layer("local", local)]).unwrap();
+        let sepolia = get_network_by_id(&networks, "testnet_sepolia").unwrap();
+        assert_eq!(sepolia.rpc, vec!["http://127.0.0.1:8545"]);
+        assert_eq!(sepolia.chain_id, 11155111);
+        assert_eq!(networks.last().unwrap().id, "anvil");
+    }
+
+    #[test]
+    fn test_rpc_override_keeps_other_chains() {
+        let mut networks = merge_networks(&[layer("built-in", DEFAULT_NETWORKS)]).unwrap();
+        let sepolia_rpc = get_network_by_id(&networks, "testnet_sepolia").unwrap().rpc.clone();
+        let rpc_override = RpcOverride { rpc_url: "http://127.0.0.1:8545".to_string(), chain_id: 11155111 };
+
+        apply_rpc_override(&mut networks, &rpc_override);
+        let find = |id: &str| networks.iter().find(|n| n.id == id).unwrap();
+        assert_eq!(find("testnet_sepolia").rpc, vec!["http://127.0.0.1:8545"]);
+        assert_ne!(sepolia_rpc, find("testnet_sepolia").rpc);
+        assert_eq!(find("ethereum_mainnet").chain_id, 1);
+        assert_ne!(find("ethereum_mainnet").rpc, vec!["http://127.0.0.1:8545"]);
+        assert_eq!(find(CUSTOM_NETWORK_ID).chain_id, 11155111);
+    }
+
+    #[test]
+    fn test_save_and_remove_network() {
+        let path = std::env::temp_dir().join(format!("tx-networks-{}", std::process::id())).join("networks.json");
+        let mut network = merge_networks(&[layer("built-in", DEFAULT_NETWORKS)]).unwrap().remove(0);
+        network.id = "anvil".to_string();
+        network.chain_id = 31337;
+
+        save_network(&path, &network).unwrap();
+        network.rpc = vec!["http://127.0.0.1:8545".to_string()];
+        save_network(&path, &network).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let saved = merge_networks(&[layer("file", &content)]).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].rpc, vec!["http://127.0.0.1:8545"]);
+
+        assert!(remove_network(&path, "anvil").unwrap());
+        assert!(!remove_network(&path, "anvil").unwrap());
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+
+        assert!(is_builtin_network("testnet_sepolia"));
+        assert!(!is_builtin_network("anvil"));
+    }
+
+    #[test]
+    fn test_errors_name_the_entry() {
+        let bad_chain = r#"[{ "id": "testnet_sepolia", "chainId": 0 }]"#;
+        let err = merge_networks(&[layer("built-in", DEFAULT_NETWORKS), layer("user.json", bad_chain)]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid network 'testnet_sepolia' in user.json: chainId must not be 0");
+
+        let incomplete = r#"[{ "id": "foo", "chainId": 5 }]"#;
+        let err = merge_networks(&[layer("TX_NETWORKS", incomplete)]).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid network 'foo' in TX_NETWORKS: missing field"));
+
+        let err = merge_networks(&[layer("x.json", r#"[{ "name": "no id" }]"#)]).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid network #1 in x.json: missing id");
+    }
 }
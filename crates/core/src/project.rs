@@ -0,0 +1,350 @@
+use anyhow::{anyhow, Result};
+use ethers::signers::coins_bip39::English;
+use ethers::signers::{LocalWallet, MnemonicBuilder};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::artifacts::DEFAULT_ARTIFACTS_DIR;
+use crate::registry::DEFAULT_DEPLOYMENTS_DIR;
+
+/// Project config, relative to the working directory
+pub const PROJECT_CONFIG_FILE: &str = "tx.toml";
+
+/// Profile used when `--profile` is not given; every other profile inherits from it
+pub const DEFAULT_PROFILE: &str = "default";
+
+pub const DEFAULT_CONTRACTS_DIR: &str = "contracts";
+
+const DEFAULT_PASSWORD_ENV: &str = "ETH_KEYSTORE_PASSWORD";
+const DEFAULT_MNEMONIC_ENV: &str = "ETH_MNEMONIC";
+
+static ACTIVE_PROFILE: OnceLock<Profile> = OnceLock::new();
+
+/// Where the private key of a named account comes from
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "AccountEntry")]
+pub enum Account {
+    /// Environment variable (or `.env` entry) holding the key
+    Env(String),
+    /// File holding the key, e.g. a Docker secret
+    File(PathBuf),
+    /// Encrypted JSON keystore, unlocked with the password in `password_env`
+    Keystore { path: PathBuf, password_env: String },
+    /// BIP-44 index derived from the mnemonic in `mnemonic_env`
+    Hd { index: u32, mnemonic_env: String },
+}
+
+/// `[accounts.<name>]` as written in tx.toml
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountEntry {
+    env: Option<String>,
+    file: Option<PathBuf>,
+    keystore: Option<PathBuf>,
+    password_env: Option<String>,
+    hd_index: Option<u32>,
+    mnemonic_env: Option<String>,
+}
+
+impl TryFrom<AccountEntry> for Account {
+    type Error = String;
+
+    fn try_from(entry: AccountEntry) -> std::result::Result<Self, Self::Error> {
+        let account = match (entry.env, entry.file, entry.keystore, entry.hd_index) {
+            (Some(var), None, None, None) => Account::Env(var),
+            (None, Some(path), None, None) => Account::File(path),
+            (None, None, Some(path), None) => Account::Keystore {
+                path,
+                password_env: entry.password_env.clone().unwrap_or_else(|| DEFAULT_PASSWORD_ENV.to_string()),
+            },
+            (None, None, None, Some(index)) => Account::Hd {
+                index,
+                mnemonic_env: entry.mnemonic_env.clone().unwrap_or_else(|| DEFAULT_MNEMONIC_ENV.to_string()),
+            },
+            _ => return Err("set exactly one of env, file, keystore or hd_index".to_string()),
+        };
+
+        if entry.password_env.is_some() && !matches!(account, Account::Keystore { .. }) {
+            return Err("password_env only applies to keystore accounts".to_string());
+        }
+        if entry.mnemonic_env.is_some() && !matches!(account, Account::Hd { .. }) {
+            return Err("mnemonic_env only applies to hd_index accounts".to_string());
+        }
+        Ok(account)
+    }
+}
+
+impl Account {
+    /// Hex private key of the account, without `0x`
+    pub fn private_key(&self) -> Result<String> {
+        match self {
+            Account::Env(var) => {
+                dotenvy::dotenv().ok();
+                let key = std::env::var(var).map_err(|_| anyhow!("{} not found in environment variables", var))?;
+                non_empty(key.trim(), var)
+            }
+            Account::File(path) => {
+                let key = fs::read_to_string(path)
+                    .map_err(|e| anyhow!("Failed to read secret file {}: {}", path.display(), e))?;
+                non_empty(key.trim(), &path.display().to_string())
+            }
+            Account::Keystore { path, password_env } => {
+                let password = read_env(password_env)?;
+                let wallet = LocalWallet::decrypt_keystore(path, password)
+                    .map_err(|e| anyhow!("Failed to decrypt keystore {}: {}", path.display(), e))?;
+                Ok(hex::encode(wallet.signer().to_bytes()))
+            }
+            Account::Hd { index, mnemonic_env } => derive_hd(&read_env(mnemonic_env)?, *index),
+        }
+    }
+}
+
+fn non_empty(key: &str, source: &str) -> Result<String> {
+    if key.is_empty() {
+        anyhow::bail!("Private key from {} is empty", source);
+    }
+    Ok(key.to_string())
+}
+
+fn read_env(var: &str) -> Result<String> {
+    dotenvy::dotenv().ok();
+    std::env::var(var).map_err(|_| anyhow!("{} not found in environment variables", var))
+}
+
+fn derive_hd(mnemonic: &str, index: u32) -> Result<String> {
+    let wallet = MnemonicBuilder::<English>::default()
+        .phrase(mnemonic.trim())
+        .index(index)
+        .and_then(|builder| builder.build())
+        .map_err(|e| anyhow!("Failed to derive HD account {}: {}", index, e))?;
+    Ok(hex::encode(wallet.signer().to_bytes()))
+}
+
+/// `[profile.<name>]`; unset values fall back to the default profile, then to built-in defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileSettings {
+    pub network: Option<String>,
+    pub account: Option<String>,
+    pub gas_strategy: Option<String>,
+    pub confirmations: Option<u64>,
+    pub contracts: Option<String>,
+    pub artifacts: Option<String>,
+    pub deployments: Option<String>,
+}
+
+impl ProfileSettings {
+    fn or(self, fallback: &ProfileSettings) -> ProfileSettings {
+        ProfileSettings {
+            network: self.network.or_else(|| fallback.network.clone()),
+            account: self.account.or_else(|| fallback.account.clone()),
+            gas_strategy: self.gas_strategy.or_else(|| fallback.gas_strategy.clone()),
+            confirmations: self.confirmations.or(fallback.confirmations),
+            contracts: self.contracts.or_else(|| fallback.contracts.clone()),
+            artifacts: self.artifacts.or_else(|| fallback.artifacts.clone()),
+            deployments: self.deployments.or_else(|| fallback.deployments.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub profile: HashMap<String, ProfileSettings>,
+    #[serde(default)]
+    pub accounts: HashMap<String, Account>,
+}
+
+/// The selected profile with its account resolved
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub name: String,
+    pub settings: ProfileSettings,
+    pub account: Option<(String, Account)>,
+}
+
+impl ProjectConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_toml(&content).map_err(|e| anyhow!("Invalid {}: {}", path.display(), e))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let config: ProjectConfig = toml::from_str(content)?;
+        if let Some((name, _)) = config.profile.iter().find(|(_, p)| p.confirmations == Some(0)) {
+            anyhow::bail!("profile '{}': confirmations must be at least 1", name);
+        }
+        Ok(config)
+    }
+
+    /// Merges `name` (the default profile when `None`) over the default profile;
+    /// `account` replaces the profile's account
+    pub fn profile(&self, name: Option<&str>, account: Option<&str>) -> Result<Profile> {
+        let name = name.unwrap_or(DEFAULT_PROFILE);
+        let defaults = self.profile.get(DEFAULT_PROFILE).cloned().unwrap_or_default();
+
+        let mut settings = match self.profile.get(name) {
+            Some(profile) => profile.clone().or(&defaults),
+            None if name == DEFAULT_PROFILE => defaults,
+            None => {
+                let mut known: Vec<&str> = self.profile.keys().map(String::as_str).collect();
+                known.sort();
+                anyhow::bail!("Profile '{}' not found in {} (known: {})", name, PROJECT_CONFIG_FILE, known.join(", "));
+            }
+        };
+        if let Some(account) = account {
+            settings.account = Some(account.to_string());
+        }
+
+        let account = match &settings.account {
+            Some(account) => {
+                let source = self.accounts.get(account).ok_or_else(|| {
+                    anyhow!("Account '{}' is not defined under [accounts] in {}", account, PROJECT_CONFIG_FILE)
+                })?;
+                Some((account.clone(), source.clone()))
+            }
+            None => None,
+        };
+
+        Ok(Profile { name: name.to_string(), settings, account })
+    }
+}
+
+/// Loads tx.toml from the working directory and selects the profile and account for the rest
+/// of the process; without tx.toml only the built-in defaults apply
+pub fn activate(profile: Option<&str>, account: Option<&str>) -> Result<&'static Profile> {
+    let active = if Path::new(PROJECT_CONFIG_FILE).exists() {
+        ProjectConfig::load(PROJECT_CONFIG_FILE)?.profile(profile, account)?
+    } else if profile.is_some() || account.is_some() {
+        anyhow::bail!("--profile and --account need a {} in the working directory", PROJECT_CONFIG_FILE);
+    } else {
+        Profile { name: DEFAULT_PROFILE.to_string(), ..Default::default() }
+    };
+
+    ACTIVE_PROFILE.set(active).map_err(|_| anyhow!("Project profile is already active"))?;
+    Ok(active_profile().expect("profile was just set"))
+}
+
+pub fn active_profile() -> Option<&'static Profile> {
+    ACTIVE_PROFILE.get()
+}
+
+fn setting(get: impl Fn(&ProfileSettings) -> Option<&String>) -> Option<&'static str> {
+    active_profile().and_then(|p| get(&p.settings)).map(String::as_str)
+}
+
+/// Network used when a command gets no `--network`
+pub fn default_network() -> Option<&'static str> {
+    setting(|s| s.network.as_ref())
+}
+
+/// Gas strategy used when a command gets no `--gas-strategy`
+pub fn default_gas_strategy() -> &'static str {
+    setting(|s| s.gas_strategy.as_ref()).unwrap_or("standard")
+}
+
+/// Blocks a sent transaction must be buried under before it counts as mined
+pub fn confirmations() -> usize {
+    active_profile().and_then(|p| p.settings.confirmations).unwrap_or(1) as usize
+}
+
+/// Account selected by the profile or `--account`
+pub fn active_account() -> Option<&'static (String, Account)> {
+    active_profile().and_then(|p| p.account.as_ref())
+}
+
+pub fn contracts_dir() -> &'static str {
+    setting(|s| s.contracts.as_ref()).unwrap_or(DEFAULT_CONTRACTS_DIR)
+}
+
+pub fn artifacts_dir() -> &'static str {
+    setting(|s| s.artifacts.as_ref()).unwrap_or(DEFAULT_ARTIFACTS_DIR)
+}
+
+pub fn deployments_dir() -> &'static str {
+    setting(|s| s.deployments.as_ref()).unwrap_or(DEFAULT_DEPLOYMENTS_DIR)
+}
+
+/// Registry file of `network_id` inside the deployments directory
+pub fn deployments_file(network_id: &str) -> String {
+    format!("{}/{}.json", deployments_dir(), network_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [profile.default]
+        network = "anvil"
+        account = "deployer"
+        artifacts = "out"
+
+        [profile.sepolia]
+        network = "testnet_sepolia"
+        account = "treasury"
+        gas_strategy = "fast"
+        confirmations = 3
+
+        [accounts.deployer]
+        env = "DEPLOYER_KEY"
+
+        [accounts.treasury]
+        keystore = "keys/treasury.json"
+
+        [accounts.hot]
+        hd_index = 2
+        mnemonic_env = "HOT_MNEMONIC"
+    "#;
+
+    #[test]
+    fn test_profile_inherits_default() {
+        let config = ProjectConfig::from_toml(CONFIG).unwrap();
+
+        let default = config.profile(None, None).unwrap();
+        assert_eq!(default.settings.network.as_deref(), Some("anvil"));
+        assert_eq!(default.account, Some(("deployer".to_string(), Account::Env("DEPLOYER_KEY".to_string()))));
+
+        let sepolia = config.profile(Some("sepolia"), Some("hot")).unwrap();
+        assert_eq!(sepolia.settings.network.as_deref(), Some("testnet_sepolia"));
+        assert_eq!(sepolia.settings.artifacts.as_deref(), Some("out"));
+        assert_eq!(sepolia.settings.confirmations, Some(3));
+        assert_eq!(
+            sepolia.account.unwrap().1,
+            Account::Hd { index: 2, mnemonic_env: "HOT_MNEMONIC".to_string() }
+        );
+
+        let treasury = config.profile(Some("sepolia"), None).unwrap().account.unwrap().1;
+        assert_eq!(
+            treasury,
+            Account::Keystore { path: PathBuf::from("keys/treasury.json"), password_env: DEFAULT_PASSWORD_ENV.to_string() }
+        );
+
+        assert!(config.profile(Some("mainnet"), None).is_err());
+        assert!(config.profile(None, Some("unknown")).is_err());
+    }
+
+    #[test]
+    fn test_invalid_accounts_are_rejected() {
+        assert!(ProjectConfig::from_toml("[accounts.a]\nenv = \"A\"\nfile = \"a.key\"").is_err());
+        assert!(ProjectConfig::from_toml("[accounts.a]\nenv = \"A\"\nmnemonic_env = \"M\"").is_err());
+        assert!(ProjectConfig::from_toml("[accounts.a]").is_err());
+        assert!(ProjectConfig::from_toml("[profile.default]\nconfirmations = 0").is_err());
+        assert!(ProjectConfig::from_toml("[profile.default]\nnetwrok = \"anvil\"").is_err());
+    }
+
+    #[test]
+    fn test_hd_derivation() {
+        let mnemonic = "test test test test test test test test test test test junk";
+        assert_eq!(
+            derive_hd(mnemonic, 0).unwrap(),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+    }
+}
@@ -0,0 +1,500 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_DEPLOYMENTS_DIR: &str = "deployments";
+
+const REGISTRY_FORMAT: u32 = 1;
+
+/// One deployed version of a registered contract
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub version: u32,
+    /// Artifact the contract was deployed from, `Name` or `path/File.sol:Name`
+    pub artifact: String,
+    pub address: String,
+    pub tx_hash: String,
+    pub deployer: String,
+    pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytecode_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abi_hash: Option<String>,
+    /// Libraries linked into the bytecode, `path/File.sol:Library -> address`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub libraries: BTreeMap<String, String>,
+    /// Transactions of the post-deploy calls a deployment plan has made on this deployment, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<String>,
+}
+
+/// Data needed to register a new deployment, the version is assigned by the registry
+#[derive(Debug, Clone)]
+pub struct NewDeployment {
+    pub artifact: String,
+    pub address: String,
+    pub tx_hash: String,
+    pub deployer: String,
+    pub timestamp: u64,
+    pub bytecode_hash: Option<String>,
+    pub abi_hash: Option<String>,
+    pub libraries: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractHistory {
+    /// Named pointers to versions, e.g. `stable -> 2`
+    #[serde(default)]
+    pub tags: BTreeMap<String, u32>,
+    pub deployments: Vec<RegistryEntry>,
+    /// Version the next deployment gets, so removed versions are never reused
+    #[serde(default)]
+    pub next_version: u32,
+}
+
+impl ContractHistory {
+    pub fn latest(&self) -> Option<&RegistryEntry> {
+        self.deployments.iter().max_by_key(|d| d.version)
+    }
+
+    pub fn get_version(&self, version: u32) -> Option<&RegistryEntry> {
+        self.deployments.iter().find(|d| d.version == version)
+    }
+
+    pub fn tags_for(&self, version: u32) -> Vec<&str> {
+        self.tags
+            .iter()
+            .filter(|(_, v)| **v == version)
+            .map(|(t, _)| t.as_str())
+            .collect()
+    }
+}
+
+/// Deployment record as written by earlier releases, a flat array per network file
+#[derive(Debug, Clone, Deserialize)]
+struct LegacyRecord {
+    contract_name: String,
+    address: String,
+    tx_hash: String,
+    deployer: String,
+    timestamp: u64,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+/// Per-network registry of deployed contracts stored in `deployments/<network>.json`
+///
+/// Contracts are keyed by name, each deployment gets an increasing version and versions
+/// can be tagged. References are written as `Name`, `Name@latest`, `Name@3` or `Name@tag`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentRegistry {
+    pub format: u32,
+    pub network: String,
+    #[serde(default)]
+    pub contracts: BTreeMap<String, ContractHistory>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl DeploymentRegistry {
+    pub fn path_for(deployments_dir: &str, network_id: &str) -> PathBuf {
+        Path::new(deployments_dir).join(format!("{}.json", network_id))
+    }
+
+    pub fn load(deployments_dir: &str, network_id: &str) -> Result<Self> {
+        Self::load_file(Self::path_for(deployments_dir, network_id), network_id)
+    }
+
+    /// Loads a registry file, returning an empty registry when it does not exist yet
+    ///
+    /// Files in the legacy flat-array format are converted in memory and written back in
+    /// the new format on the next save.
+    pub fn load_file<P: AsRef<Path>>(path: P, network_id: &str) -> Result<Self> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(DeploymentRegistry {
+                format: REGISTRY_FORMAT,
+                network: network_id.to_string(),
+                contracts: BTreeMap::new(),
+                path: path.to_path_buf(),
+            });
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read deployment registry {}: {}", path.display(), e))?;
+
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse deployment registry {}: {}", path.display(), e))?;
+
+        let mut registry = if value.is_array() {
+            let records: Vec<LegacyRecord> = serde_json::from_value(value)
+                .map_err(|e| anyhow!("Failed to parse legacy deployment file {}: {}", path.display(), e))?;
+            Self::from_legacy(network_id, records)
+        } else {
+            serde_json::from_value::<DeploymentRegistry>(value)
+                .map_err(|e| anyhow!("Failed to parse deployment registry {}: {}", path.display(), e))?
+        };
+
+        if registry.format > REGISTRY_FORMAT {
+            anyhow::bail!(
+                "Deployment registry {} uses format {}, this build supports up to {}",
+                path.display(),
+                registry.format,
+                REGISTRY_FORMAT
+            );
+        }
+
+        registry.path = path.to_path_buf();
+        Ok(registry)
+    }
+
+    fn from_legacy(network_id: &str, records: Vec<LegacyRecord>) -> Self {
+        let mut registry = DeploymentRegistry {
+            format: REGISTRY_FORMAT,
+            network: network_id.to_string(),
+            contracts: BTreeMap::new(),
+            path: PathBuf::new(),
+        };
+
+        for record in records {
+            let name = record.label.clone().unwrap_or_else(|| record.contract_name.clone());
+            registry.register(
+                &name,
+                NewDeployment {
+                    artifact: record.contract_name,
+                    address: record.address,
+                    tx_hash: record.tx_hash,
+                    deployer: record.deployer,
+                    timestamp: record.timestamp,
+                    bytecode_hash: None,
+                    abi_hash: None,
+                    libraries: BTreeMap::new(),
+                },
+            );
+        }
+
+        registry
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the registry to a temporary file and renames it over the original
+    pub fn save(&self) -> Result<()> {
+        write_atomic(&self.path, &serde_json::to_string_pretty(self)?)
+    }
+
+    /// Adds a deployment as the next version of `name` and returns that version
+    pub fn register(&mut self, name: &str, deployment: NewDeployment) -> u32 {
+        let history = self.contracts.entry(name.to_string()).or_default();
+        // Registries written before `next_version` existed only know their latest version
+        let version = history.next_version.max(history.latest().map(|d| d.version + 1).unwrap_or(1));
+        history.next_version = version + 1;
+
+        history.deployments.push(RegistryEntry {
+            version,
+            artifact: deployment.artifact,
+            address: deployment.address,
+            tx_hash: deployment.tx_hash,
+            deployer: deployment.deployer,
+            timestamp: deployment.timestamp,
+            bytecode_hash: deployment.bytecode_hash,
+            abi_hash: deployment.abi_hash,
+            libraries: deployment.libraries,
+            calls: Vec::new(),
+        });
+
+        version
+    }
+
+    /// Appends the transaction of a completed post-deploy call to version `version` of `name`
+    pub fn record_call(&mut self, name: &str, version: u32, tx_hash: &str) -> Result<()> {
+        let entry = self
+            .contracts
+            .get_mut(name)
+            .and_then(|h| h.deployments.iter_mut().find(|d| d.version == version))
+            .ok_or_else(|| anyhow!("Version {} of {} not found", version, name))?;
+
+        entry.calls.push(tx_hash.to_string());
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ContractHistory> {
+        self.contracts.get(name)
+    }
+
+    /// Resolves `Name`, `Name@latest`, `Name@<version>` or `Name@<tag>` to a deployment
+    ///
+    /// `Name` may also be written as `path/File.sol:Name` to only consider deployments of that artifact.
+    pub fn resolve(&self, reference: &str) -> Result<(&str, &RegistryEntry)> {
+        let (name, selector) = split_reference(reference);
+        let (artifact, name) = match name.rsplit_once(':') {
+            Some((_, contract)) => (Some(name), contract),
+            None => (None, name),
+        };
+
+        let (name, history) = self
+            .contracts
+            .get_key_value(name)
+            .ok_or_else(|| anyhow!("Contract '{}' is not registered on {}", name, self.network))?;
+
+        let from_artifact = |entry: &&RegistryEntry| {
+            artifact.is_none_or(|a| entry.artifact == a || !entry.artifact.contains(':'))
+        };
+
+        let entry = match selector {
+            None | Some("latest") => history
+                .deployments
+                .iter()
+                .filter(from_artifact)
+                .max_by_key(|d| d.version),
+            Some(selector) => match history.tags.get(selector) {
+                Some(version) => history.get_version(*version),
+                None => parse_version(selector).and_then(|v| history.get_version(v)),
+            }
+            .filter(from_artifact),
+        };
+
+        let entry = entry.ok_or_else(|| {
+            anyhow!("No deployment '{}' of {} on {}", selector.unwrap_or("latest"), artifact.unwrap_or(name), self.network)
+        })?;
+
+        Ok((name.as_str(), entry))
+    }
+
+    pub fn find_by_address(&self, address: &str) -> Option<(&str, &RegistryEntry)> {
+        self.entries()
+            .into_iter()
+            .filter(|(_, e)| e.address.eq_ignore_ascii_case(address))
+            .max_by_key(|(_, e)| e.timestamp)
+    }
+
+    /// Points `tag` at the deployment `reference` resolves to
+    pub fn tag(&mut self, reference: &str, tag: &str) -> Result<u32> {
+        if tag == "latest" || parse_version(tag).is_some() {
+            anyhow::bail!("'{}' is reserved and cannot be used as a tag", tag);
+        }
+
+        let (name, version) = {
+            let (name, entry) = self.resolve(reference)?;
+            (name.to_string(), entry.version)
+        };
+
+        if let Some(history) = self.contracts.get_mut(&name) {
+            history.tags.insert(tag.to_string(), version);
+        }
+
+        Ok(version)
+    }
+
+    /// Removes the deployment `reference` resolves to along with tags pointing at it
+    ///
+    /// The contract keeps its history entry, even when empty, so its versions are not handed out again.
+    pub fn remove(&mut self, reference: &str) -> Result<(String, RegistryEntry)> {
+        let (name, version) = {
+            let (name, entry) = self.resolve(reference)?;
+            (name.to_string(), entry.version)
+        };
+
+        let history = self
+            .contracts
+            .get_mut(&name)
+            .ok_or_else(|| anyhow!("Contract '{}' is not registered", name))?;
+
+        let index = history
+            .deployments
+            .iter()
+            .position(|d| d.version == version)
+            .ok_or_else(|| anyhow!("Version {} of {} not found", version, name))?;
+
+        let removed = history.deployments.remove(index);
+        history.tags.retain(|_, v| *v != version);
+
+        Ok((name, removed))
+    }
+
+    /// Every deployment in the registry, ordered by contract name then version
+    pub fn entries(&self) -> Vec<(&str, &RegistryEntry)> {
+        self.contracts
+            .iter()
+            .flat_map(|(name, history)| history.deployments.iter().map(move |d| (name.as_str(), d)))
+            .collect()
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`, so a
+/// crash mid-write leaves the previous file intact
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+        && !dir.exists()
+    {
+        fs::create_dir_all(dir)?;
+    }
+
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow!("Invalid path {}", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    fs::write(&tmp_path, contents)
+        .map_err(|e| anyhow!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, path)
+        .map_err(|e| anyhow!("Failed to replace {}: {}", path.display(), e))?;
+
+    Ok(())
+}
+
+fn split_reference(reference: &str) -> (&str, Option<&str>) {
+    match reference.split_once('@') {
+        Some((name, selector)) => (name.trim(), Some(selector.trim())),
+        None => (reference.trim(), None),
+    }
+}
+
+fn parse_version(selector: &str) -> Option<u32> {
+    selector.trim_start_matches('v').parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(artifact: &str, address: &str, timestamp: u64) -> NewDeployment {
+        NewDeployment {
+            artifact: artifact.to_string(),
+            address: address.to_string(),
+            tx_hash: "0x01".to_string(),
+            deployer: "0x02".to_string(),
+            timestamp,
+            bytecode_hash: None,
+            abi_hash: None,
+            libraries: BTreeMap::new(),
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("tx-registry-{}-{}", std::process::id(), name))
+            .join("testnet.json")
+    }
+
+    #[test]
+    fn test_versions_and_resolution() {
+        let mut registry = DeploymentRegistry::load_file(temp_path("resolve"), "testnet").unwrap();
+        assert_eq!(registry.register("HelloWorld", deployment("HelloWorld", "0xaa", 1)), 1);
+        assert_eq!(registry.register("HelloWorld", deployment("HelloWorld", "0xbb", 2)), 2);
+
+        assert_eq!(registry.resolve("HelloWorld").unwrap().1.address, "0xbb");
+        assert_eq!(registry.resolve("HelloWorld@latest").unwrap().1.address, "0xbb");
+        assert_eq!(registry.resolve("HelloWorld@1").unwrap().1.address, "0xaa");
+        assert_eq!(registry.resolve("HelloWorld@v1").unwrap().1.address, "0xaa");
+
+        registry.tag("HelloWorld@1", "stable").unwrap();
+        assert_eq!(registry.resolve("HelloWorld@stable").unwrap().1.version, 1);
+        assert!(registry.tag("HelloWorld", "latest").is_err());
+        assert!(registry.resolve("HelloWorld@3").is_err());
+        assert!(registry.resolve("Missing").is_err());
+    }
+
+    #[test]
+    fn test_record_call() {
+        let mut registry = DeploymentRegistry::load_file(temp_path("calls"), "testnet").unwrap();
+        registry.register("Vault", deployment("Vault", "0xaa", 1));
+
+        registry.record_call("Vault", 1, "0x0c").unwrap();
+        assert_eq!(registry.resolve("Vault").unwrap().1.calls, vec!["0x0c"]);
+        assert!(registry.record_call("Vault", 2, "0x0d").is_err());
+        assert!(registry.record_call("Token", 1, "0x0d").is_err());
+    }
+
+    #[test]
+    fn test_resolve_by_source_qualified_name() {
+        let mut registry = DeploymentRegistry::load_file(temp_path("qualified"), "testnet").unwrap();
+        registry.register("Vault", deployment("contracts/Vault.sol:Vault", "0xaa", 1));
+        registry.register("Vault", deployment("contracts/legacy/Vault.sol:Vault", "0xbb", 2));
+
+        assert_eq!(registry.resolve("Vault").unwrap().1.address, "0xbb");
+        assert_eq!(registry.resolve("contracts/Vault.sol:Vault").unwrap().1.address, "0xaa");
+        assert!(registry.resolve("contracts/Vault.sol:Vault@2").is_err());
+    }
+
+    #[test]
+    fn test_remove_drops_tags() {
+        let mut registry = DeploymentRegistry::load_file(temp_path("remove"), "testnet").unwrap();
+        registry.register("Token", deployment("Token", "0xaa", 1));
+        registry.register("Token", deployment("Token", "0xbb", 2));
+        registry.tag("Token@2", "stable").unwrap();
+
+        let (name, removed) = registry.remove("Token@stable").unwrap();
+        assert_eq!(name, "Token");
+        assert_eq!(removed.version, 2);
+        assert!(registry.get("Token").unwrap().tags.is_empty());
+        assert_eq!(registry.resolve("Token").unwrap().1.version, 1);
+
+        registry.remove("Token").unwrap();
+        assert!(registry.get("Token").unwrap().deployments.is_empty());
+        assert!(registry.resolve("Token").is_err());
+    }
+
+    #[test]
+    fn test_removed_versions_are_not_reused() {
+        let path = temp_path("reuse");
+        let mut registry = DeploymentRegistry::load_file(&path, "testnet").unwrap();
+        registry.register("Token", deployment("Token", "0xaa", 1));
+        registry.register("Token", deployment("Token", "0xbb", 2));
+        registry.remove("Token@2").unwrap();
+        registry.save().unwrap();
+
+        let mut registry = DeploymentRegistry::load_file(&path, "testnet").unwrap();
+        assert_eq!(registry.register("Token", deployment("Token", "0xcc", 3)), 3);
+        assert!(registry.resolve("Token@2").is_err());
+
+        registry.remove("Token@1").unwrap();
+        registry.remove("Token@3").unwrap();
+        assert_eq!(registry.register("Token", deployment("Token", "0xdd", 4)), 4);
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_legacy_file_migration_and_atomic_save() {
+        let path = temp_path("legacy");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            r#"[
+                {"contract_name":"HelloWorld","address":"0xaa","network":"testnet","tx_hash":"0x1","deployer":"0x2","timestamp":1},
+                {"contract_name":"HelloWorld","address":"0xbb","network":"testnet","tx_hash":"0x3","deployer":"0x2","timestamp":2},
+                {"contract_name":"MyToken","address":"0xcc","network":"testnet","tx_hash":"0x4","deployer":"0x2","timestamp":3,"label":"Token"}
+            ]"#,
+        )
+        .unwrap();
+
+        let registry = DeploymentRegistry::load_file(&path, "testnet").unwrap();
+        assert_eq!(registry.resolve("HelloWorld").unwrap().1.address, "0xbb");
+        assert_eq!(registry.resolve("Token").unwrap().1.artifact, "MyToken");
+        assert_eq!(registry.find_by_address("0xAA").unwrap().0, "HelloWorld");
+
+        registry.save().unwrap();
+        let reloaded = DeploymentRegistry::load_file(&path, "testnet").unwrap();
+        assert_eq!(reloaded.entries().len(), 3);
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_an_error() {
+        let path = temp_path("corrupt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{ not json").unwrap();
+
+        assert!(DeploymentRegistry::load_file(&path, "testnet").is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+}
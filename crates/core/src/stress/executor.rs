@@ -36,7 +36,7 @@ impl StressExecutor {
         private_key: &str,
         network: Network,
     ) -> Result<Self> {
-        let http_client = HttpClient::new(rpc_url).await?;
+        let http_client = HttpClient::for_network(rpc_url, &network).await?;
 
         let ws_client = if !network.ws_rpc.is_empty() {
             Some(WebSocketClient::new(&network.ws_rpc[0]))
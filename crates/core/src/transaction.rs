@@ -0,0 +1,480 @@
+use crate::gas::{GasCalculator, GasStrategy};
+use crate::network::HttpClient;
+use crate::networks::Network;
+use anyhow::{anyhow, Result};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::rlp::{Rlp, RlpStream};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxType {
+    Legacy,
+    Eip1559,
+}
+
+/// A fully specified transaction that can be signed without network access
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransaction {
+    #[serde(rename = "type")]
+    pub tx_type: TxType,
+    pub chain_id: u64,
+    pub from: Address,
+    /// `None` for contract creation
+    pub to: Option<Address>,
+    pub nonce: U256,
+    pub value: U256,
+    pub data: Bytes,
+    pub gas: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+}
+
+impl UnsignedTransaction {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid transaction JSON: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_typed(&self) -> Result<TypedTransaction> {
+        let tx: TypedTransaction = match self.tx_type {
+            TxType::Legacy => {
+                let gas_price = self.gas_price.ok_or_else(|| anyhow!("Legacy transaction without gasPrice"))?;
+                let mut tx = TransactionRequest::new()
+                    .from(self.from)
+                    .nonce(self.nonce)
+                    .value(self.value)
+                    .data(self.data.clone())
+                    .gas(self.gas)
+                    .gas_price(gas_price)
+                    .chain_id(self.chain_id);
+                if let Some(to) = self.to {
+                    tx = tx.to(to);
+                }
+                tx.into()
+            }
+            TxType::Eip1559 => {
+                let (Some(max_fee), Some(priority_fee)) = (self.max_fee_per_gas, self.max_priority_fee_per_gas) else {
+                    anyhow::bail!("EIP-1559 transaction without maxFeePerGas and maxPriorityFeePerGas");
+                };
+                let mut tx = Eip1559TransactionRequest::new()
+                    .from(self.from)
+                    .nonce(self.nonce)
+                    .value(self.value)
+                    .data(self.data.clone())
+                    .gas(self.gas)
+                    .max_fee_per_gas(max_fee)
+                    .max_priority_fee_per_gas(priority_fee)
+                    .chain_id(self.chain_id);
+                if let Some(to) = self.to {
+                    tx = tx.to(to);
+                }
+                tx.into()
+            }
+        };
+
+        Ok(tx)
+    }
+
+    /// Signs with `private_key` and returns the RLP encoded transaction, ready for `eth_sendRawTransaction`
+    pub fn sign(&self, private_key: &str) -> Result<Bytes> {
+        let wallet = crate::config::normalize_private_key(private_key)
+            .parse::<LocalWallet>()
+            .map_err(|e| anyhow!("Invalid private key: {}", e))?
+            .with_chain_id(self.chain_id);
+
+        if wallet.address() != self.from {
+            anyhow::bail!(
+                "Transaction is from {:#x} but the key belongs to {:#x}",
+                self.from,
+                wallet.address()
+            );
+        }
+
+        let tx = self.to_typed()?;
+        let signature = wallet
+            .sign_transaction_sync(&tx)
+            .map_err(|e| anyhow!("Failed to sign transaction: {}", e))?;
+
+        Ok(tx.rlp_signed(&signature))
+    }
+}
+
+/// What the transaction does, before nonce, gas and fees are known
+#[derive(Debug, Clone)]
+pub struct TransactionIntent {
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+/// Fills nonce, gas limit and fees of a transaction from the network
+pub struct TransactionBuilder {
+    http_client: HttpClient,
+    chain_id: u64,
+}
+
+impl TransactionBuilder {
+    /// Fails unless `rpc_url` serves the chain `network` is configured with
+    pub async fn new(rpc_url: &str, network: &Network) -> Result<Self> {
+        Ok(TransactionBuilder {
+            http_client: HttpClient::for_network(rpc_url, network).await?,
+            chain_id: network.chain_id,
+        })
+    }
+
+    /// `tx_type` defaults to EIP-1559 when the latest block has a base fee
+    pub async fn build(
+        &self,
+        intent: TransactionIntent,
+        tx_type: Option<TxType>,
+        gas_strategy: GasStrategy,
+    ) -> Result<UnsignedTransaction> {
+        let provider = self.http_client.get_provider();
+
+        let tx_type = match tx_type {
+            Some(tx_type) => tx_type,
+            None => match provider.get_block(BlockNumber::Latest).await {
+                Ok(Some(block)) if block.base_fee_per_gas.is_none() => TxType::Legacy,
+                _ => TxType::Eip1559,
+            },
+        };
+
+        let nonce = provider
+            .get_transaction_count(intent.from, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| anyhow!("Failed to get nonce: {}", e))?;
+
+        let mut estimate_request = TransactionRequest::new()
+            .from(intent.from)
+            .value(intent.value)
+            .data(intent.data.clone());
+        if let Some(to) = intent.to {
+            estimate_request = estimate_request.to(to);
+        }
+        let gas = self.http_client.estimate_gas(&estimate_request.into()).await?;
+        let gas = gas * 120 / 100;
+
+        let fees = GasCalculator::suggest_fees(provider, gas_strategy, tx_type == TxType::Legacy).await?;
+
+        Ok(UnsignedTransaction {
+            tx_type,
+            chain_id: self.chain_id,
+            from: intent.from,
+            to: intent.to,
+            nonce,
+            value: intent.value,
+            data: intent.data,
+            gas,
+            gas_price: (tx_type == TxType::Legacy).then_some(fees.gas_price),
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee,
+        })
+    }
+}
+
+/// A signed transaction decoded from its raw RLP form
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTransaction {
+    /// EIP-2718 type, 0 for legacy
+    pub tx_type: u8,
+    pub hash: H256,
+    pub from: Address,
+    /// `None` for pre EIP-155 legacy transactions
+    pub chain_id: Option<u64>,
+    pub nonce: U256,
+    pub gas_limit: U256,
+    pub gas_price: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub max_fee_per_blob_gas: Option<U256>,
+    pub blob_versioned_hashes: Vec<H256>,
+    /// Number of addresses in the access list
+    pub access_list_len: usize,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Bytes,
+}
+
+impl DecodedTransaction {
+    pub fn type_name(&self) -> &'static str {
+        match self.tx_type {
+            0 => "legacy",
+            1 => "EIP-2930",
+            2 => "EIP-1559",
+            3 => "EIP-4844",
+            _ => "unknown",
+        }
+    }
+
+    /// Most the sender can be charged: gas and blob gas at their max fees plus the value,
+    /// `None` when that does not fit in 256 bits
+    pub fn max_cost(&self) -> Option<U256> {
+        let gas_price = self.max_fee_per_gas.or(self.gas_price).unwrap_or_default();
+        let blob_gas = U256::from(GAS_PER_BLOB).checked_mul(U256::from(self.blob_versioned_hashes.len()))?;
+        let blob_cost = self.max_fee_per_blob_gas.unwrap_or_default().checked_mul(blob_gas)?;
+
+        self.gas_limit.checked_mul(gas_price)?.checked_add(blob_cost)?.checked_add(self.value)
+    }
+}
+
+/// Blob gas used by every blob of an EIP-4844 transaction
+pub const GAS_PER_BLOB: u64 = 131_072;
+
+/// Decodes a signed legacy, EIP-2930, EIP-1559 or EIP-4844 transaction and recovers its sender
+///
+/// EIP-4844 transactions are accepted both bare and in their network form with blobs attached.
+pub fn decode_raw(raw: &[u8]) -> Result<DecodedTransaction> {
+    let first = *raw.first().ok_or_else(|| anyhow!("Empty transaction"))?;
+    if first >= 0xc0 {
+        return decode_legacy(raw);
+    }
+
+    let tx_type = first;
+    let mut body = Rlp::new(&raw[1..]);
+    if tx_type == 3 && body.at(0).map(|item| item.is_list()).unwrap_or(false) {
+        body = body.at(0).map_err(rlp_error)?;
+    }
+
+    // chainId, nonce, [fee fields], gas, to, value, data, accessList, [blob fields], yParity, r, s
+    let (fee_fields, blob_fields) = match tx_type {
+        1 => (1, 0),
+        2 => (2, 0),
+        3 => (2, 2),
+        _ => anyhow::bail!("Unsupported transaction type 0x{:02x}", tx_type),
+    };
+    let field_count = 2 + fee_fields + 5 + blob_fields;
+    if body.item_count().map_err(rlp_error)? != field_count + 3 {
+        anyhow::bail!("Malformed {} transaction", tx_type);
+    }
+
+    let mut payload = vec![tx_type];
+    payload.extend_from_slice(&unsigned_list(&body, field_count)?.out());
+    let mut hashed = vec![tx_type];
+    hashed.extend_from_slice(body.as_raw());
+
+    let fee = |i: usize| body.val_at::<U256>(2 + i).map_err(rlp_error);
+    let (gas_price, max_priority_fee_per_gas, max_fee_per_gas) = match fee_fields {
+        1 => (Some(fee(0)?), None, None),
+        _ => (None, Some(fee(0)?), Some(fee(1)?)),
+    };
+
+    let base = 2 + fee_fields;
+    let signature = signature_at(&body, field_count, body.val_at(field_count).map_err(rlp_error)?)?;
+
+    Ok(DecodedTransaction {
+        tx_type,
+        hash: H256::from(ethers::utils::keccak256(&hashed)),
+        from: recover(&signature, &payload)?,
+        chain_id: Some(body.val_at(0).map_err(rlp_error)?),
+        nonce: body.val_at(1).map_err(rlp_error)?,
+        gas_limit: body.val_at(base).map_err(rlp_error)?,
+        gas_price,
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+        max_fee_per_blob_gas: (blob_fields > 0).then(|| body.val_at(base + 5)).transpose().map_err(rlp_error)?,
+        blob_versioned_hashes: if blob_fields > 0 { body.list_at(base + 6).map_err(rlp_error)? } else { Vec::new() },
+        access_list_len: body.at(base + 4).and_then(|l| l.item_count()).map_err(rlp_error)?,
+        to: address_at(&body, base + 1)?,
+        value: body.val_at(base + 2).map_err(rlp_error)?,
+        data: Bytes::from(body.val_at::<Vec<u8>>(base + 3).map_err(rlp_error)?),
+    })
+}
+
+fn decode_legacy(raw: &[u8]) -> Result<DecodedTransaction> {
+    // nonce, gasPrice, gas, to, value, data, v, r, s
+    let rlp = Rlp::new(raw);
+    if rlp.item_count().map_err(rlp_error)? != 9 {
+        anyhow::bail!("Malformed legacy transaction");
+    }
+
+    let v: u64 = rlp.val_at(6).map_err(rlp_error)?;
+    let chain_id = (v >= 35).then(|| (v - 35) / 2);
+
+    let mut stream = unsigned_list_with_extra(&rlp, 6, if chain_id.is_some() { 3 } else { 0 })?;
+    if let Some(chain_id) = chain_id {
+        stream.append(&chain_id);
+        stream.append(&0u8);
+        stream.append(&0u8);
+    }
+
+    let signature = signature_at(&rlp, 6, v)?;
+
+    Ok(DecodedTransaction {
+        tx_type: 0,
+        hash: H256::from(ethers::utils::keccak256(raw)),
+        from: recover(&signature, &stream.out())?,
+        chain_id,
+        nonce: rlp.val_at(0).map_err(rlp_error)?,
+        gas_limit: rlp.val_at(2).map_err(rlp_error)?,
+        gas_price: Some(rlp.val_at(1).map_err(rlp_error)?),
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+        max_fee_per_blob_gas: None,
+        blob_versioned_hashes: Vec::new(),
+        access_list_len: 0,
+        to: address_at(&rlp, 3)?,
+        value: rlp.val_at(4).map_err(rlp_error)?,
+        data: Bytes::from(rlp.val_at::<Vec<u8>>(5).map_err(rlp_error)?),
+    })
+}
+
+/// The first `count` items of `rlp` re-encoded as a list, i.e. the signed payload without the signature
+fn unsigned_list(rlp: &Rlp, count: usize) -> Result<RlpStream> {
+    unsigned_list_with_extra(rlp, count, 0)
+}
+
+fn unsigned_list_with_extra(rlp: &Rlp, count: usize, extra: usize) -> Result<RlpStream> {
+    let mut stream = RlpStream::new_list(count + extra);
+    for i in 0..count {
+        stream.append_raw(rlp.at(i).map_err(rlp_error)?.as_raw(), 1);
+    }
+    Ok(stream)
+}
+
+fn signature_at(rlp: &Rlp, v_index: usize, v: u64) -> Result<Signature> {
+    Ok(Signature {
+        r: rlp.val_at(v_index + 1).map_err(rlp_error)?,
+        s: rlp.val_at(v_index + 2).map_err(rlp_error)?,
+        v,
+    })
+}
+
+fn recover(signature: &Signature, payload: &[u8]) -> Result<Address> {
+    signature
+        .recover(H256::from(ethers::utils::keccak256(payload)))
+        .map_err(|e| anyhow!("Failed to recover sender: {}", e))
+}
+
+fn address_at(rlp: &Rlp, index: usize) -> Result<Option<Address>> {
+    let item = rlp.at(index).map_err(rlp_error)?;
+    if item.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(item.as_val().map_err(rlp_error)?))
+    }
+}
+
+fn rlp_error(e: ethers::utils::rlp::DecoderError) -> anyhow::Error {
+    anyhow!("Invalid RLP: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+    fn transfer(tx_type: TxType) -> UnsignedTransaction {
+        let wallet: LocalWallet = KEY.parse().unwrap();
+        UnsignedTransaction {
+            tx_type,
+            chain_id: 11155111,
+            from: wallet.address(),
+            to: Some(Address::repeat_byte(0x11)),
+            nonce: U256::from(7),
+            value: U256::exp10(16),
+            data: Bytes::default(),
+            gas: U256::from(21000),
+            gas_price: (tx_type == TxType::Legacy).then(|| U256::exp10(9)),
+            max_fee_per_gas: (tx_type == TxType::Eip1559).then(|| U256::exp10(10)),
+            max_priority_fee_per_gas: (tx_type == TxType::Eip1559).then(|| U256::exp10(9)),
+        }
+    }
+
+    #[test]
+    fn test_sign_round_trips_through_json_and_rlp() {
+        for tx_type in [TxType::Eip1559, TxType::Legacy] {
+            let tx = transfer(tx_type);
+            let parsed = UnsignedTransaction::from_json(&tx.to_json().unwrap()).unwrap();
+            assert_eq!(parsed, tx);
+
+            let raw = parsed.sign(KEY).unwrap();
+            let (decoded, signature) = TypedTransaction::decode_signed(&Rlp::new(&raw)).unwrap();
+
+            assert_eq!(signature.recover(decoded.sighash()).unwrap(), tx.from);
+            assert_eq!(decoded.nonce(), Some(&tx.nonce));
+            assert_eq!(decoded.chain_id(), Some(tx.chain_id.into()));
+        }
+    }
+
+    #[test]
+    fn test_decode_raw_recovers_sender() {
+        for tx_type in [TxType::Eip1559, TxType::Legacy] {
+            let tx = transfer(tx_type);
+            let raw = tx.sign(KEY).unwrap();
+            let decoded = decode_raw(&raw).unwrap();
+
+            assert_eq!(decoded.from, tx.from);
+            assert_eq!(decoded.to, tx.to);
+            assert_eq!(decoded.chain_id, Some(tx.chain_id));
+            assert_eq!((decoded.nonce, decoded.value, decoded.gas_limit), (tx.nonce, tx.value, tx.gas));
+            assert_eq!(decoded.max_fee_per_gas, tx.max_fee_per_gas);
+            assert_eq!(decoded.gas_price, tx.gas_price);
+            assert_eq!(decoded.hash, H256::from(ethers::utils::keccak256(&raw)));
+        }
+    }
+
+    #[test]
+    fn test_decode_raw_blob_transaction() {
+        let wallet: LocalWallet = KEY.parse().unwrap();
+        let blob_hash = H256::repeat_byte(0x01);
+
+        let fields = |stream: &mut RlpStream| {
+            stream.append(&1u64);
+            stream.append(&3u8);
+            stream.append(&U256::exp10(9));
+            stream.append(&U256::exp10(10));
+            stream.append(&U256::from(21000));
+            stream.append(&Address::repeat_byte(0x11));
+            stream.append(&0u8);
+            stream.append(&vec![0xabu8, 0xcd]);
+            stream.begin_list(0);
+            stream.append(&U256::from(7));
+            stream.append_list(&[blob_hash]);
+        };
+
+        let mut unsigned = RlpStream::new_list(11);
+        fields(&mut unsigned);
+        let mut payload = vec![3u8];
+        payload.extend_from_slice(&unsigned.out());
+        let signature = wallet.sign_hash(H256::from(ethers::utils::keccak256(&payload))).unwrap();
+
+        let mut signed = RlpStream::new_list(14);
+        fields(&mut signed);
+        signed.append(&(signature.v - 27));
+        signed.append(&signature.r);
+        signed.append(&signature.s);
+        let mut raw = vec![3u8];
+        raw.extend_from_slice(&signed.out());
+
+        let decoded = decode_raw(&raw).unwrap();
+        assert_eq!(decoded.type_name(), "EIP-4844");
+        assert_eq!(decoded.from, wallet.address());
+        assert_eq!(decoded.nonce, U256::from(3));
+        assert_eq!(decoded.max_fee_per_blob_gas, Some(U256::from(7)));
+        assert_eq!(decoded.blob_versioned_hashes, vec![blob_hash]);
+        assert_eq!(decoded.data.to_vec(), vec![0xab, 0xcd]);
+        assert_eq!(decoded.max_cost(), Some(U256::from(21000) * U256::exp10(10) + U256::from(7 * GAS_PER_BLOB)));
+
+        let huge = DecodedTransaction { gas_limit: U256::MAX, ..decoded };
+        assert_eq!(huge.max_cost(), None);
+    }
+
+    #[test]
+    fn test_sign_rejects_other_key() {
+        let mut tx = transfer(TxType::Eip1559);
+        tx.from = Address::repeat_byte(0x22);
+        assert!(tx.sign(KEY).is_err());
+    }
+}
@@ -1,7 +1,9 @@
+use crate::storage_layout::StorageLayout;
 use anyhow::Result;
 use ethers::prelude::*;
 use ethers::abi::Abi;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -11,6 +13,23 @@ pub struct ContractArtifact {
     pub bytecode: serde_json::Value,
     #[serde(rename = "deployedBytecode")]
     pub deployed_bytecode: Option<serde_json::Value>,
+    #[serde(rename = "storageLayout", default)]
+    pub storage_layout: Option<serde_json::Value>,
+}
+
+/// A library the bytecode must be linked against, with the byte ranges of its placeholders
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkReference {
+    pub source: String,
+    pub library: String,
+    pub offsets: Vec<(usize, usize)>,
+}
+
+impl LinkReference {
+    /// Fully qualified name, `path/File.sol:Library`
+    pub fn reference(&self) -> String {
+        format!("{}:{}", self.source, self.library)
+    }
 }
 
 pub struct ArtifactLoader;
@@ -26,20 +45,22 @@ impl ArtifactLoader {
         Ok(artifact)
     }
 
+    /// Loads the artifact of `Name` or `path/File.sol:Name` from `artifact_dir`
+    pub fn load_contract(artifact_dir: &str, reference: &str) -> Result<ContractArtifact> {
+        let path = x_core::artifacts::resolve_artifact(artifact_dir, reference)?;
+        Self::load_artifact(path)
+    }
+
     pub fn get_bytecode(artifact: &ContractArtifact) -> Result<Bytes> {
-        let bytecode_obj = &artifact.bytecode;
-
-        let bytecode_str = if let Some(object) = bytecode_obj.get("object") {
-            object.as_str().ok_or_else(|| {
-                anyhow::anyhow!("Bytecode object is not a string")
-            })?
-        } else if bytecode_obj.is_string() {
-            bytecode_obj.as_str().ok_or_else(|| {
-                anyhow::anyhow!("Bytecode is not a string")
-            })?
-        } else {
-            return Err(anyhow::anyhow!("Invalid bytecode format"));
-        };
+        let bytecode_str = bytecode_object(&artifact.bytecode)?;
+
+        if bytecode_str.contains("__$") {
+            let libraries: Vec<String> = Self::link_references(artifact).iter().map(|l| l.reference()).collect();
+            return Err(anyhow::anyhow!(
+                "Bytecode has unlinked libraries ({}), link them before deploying",
+                if libraries.is_empty() { "unknown".to_string() } else { libraries.join(", ") }
+            ));
+        }
 
         let bytes = hex::decode(bytecode_str.trim_start_matches("0x"))
             .map_err(|e| anyhow::anyhow!("Failed to decode bytecode: {}", e))?;
@@ -47,6 +68,64 @@ impl ArtifactLoader {
         Ok(Bytes::from(bytes))
     }
 
+    /// Libraries the creation bytecode must be linked against
+    pub fn link_references(artifact: &ContractArtifact) -> Vec<LinkReference> {
+        let mut references = Vec::new();
+
+        let Some(files) = artifact.bytecode.get("linkReferences").and_then(|l| l.as_object()) else {
+            return references;
+        };
+
+        for (source, libraries) in files {
+            for (library, offsets) in libraries.as_object().into_iter().flatten() {
+                let offsets = offsets
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|o| Some((o.get("start")?.as_u64()? as usize, o.get("length")?.as_u64()? as usize)))
+                    .collect();
+
+                references.push(LinkReference {
+                    source: source.clone(),
+                    library: library.clone(),
+                    offsets,
+                });
+            }
+        }
+
+        references
+    }
+
+    /// Replaces the `__$<hash>$__` placeholders of every library in `libraries` with its address
+    ///
+    /// Keys are fully qualified library names, `path/File.sol:Library`.
+    pub fn link(artifact: &ContractArtifact, libraries: &BTreeMap<String, Address>) -> Result<ContractArtifact> {
+        let mut linked = artifact.clone();
+
+        for code in std::iter::once(&mut linked.bytecode).chain(linked.deployed_bytecode.as_mut()) {
+            let mut object = bytecode_object(code)?.to_string();
+            for (library, address) in libraries {
+                object = object.replace(&link_placeholder(library), &hex::encode(address.as_bytes()));
+            }
+
+            match code.get_mut("object") {
+                Some(slot) => *slot = serde_json::Value::String(object),
+                None => *code = serde_json::Value::String(object),
+            }
+        }
+
+        let remaining: Vec<String> = Self::link_references(&linked)
+            .into_iter()
+            .filter(|l| !libraries.contains_key(&l.reference()))
+            .map(|l| l.reference())
+            .collect();
+        if !remaining.is_empty() || bytecode_object(&linked.bytecode)?.contains("__$") {
+            return Err(anyhow::anyhow!("No address for libraries: {}", remaining.join(", ")));
+        }
+
+        Ok(linked)
+    }
+
     pub fn get_abi(artifact: &ContractArtifact) -> Result<Abi> {
         let abi_json = &artifact.abi;
         let abi = serde_json::from_value(abi_json.clone())
@@ -54,4 +133,115 @@ impl ArtifactLoader {
 
         Ok(abi)
     }
+
+    pub fn get_storage_layout(artifact: &ContractArtifact) -> Result<StorageLayout> {
+        let layout = artifact.storage_layout.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Artifact has no storageLayout, add extra_output = [\"storageLayout\"] to foundry.toml and recompile")
+        })?;
+
+        StorageLayout::from_value(layout)
+    }
+
+    /// Keccak-256 of the creation bytecode, used to spot drift between artifacts and deployments
+    ///
+    /// Library addresses are zeroed first, so linked and unlinked bytecode hash the same.
+    pub fn bytecode_hash(artifact: &ContractArtifact) -> Result<String> {
+        let object = zero_link_placeholders(bytecode_object(&artifact.bytecode)?.trim_start_matches("0x"));
+        let mut bytecode = hex::decode(object)
+            .map_err(|e| anyhow::anyhow!("Failed to decode bytecode: {}", e))?;
+
+        for reference in Self::link_references(artifact) {
+            for (start, length) in reference.offsets {
+                if let Some(range) = bytecode.get_mut(start..start + length) {
+                    range.fill(0);
+                }
+            }
+        }
+
+        Ok(format!("0x{}", hex::encode(ethers::utils::keccak256(&bytecode))))
+    }
+
+    pub fn abi_hash(artifact: &ContractArtifact) -> Result<String> {
+        let abi = serde_json::to_vec(&artifact.abi)?;
+        Ok(format!("0x{}", hex::encode(ethers::utils::keccak256(abi))))
+    }
+}
+
+fn bytecode_object(bytecode: &serde_json::Value) -> Result<&str> {
+    if let Some(object) = bytecode.get("object") {
+        object.as_str().ok_or_else(|| anyhow::anyhow!("Bytecode object is not a string"))
+    } else {
+        bytecode.as_str().ok_or_else(|| anyhow::anyhow!("Invalid bytecode format"))
+    }
+}
+
+/// `__$<first 34 hex chars of keccak256(name)>$__`, the placeholder solc emits for a library
+fn link_placeholder(fully_qualified_name: &str) -> String {
+    let hash = hex::encode(ethers::utils::keccak256(fully_qualified_name.as_bytes()));
+    format!("__${}$__", &hash[..34])
+}
+
+/// Replaces every 40 character library placeholder with zeros
+pub(crate) fn zero_link_placeholders(hex_code: &str) -> String {
+    let mut result = String::with_capacity(hex_code.len());
+    let mut rest = hex_code;
+
+    while let Some(pos) = rest.find("__") {
+        result.push_str(&rest[..pos]);
+        let placeholder_len = 40.min(rest.len() - pos);
+        result.push_str(&"0".repeat(placeholder_len));
+        rest = &rest[pos + placeholder_len..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library_artifact() -> ContractArtifact {
+        let placeholder = link_placeholder("contracts/Math.sol:Math");
+        let link_references = serde_json::json!({ "contracts/Math.sol": { "Math": [{ "start": 2, "length": 20 }] } });
+
+        ContractArtifact {
+            abi: serde_json::json!([]),
+            bytecode: serde_json::json!({
+                "object": format!("0x6080{}00", placeholder),
+                "linkReferences": link_references,
+            }),
+            deployed_bytecode: Some(serde_json::json!({
+                "object": format!("0x6081{}00", placeholder),
+                "linkReferences": link_references,
+            })),
+            storage_layout: None,
+        }
+    }
+
+    #[test]
+    fn test_link_splices_library_addresses() {
+        let artifact = library_artifact();
+        assert!(ArtifactLoader::get_bytecode(&artifact).is_err());
+        assert_eq!(ArtifactLoader::link_references(&artifact)[0].reference(), "contracts/Math.sol:Math");
+
+        let address: Address = "0x00000000000000000000000000000000000000aa".parse().unwrap();
+        let libraries = BTreeMap::from([("contracts/Math.sol:Math".to_string(), address)]);
+        let linked = ArtifactLoader::link(&artifact, &libraries).unwrap();
+
+        let bytecode = ArtifactLoader::get_bytecode(&linked).unwrap();
+        assert_eq!(bytecode.len(), 23);
+        assert_eq!(&bytecode[2..22], address.as_bytes());
+        assert!(!linked.deployed_bytecode.unwrap()["object"].as_str().unwrap().contains("__$"));
+
+        assert_eq!(
+            ArtifactLoader::bytecode_hash(&artifact).unwrap(),
+            ArtifactLoader::bytecode_hash(&ArtifactLoader::link(&artifact, &libraries).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_link_requires_every_library() {
+        assert!(ArtifactLoader::link(&library_artifact(), &BTreeMap::new()).is_err());
+    }
 }
@@ -28,7 +28,7 @@ impl ContractDeployer {
         private_key: &str,
         network: Network,
     ) -> Result<Self> {
-        let http_client = HttpClient::new(rpc_url).await?;
+        let http_client = HttpClient::for_network(rpc_url, &network).await?;
 
         let ws_client = if !network.ws_rpc.is_empty() {
             Some(WebSocketClient::new(&network.ws_rpc[0]))
@@ -50,6 +50,14 @@ impl ContractDeployer {
         })
     }
 
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    pub fn network_id(&self) -> &str {
+        &self.network.id
+    }
+
     pub async fn deploy(
         &self,
         artifact: &ContractArtifact,
@@ -148,7 +156,7 @@ impl ContractDeployer {
             }
         }
 
-        let receipt = pending_tx.confirmations(1).await
+        let receipt = pending_tx.confirmations(x_core::project::confirmations()).await
             .map_err(|e| anyhow::anyhow!("Failed to confirm transaction: {}", e))?
             .ok_or_else(|| anyhow::anyhow!("Transaction confirmation timeout"))?;
 
@@ -0,0 +1,320 @@
+use crate::artifact::{zero_link_placeholders, ArtifactLoader, ContractArtifact};
+use crate::metadata::MetadataManager;
+use crate::proxy::IMPLEMENTATION_SLOT;
+use anyhow::{anyhow, Result};
+use ethers::prelude::*;
+use x_core::network::HttpClient;
+use x_core::registry::{DeploymentRegistry, RegistryEntry};
+
+/// Runtime bytecode of an artifact together with the byte ranges that differ per deployment
+#[derive(Debug, Clone)]
+pub struct DeployedCode {
+    pub code: Vec<u8>,
+    /// Immutable and linked library ranges, `(start, length)`, ignored when comparing
+    pub masked: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriftStatus {
+    Match,
+    /// Same code, but the CBOR metadata hash differs (comments, paths or compiler settings changed)
+    MetadataMismatch,
+    Mismatch,
+    /// No code at the recorded address
+    Missing,
+    /// The artifact could not be loaded, so nothing was compared
+    NoArtifact(String),
+}
+
+impl std::fmt::Display for DriftStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriftStatus::Match => write!(f, "match"),
+            DriftStatus::MetadataMismatch => write!(f, "match (metadata differs)"),
+            DriftStatus::Mismatch => write!(f, "mismatch"),
+            DriftStatus::Missing => write!(f, "missing"),
+            DriftStatus::NoArtifact(e) => write!(f, "no artifact ({})", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DriftReport {
+    pub name: String,
+    pub version: u32,
+    pub artifact: String,
+    pub address: String,
+    /// Implementation compared instead of the address itself when the entry is a tracked proxy
+    pub implementation: Option<String>,
+    pub status: DriftStatus,
+    /// The artifact's bytecode hash no longer matches the one recorded at deploy time
+    pub artifact_changed: bool,
+}
+
+impl DeployedCode {
+    pub fn from_artifact(artifact: &ContractArtifact) -> Result<Self> {
+        let deployed = artifact
+            .deployed_bytecode
+            .as_ref()
+            .ok_or_else(|| anyhow!("Artifact has no deployedBytecode"))?;
+
+        let object = match deployed.get("object") {
+            Some(object) => object.as_str(),
+            None => deployed.as_str(),
+        }
+        .ok_or_else(|| anyhow!("Invalid deployedBytecode format"))?;
+
+        let mut masked = Vec::new();
+        collect_ranges(deployed.get("immutableReferences"), &mut masked);
+        if let Some(files) = deployed.get("linkReferences").and_then(|l| l.as_object()) {
+            for libraries in files.values() {
+                if let Some(libraries) = libraries.as_object() {
+                    for refs in libraries.values() {
+                        collect_ranges(Some(refs), &mut masked);
+                    }
+                }
+            }
+        }
+
+        // Unlinked libraries show up as `__$<hash>$__` placeholders, zero them like immutables
+        let hex_code = zero_link_placeholders(object.trim_start_matches("0x"));
+        let code = hex::decode(hex_code)
+            .map_err(|e| anyhow!("Failed to decode deployedBytecode: {}", e))?;
+
+        Ok(DeployedCode { code, masked })
+    }
+
+    /// Compares on-chain runtime code with this artifact code
+    pub fn compare(&self, onchain: &[u8]) -> DriftStatus {
+        if onchain.is_empty() {
+            return DriftStatus::Missing;
+        }
+
+        let onchain = self.mask(onchain);
+        if onchain == self.code {
+            return DriftStatus::Match;
+        }
+
+        let expected = strip_metadata(&self.code);
+        let actual = strip_metadata(&onchain);
+        if expected.len() < self.code.len() && actual.len() < onchain.len() && expected == actual {
+            DriftStatus::MetadataMismatch
+        } else {
+            DriftStatus::Mismatch
+        }
+    }
+
+    fn mask(&self, onchain: &[u8]) -> Vec<u8> {
+        let mut code = onchain.to_vec();
+        for &(start, length) in &self.masked {
+            if let Some(range) = code.get_mut(start..start + length) {
+                range.fill(0);
+            }
+        }
+        code
+    }
+}
+
+fn collect_ranges(value: Option<&serde_json::Value>, out: &mut Vec<(usize, usize)>) {
+    let items = match value {
+        Some(serde_json::Value::Object(map)) => map.values().collect::<Vec<_>>(),
+        Some(value @ serde_json::Value::Array(_)) => vec![value],
+        _ => return,
+    };
+
+    for refs in items.iter().filter_map(|v| v.as_array()) {
+        for r in refs {
+            let start = r.get("start").and_then(|s| s.as_u64());
+            let length = r.get("length").and_then(|l| l.as_u64());
+            if let (Some(start), Some(length)) = (start, length) {
+                out.push((start as usize, length as usize));
+            }
+        }
+    }
+}
+
+/// Strips the CBOR encoded metadata solc appends to runtime code, if present
+///
+/// The last two bytes hold the big-endian length of the CBOR map that precedes them.
+pub fn strip_metadata(code: &[u8]) -> &[u8] {
+    if code.len() < 2 {
+        return code;
+    }
+
+    let cbor_len = u16::from_be_bytes([code[code.len() - 2], code[code.len() - 1]]) as usize;
+    if cbor_len == 0 || cbor_len + 2 > code.len() {
+        return code;
+    }
+
+    let start = code.len() - 2 - cbor_len;
+    // CBOR maps with 1 to 5 entries start with 0xa1..=0xa5
+    if (0xa1..=0xa5).contains(&code[start]) {
+        &code[..start]
+    } else {
+        code
+    }
+}
+
+pub struct DriftChecker {
+    http_client: HttpClient,
+}
+
+impl DriftChecker {
+    pub async fn new(rpc_url: &str) -> Result<Self> {
+        let http_client = HttpClient::new(rpc_url).await?;
+        Ok(DriftChecker { http_client })
+    }
+
+    /// Checks every registered deployment of `network_id` against its artifact
+    pub async fn check_network(&self, network_id: &str, artifact_dir: &str) -> Result<Vec<DriftReport>> {
+        let registry = DeploymentRegistry::load(x_core::project::deployments_dir(), network_id)?;
+        let mut reports = Vec::new();
+
+        for (name, entry) in registry.entries() {
+            reports.push(self.check_entry(&registry, name, entry, artifact_dir).await?);
+        }
+
+        Ok(reports)
+    }
+
+    pub async fn check_entry(
+        &self,
+        registry: &DeploymentRegistry,
+        name: &str,
+        entry: &RegistryEntry,
+        artifact_dir: &str,
+    ) -> Result<DriftReport> {
+        let mut report = DriftReport {
+            name: name.to_string(),
+            version: entry.version,
+            artifact: entry.artifact.clone(),
+            address: entry.address.clone(),
+            implementation: None,
+            status: DriftStatus::Missing,
+            artifact_changed: false,
+        };
+
+        let artifact = match ArtifactLoader::load_contract(artifact_dir, &entry.artifact) {
+            Ok(artifact) => artifact,
+            Err(e) => {
+                report.status = DriftStatus::NoArtifact(e.to_string());
+                return Ok(report);
+            }
+        };
+
+        if let (Some(recorded), Ok(current)) = (&entry.bytecode_hash, ArtifactLoader::bytecode_hash(&artifact)) {
+            report.artifact_changed = !recorded.eq_ignore_ascii_case(&current);
+        }
+
+        let expected = match DeployedCode::from_artifact(&artifact) {
+            Ok(expected) => expected,
+            Err(e) => {
+                report.status = DriftStatus::NoArtifact(e.to_string());
+                return Ok(report);
+            }
+        };
+
+        let mut address: Address = entry
+            .address
+            .parse()
+            .map_err(|_| anyhow!("Invalid address recorded for {}@{}: {}", name, entry.version, entry.address))?;
+
+        // Proxies are registered under the implementation name, so compare the implementation code.
+        // Each upgrade registers a new version, older versions map onto the recorded history.
+        if let Some(proxy) = MetadataManager::find_proxy(&registry.network, &entry.address)? {
+            let versions: Vec<u32> = registry
+                .get(name)
+                .map(|h| {
+                    h.deployments
+                        .iter()
+                        .filter(|d| d.address.eq_ignore_ascii_case(&entry.address))
+                        .map(|d| d.version)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            address = if versions.iter().max() == Some(&entry.version) {
+                let slot = self
+                    .http_client
+                    .get_storage_at(address, IMPLEMENTATION_SLOT.parse()?)
+                    .await?;
+                Address::from_slice(&slot.as_bytes()[12..])
+            } else {
+                let index = versions.iter().filter(|v| **v < entry.version).count();
+                let implementation = proxy
+                    .implementations
+                    .get(index)
+                    .ok_or_else(|| anyhow!("No recorded implementation for {}@{}", name, entry.version))?;
+                implementation
+                    .address
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid implementation address: {}", implementation.address))?
+            };
+            report.implementation = Some(format!("{:#x}", address));
+        }
+
+        let onchain = self.http_client.get_code(address).await?;
+        report.status = expected.compare(&onchain);
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_metadata(body: &[u8], metadata: &[u8]) -> Vec<u8> {
+        let mut code = body.to_vec();
+        code.extend_from_slice(metadata);
+        code.extend_from_slice(&(metadata.len() as u16).to_be_bytes());
+        code
+    }
+
+    #[test]
+    fn test_strip_metadata() {
+        let code = with_metadata(&[0x60, 0x80, 0x60, 0x40, 0x52], &[0xa1, 0x64, 0x73, 0x6f, 0x6c, 0x63]);
+        assert_eq!(strip_metadata(&code), &[0x60, 0x80, 0x60, 0x40, 0x52]);
+
+        let no_metadata = [0x60, 0x80, 0x60, 0x40];
+        assert_eq!(strip_metadata(&no_metadata), &no_metadata);
+    }
+
+    #[test]
+    fn test_compare_ignores_immutables_and_reports_metadata() {
+        let body = [0x60, 0x80, 0x7f, 0x00, 0x00, 0x00, 0x52];
+        let expected = DeployedCode {
+            code: with_metadata(&body, &[0xa1, 0x01]),
+            masked: vec![(3, 3)],
+        };
+
+        let deployed = with_metadata(&[0x60, 0x80, 0x7f, 0xaa, 0xbb, 0xcc, 0x52], &[0xa1, 0x01]);
+        assert_eq!(expected.compare(&deployed), DriftStatus::Match);
+
+        let recompiled = with_metadata(&[0x60, 0x80, 0x7f, 0xaa, 0xbb, 0xcc, 0x52], &[0xa1, 0x02]);
+        assert_eq!(expected.compare(&recompiled), DriftStatus::MetadataMismatch);
+
+        let changed = with_metadata(&[0x60, 0x81, 0x7f, 0xaa, 0xbb, 0xcc, 0x52], &[0xa1, 0x01]);
+        assert_eq!(expected.compare(&changed), DriftStatus::Mismatch);
+
+        assert_eq!(expected.compare(&[]), DriftStatus::Missing);
+    }
+
+    #[test]
+    fn test_from_artifact_reads_immutables_and_link_placeholders() {
+        let artifact = ContractArtifact {
+            abi: serde_json::json!([]),
+            bytecode: serde_json::json!({ "object": "0x" }),
+            deployed_bytecode: Some(serde_json::json!({
+                "object": "0x6080__$0123456789abcdef0123456789abcdef01$__00",
+                "immutableReferences": { "12": [{ "start": 1, "length": 1 }] },
+                "linkReferences": { "src/Lib.sol": { "Lib": [{ "start": 2, "length": 20 }] } }
+            })),
+            storage_layout: None,
+        };
+
+        let deployed = DeployedCode::from_artifact(&artifact).unwrap();
+        assert_eq!(deployed.code.len(), 23);
+        assert_eq!(deployed.masked, vec![(1, 1), (2, 20)]);
+    }
+}
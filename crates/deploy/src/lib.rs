@@ -1,7 +1,17 @@
 pub mod artifact;
 pub mod deployer;
+pub mod drift;
+pub mod linker;
 pub mod metadata;
+pub mod plan;
+pub mod proxy;
+pub mod storage_layout;
 
-pub use artifact::{ArtifactLoader, ContractArtifact};
+pub use artifact::{ArtifactLoader, ContractArtifact, LinkReference};
 pub use deployer::{ContractDeployer, DeploymentResult};
-pub use metadata::{DeploymentMetadata, MetadataManager};
+pub use drift::{DriftChecker, DriftReport, DriftStatus};
+pub use linker::{LibraryLinker, LinkMap, LinkedArtifact};
+pub use metadata::{ImplementationMetadata, MetadataManager, ProxyMetadata};
+pub use plan::{DeploymentPlan, PlanExecutor, StepResult};
+pub use proxy::{ProxyDeployment, ProxyKind, ProxyManager, UpgradeResult};
+pub use storage_layout::{LayoutChange, LayoutDiff, StorageLayout};
@@ -0,0 +1,113 @@
+use crate::artifact::{ArtifactLoader, ContractArtifact};
+use crate::deployer::ContractDeployer;
+use crate::metadata::MetadataManager;
+use anyhow::{anyhow, Result};
+use ethers::types::Address;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use x_core::gas::GasStrategy;
+
+/// Linked libraries, `path/File.sol:Library -> address`
+pub type LinkMap = BTreeMap<String, String>;
+
+type LinkFuture<'b> = Pin<Box<dyn Future<Output = Result<(ContractArtifact, LinkMap)>> + 'b>>;
+
+#[derive(Debug, Clone)]
+pub struct LinkedArtifact {
+    pub artifact: ContractArtifact,
+    pub libraries: LinkMap,
+    /// Libraries that had to be deployed, as opposed to reused from the registry
+    pub deployed: Vec<String>,
+}
+
+/// Resolves the libraries an artifact links against, deploying the ones not yet in the registry
+pub struct LibraryLinker<'a> {
+    deployer: &'a ContractDeployer,
+    artifact_dir: &'a str,
+}
+
+impl<'a> LibraryLinker<'a> {
+    pub fn new(deployer: &'a ContractDeployer, artifact_dir: &'a str) -> Self {
+        LibraryLinker { deployer, artifact_dir }
+    }
+
+    /// Returns `artifact` with every library placeholder replaced by a deployed address
+    ///
+    /// A registered library is reused when it was deployed from the same artifact and its
+    /// bytecode is unchanged, otherwise the library is deployed (and linked itself) first.
+    pub async fn link(&self, artifact: &ContractArtifact, gas_strategy: GasStrategy) -> Result<LinkedArtifact> {
+        let mut deployed = Vec::new();
+        let (artifact, libraries) = self.link_recursive(artifact, gas_strategy, &mut deployed, 0).await?;
+        Ok(LinkedArtifact { artifact, libraries, deployed })
+    }
+
+    fn link_recursive<'b>(
+        &'b self,
+        artifact: &'b ContractArtifact,
+        gas_strategy: GasStrategy,
+        deployed: &'b mut Vec<String>,
+        depth: usize,
+    ) -> LinkFuture<'b> {
+        Box::pin(async move {
+            let references = ArtifactLoader::link_references(artifact);
+            if references.is_empty() {
+                return Ok((artifact.clone(), LinkMap::new()));
+            }
+            if depth > 16 {
+                anyhow::bail!("Library links nest too deep, is there a cycle?");
+            }
+
+            let network_id = self.deployer.network_id();
+            let mut addresses: BTreeMap<String, Address> = BTreeMap::new();
+
+            for reference in references {
+                let qualified = reference.reference();
+                let library_artifact = ArtifactLoader::load_contract(self.artifact_dir, &qualified)
+                    .map_err(|e| anyhow!("Cannot link {}: {}", qualified, e))?;
+                let current_hash = ArtifactLoader::bytecode_hash(&library_artifact).ok();
+
+                let registered = MetadataManager::find_deployment(network_id, &reference.library)?
+                    .filter(|entry| {
+                        entry.artifact == qualified && current_hash.is_some() && entry.bytecode_hash == current_hash
+                    });
+
+                let address = match registered {
+                    Some(entry) => entry.address,
+                    None => {
+                        let (linked, libraries) = self
+                            .link_recursive(&library_artifact, gas_strategy, deployed, depth + 1)
+                            .await?;
+                        let result = self.deployer.deploy(&linked, None, gas_strategy).await?;
+                        let address = format!("{:#x}", result.contract_address);
+
+                        MetadataManager::record_linked_deployment(
+                            network_id,
+                            &reference.library,
+                            &qualified,
+                            &linked,
+                            &address,
+                            &format!("{:#x}", result.tx_hash),
+                            &format!("{:#x}", self.deployer.address()),
+                            &libraries,
+                        )?;
+                        deployed.push(qualified.clone());
+                        address
+                    }
+                };
+
+                let parsed = address
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid address {} registered for {}", address, qualified))?;
+                addresses.insert(qualified, parsed);
+            }
+
+            let linked = ArtifactLoader::link(artifact, &addresses)?;
+            let libraries = addresses
+                .into_iter()
+                .map(|(name, address)| (name, format!("{:#x}", address)))
+                .collect();
+            Ok((linked, libraries))
+        })
+    }
+}
@@ -1,77 +1,204 @@
+use crate::artifact::{ArtifactLoader, ContractArtifact};
+use crate::linker::LinkMap;
+use crate::proxy::ProxyKind;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use anyhow::Result;
 use std::time::{SystemTime, UNIX_EPOCH};
+use x_core::registry::{write_atomic, DeploymentRegistry, NewDeployment, RegistryEntry};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DeploymentMetadata {
+pub struct ImplementationMetadata {
     pub contract_name: String,
     pub address: String,
-    pub network: String,
     pub tx_hash: String,
-    pub deployer: String,
     pub timestamp: u64,
+    /// Storage layout of the implementation at deploy time, used to vet later upgrades
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_layout: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyMetadata {
+    pub proxy_address: String,
+    pub kind: ProxyKind,
+    pub network: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<String>,
+    /// Every implementation the proxy has pointed to, oldest first
+    pub implementations: Vec<ImplementationMetadata>,
+}
+
+impl ProxyMetadata {
+    pub fn current_implementation(&self) -> Option<&ImplementationMetadata> {
+        self.implementations.last()
+    }
 }
 
 pub struct MetadataManager;
 
 impl MetadataManager {
-    pub fn save_deployment(
-        contract_name: &str,
+    /// Registers a new version of `name` in `deployments/<network>.json` and returns its version
+    pub fn record_deployment(
+        network_id: &str,
+        name: &str,
+        artifact_name: &str,
+        artifact: &ContractArtifact,
         address: &str,
+        tx_hash: &str,
+        deployer: &str,
+    ) -> Result<u32> {
+        Self::record_linked_deployment(
+            network_id,
+            name,
+            artifact_name,
+            artifact,
+            address,
+            tx_hash,
+            deployer,
+            &LinkMap::new(),
+        )
+    }
+
+    /// Same as [`Self::record_deployment`], also recording the libraries linked into the bytecode
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_linked_deployment(
         network_id: &str,
+        name: &str,
+        artifact_name: &str,
+        artifact: &ContractArtifact,
+        address: &str,
         tx_hash: &str,
         deployer: &str,
-    ) -> Result<()> {
-        let deployments_dir = PathBuf::from("deployments");
-        
-        if !deployments_dir.exists() {
-            fs::create_dir_all(&deployments_dir)?;
-        }
+        libraries: &LinkMap,
+    ) -> Result<u32> {
+        let mut registry = DeploymentRegistry::load(x_core::project::deployments_dir(), network_id)?;
 
-        let filename = format!("{}.json", network_id);
-        let filepath = deployments_dir.join(&filename);
+        let version = registry.register(
+            name,
+            Self::new_deployment(artifact_name, artifact, address, tx_hash, deployer, libraries)?,
+        );
+        registry.save()?;
 
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)?
-            .as_secs();
+        Ok(version)
+    }
 
-        let metadata = DeploymentMetadata {
-            contract_name: contract_name.to_string(),
+    /// Same as [`Self::record_linked_deployment`], also pointing `tag` at the new version in the same write
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_tagged_deployment(
+        network_id: &str,
+        name: &str,
+        tag: &str,
+        artifact_name: &str,
+        artifact: &ContractArtifact,
+        address: &str,
+        tx_hash: &str,
+        deployer: &str,
+        libraries: &LinkMap,
+    ) -> Result<u32> {
+        let mut registry = DeploymentRegistry::load(x_core::project::deployments_dir(), network_id)?;
+
+        let version = registry.register(
+            name,
+            Self::new_deployment(artifact_name, artifact, address, tx_hash, deployer, libraries)?,
+        );
+        registry.tag(&format!("{}@{}", name, version), tag)?;
+        registry.save()?;
+
+        Ok(version)
+    }
+
+    fn new_deployment(
+        artifact_name: &str,
+        artifact: &ContractArtifact,
+        address: &str,
+        tx_hash: &str,
+        deployer: &str,
+        libraries: &LinkMap,
+    ) -> Result<NewDeployment> {
+        Ok(NewDeployment {
+            artifact: artifact_name.to_string(),
             address: address.to_string(),
-            network: network_id.to_string(),
             tx_hash: tx_hash.to_string(),
             deployer: deployer.to_string(),
-            timestamp,
-        };
+            timestamp: Self::current_timestamp()?,
+            bytecode_hash: ArtifactLoader::bytecode_hash(artifact).ok(),
+            abi_hash: ArtifactLoader::abi_hash(artifact).ok(),
+            libraries: libraries.clone(),
+        })
+    }
+
+    /// Records a completed post-deploy call on version `version` of `name`
+    pub fn record_call(network_id: &str, name: &str, version: u32, tx_hash: &str) -> Result<()> {
+        let mut registry = DeploymentRegistry::load(x_core::project::deployments_dir(), network_id)?;
+        registry.record_call(name, version, tx_hash)?;
+        registry.save()
+    }
 
-        let mut deployments: Vec<DeploymentMetadata> = if filepath.exists() {
-            let content = fs::read_to_string(&filepath)?;
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
+    /// Latest registered deployment of `name`, if any
+    pub fn find_deployment(network_id: &str, name: &str) -> Result<Option<RegistryEntry>> {
+        let registry = DeploymentRegistry::load(x_core::project::deployments_dir(), network_id)?;
+        Ok(registry.get(name).and_then(|h| h.latest()).cloned())
+    }
 
-        deployments.push(metadata);
+    /// Appends an implementation to the history of a proxy, creating its entry on first use
+    pub fn save_proxy_implementation(
+        network_id: &str,
+        proxy_address: &str,
+        kind: ProxyKind,
+        admin: Option<&str>,
+        implementation: ImplementationMetadata,
+    ) -> Result<()> {
+        let filepath = Self::proxies_path(network_id);
+        let mut proxies = Self::get_proxies(network_id)?;
 
-        let json = serde_json::to_string_pretty(&deployments)?;
-        fs::write(&filepath, json)?;
+        match proxies
+            .iter_mut()
+            .find(|p| p.proxy_address.eq_ignore_ascii_case(proxy_address))
+        {
+            Some(proxy) => {
+                if let Some(admin) = admin {
+                    proxy.admin = Some(admin.to_string());
+                }
+                proxy.implementations.push(implementation);
+            }
+            None => proxies.push(ProxyMetadata {
+                proxy_address: proxy_address.to_string(),
+                kind,
+                network: network_id.to_string(),
+                admin: admin.map(|a| a.to_string()),
+                implementations: vec![implementation],
+            }),
+        }
 
-        Ok(())
+        write_atomic(&filepath, &serde_json::to_string_pretty(&proxies)?)
     }
 
-    pub fn get_deployments(network_id: &str) -> Result<Vec<DeploymentMetadata>> {
-        let deployments_dir = PathBuf::from("deployments");
-        let filename = format!("{}.json", network_id);
-        let filepath = deployments_dir.join(&filename);
+    pub fn get_proxies(network_id: &str) -> Result<Vec<ProxyMetadata>> {
+        let filepath = Self::proxies_path(network_id);
 
         if !filepath.exists() {
             return Ok(Vec::new());
         }
 
         let content = fs::read_to_string(&filepath)?;
-        let deployments = serde_json::from_str(&content)?;
-        Ok(deployments)
+        let proxies = serde_json::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", filepath.display(), e))?;
+        Ok(proxies)
+    }
+
+    pub fn find_proxy(network_id: &str, proxy_address: &str) -> Result<Option<ProxyMetadata>> {
+        Ok(Self::get_proxies(network_id)?
+            .into_iter()
+            .find(|p| p.proxy_address.eq_ignore_ascii_case(proxy_address)))
+    }
+
+    fn proxies_path(network_id: &str) -> PathBuf {
+        PathBuf::from(x_core::project::deployments_dir()).join(format!("{}.proxies.json", network_id))
+    }
+
+    pub fn current_timestamp() -> Result<u64> {
+        Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
     }
 }
@@ -0,0 +1,559 @@
+use crate::artifact::{ArtifactLoader, ContractArtifact};
+use crate::deployer::ContractDeployer;
+use crate::linker::LibraryLinker;
+use crate::metadata::MetadataManager;
+use alloy_dyn_abi::DynSolValue;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use x_core::gas::GasStrategy;
+use x_core::invoker::{Codec, ContractExecutor, DynAbiConstructor, DynAbiFunction};
+use x_core::networks::Network;
+use x_core::registry::{DeploymentRegistry, RegistryEntry};
+
+/// Registry tag marking the version a deployment plan step deployed
+pub const PLAN_TAG: &str = "plan";
+
+/// A declarative list of contracts to deploy, loaded from a TOML manifest
+///
+/// ```toml
+/// [[step]]
+/// name = "Token"
+/// artifact = "MyToken"
+/// args = ["Token", "TKN"]
+///
+/// [[step]]
+/// name = "Vault"
+/// args = ["${Token.address}"]
+///
+/// [[step.call]]
+/// function = "transferOwnership"
+/// args = ["0x..."]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeploymentPlan {
+    #[serde(rename = "step", default)]
+    pub steps: Vec<PlanStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlanStep {
+    pub name: String,
+    /// Artifact to deploy, defaults to the step name
+    #[serde(default)]
+    pub artifact: Option<String>,
+    #[serde(default)]
+    pub args: Vec<PlanArg>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(rename = "call", default)]
+    pub calls: Vec<PlanCall>,
+}
+
+/// A function invoked on the freshly deployed contract, e.g. `transferOwnership`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlanCall {
+    pub function: String,
+    #[serde(default)]
+    pub args: Vec<PlanArg>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum PlanArg {
+    Text(String),
+    Integer(i64),
+    Bool(bool),
+}
+
+impl std::fmt::Display for PlanArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanArg::Text(s) => write!(f, "{}", s),
+            PlanArg::Integer(i) => write!(f, "{}", i),
+            PlanArg::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StepOutput {
+    pub address: String,
+    pub tx_hash: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub name: String,
+    pub contract_name: String,
+    pub address: String,
+    pub tx_hash: String,
+    /// The contract was already deployed by an earlier run
+    pub skipped: bool,
+    /// Post-deploy calls made by this run
+    pub calls: Vec<(String, String)>,
+}
+
+impl PlanStep {
+    pub fn artifact_name(&self) -> &str {
+        self.artifact.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Steps this one must wait for, explicit `depends_on` plus every `${Step.field}` reference
+    pub fn dependencies(&self) -> Result<Vec<String>> {
+        let mut deps = self.depends_on.clone();
+
+        let args = self
+            .args
+            .iter()
+            .chain(self.calls.iter().flat_map(|c| c.args.iter()));
+
+        for arg in args {
+            for (step, _field) in find_placeholders(&arg.to_string())? {
+                if !deps.contains(&step) {
+                    deps.push(step);
+                }
+            }
+        }
+
+        Ok(deps)
+    }
+}
+
+impl DeploymentPlan {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read deployment plan {}: {}", path.display(), e))?;
+
+        Self::from_toml(&content)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let plan: DeploymentPlan = toml::from_str(content)
+            .map_err(|e| anyhow!("Failed to parse deployment plan: {}", e))?;
+
+        if plan.steps.is_empty() {
+            anyhow::bail!("Deployment plan has no steps");
+        }
+
+        let mut names = HashSet::new();
+        for step in &plan.steps {
+            if step.name.trim().is_empty() {
+                anyhow::bail!("Deployment plan contains a step without a name");
+            }
+            if !names.insert(step.name.as_str()) {
+                anyhow::bail!("Duplicate step name '{}' in deployment plan", step.name);
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Orders steps so that every step runs after the steps it references
+    ///
+    /// Independent steps keep the order in which they appear in the manifest.
+    pub fn execution_order(&self) -> Result<Vec<&PlanStep>> {
+        let mut pending: Vec<(&PlanStep, Vec<String>)> = Vec::new();
+        for step in &self.steps {
+            let deps = step.dependencies()?;
+            for dep in &deps {
+                if dep == &step.name {
+                    anyhow::bail!("Step '{}' depends on itself", step.name);
+                }
+                if !self.steps.iter().any(|s| &s.name == dep) {
+                    anyhow::bail!("Step '{}' references unknown step '{}'", step.name, dep);
+                }
+            }
+            pending.push((step, deps));
+        }
+
+        let mut ordered: Vec<&PlanStep> = Vec::new();
+        let mut done: HashSet<String> = HashSet::new();
+
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .position(|(_, deps)| deps.iter().all(|d| done.contains(d)));
+
+            match ready {
+                Some(index) => {
+                    let (step, _) = pending.remove(index);
+                    done.insert(step.name.clone());
+                    ordered.push(step);
+                }
+                None => {
+                    let names: Vec<&str> = pending.iter().map(|(s, _)| s.name.as_str()).collect();
+                    anyhow::bail!("Dependency cycle between steps: {}", names.join(", "));
+                }
+            }
+        }
+
+        Ok(ordered)
+    }
+}
+
+/// Extracts `(step, field)` pairs from `${step.field}` placeholders
+fn find_placeholders(input: &str) -> Result<Vec<(String, String)>> {
+    let mut found = Vec::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| anyhow!("Unterminated placeholder in '{}'", input))?;
+
+        let (step, field) = after[..end]
+            .split_once('.')
+            .ok_or_else(|| anyhow!("Placeholder '${{{}}}' must look like ${{Step.address}}", &after[..end]))?;
+
+        found.push((step.trim().to_string(), field.trim().to_string()));
+        rest = &after[end + 1..];
+    }
+
+    Ok(found)
+}
+
+/// Replaces `${Step.address}` and `${Step.tx_hash}` with outputs of earlier steps
+pub fn resolve_placeholders(input: &str, outputs: &HashMap<String, StepOutput>) -> Result<String> {
+    let mut resolved = input.to_string();
+
+    for (step, field) in find_placeholders(input)? {
+        let output = outputs
+            .get(&step)
+            .ok_or_else(|| anyhow!("No output available for step '{}'", step))?;
+
+        let value = match field.as_str() {
+            "address" => &output.address,
+            "tx_hash" => &output.tx_hash,
+            _ => anyhow::bail!("Unknown field '{}' in placeholder for step '{}'", field, step),
+        };
+
+        resolved = resolved.replace(&format!("${{{}.{}}}", step, field), value);
+    }
+
+    Ok(resolved)
+}
+
+fn parse_args(
+    args: &[PlanArg],
+    inputs: &[(String, String)],
+    outputs: &HashMap<String, StepOutput>,
+) -> Result<Vec<DynSolValue>> {
+    if args.len() != inputs.len() {
+        anyhow::bail!("Expected {} arguments, got {}", inputs.len(), args.len());
+    }
+
+    let mut parsed = Vec::new();
+    for (i, (arg, (_, ty))) in args.iter().zip(inputs).enumerate() {
+        let value = resolve_placeholders(&arg.to_string(), outputs)?;
+        let value = Codec::parse_value(&value, ty)
+            .map_err(|e| anyhow!("Failed to parse argument {}: {}", i, e))?;
+        parsed.push(value);
+    }
+
+    Ok(parsed)
+}
+
+pub struct PlanExecutor {
+    deployer: ContractDeployer,
+    executor: ContractExecutor,
+    network: Network,
+}
+
+impl PlanExecutor {
+    pub async fn new(
+        rpc_url: &str,
+        private_key: &str,
+        network: Network,
+    ) -> Result<Self> {
+        let deployer = ContractDeployer::new(rpc_url, private_key, network.clone()).await?;
+        let executor = ContractExecutor::new(rpc_url, private_key, network.clone()).await?;
+
+        Ok(PlanExecutor {
+            deployer,
+            executor,
+            network,
+        })
+    }
+
+    /// Runs every step of the plan in dependency order
+    ///
+    /// Each step is registered in `deployments/<network>.json` under its step name and tagged
+    /// `plan` as soon as its contract is mined, and each post-deploy call is recorded once it
+    /// succeeds. A step whose tagged deployment has the same bytecode as its artifact is not
+    /// deployed again, so an interrupted plan can simply be run again and picks up at the
+    /// first call that has not completed.
+    pub async fn execute(
+        &self,
+        plan: &DeploymentPlan,
+        artifact_dir: &str,
+        gas_strategy: GasStrategy,
+        on_progress: impl Fn(&StepResult),
+    ) -> Result<Vec<StepResult>> {
+        let order = plan.execution_order()?;
+        let mut outputs: HashMap<String, StepOutput> = HashMap::new();
+        let mut results = Vec::new();
+
+        for step in order {
+            let artifact = ArtifactLoader::load_contract(artifact_dir, step.artifact_name())?;
+            let bytecode_hash = ArtifactLoader::bytecode_hash(&artifact)?;
+            let registry = DeploymentRegistry::load(x_core::project::deployments_dir(), &self.network.id)?;
+
+            let (record, skipped) = match recorded_step(&registry, &step.name, &bytecode_hash) {
+                Some(record) => (record.clone(), true),
+                None => {
+                    let record = self
+                        .deploy_step(step, &artifact, artifact_dir, gas_strategy, &outputs)
+                        .await
+                        .map_err(|e| anyhow!("Step '{}' failed: {}", step.name, e))?;
+                    (record, false)
+                }
+            };
+
+            outputs.insert(
+                step.name.clone(),
+                StepOutput {
+                    address: record.address.clone(),
+                    tx_hash: record.tx_hash.clone(),
+                },
+            );
+
+            let calls = self
+                .run_calls(step, &artifact, &record, &outputs)
+                .await
+                .map_err(|e| anyhow!("Step '{}' failed: {}", step.name, e))?;
+
+            let result = StepResult {
+                name: step.name.clone(),
+                contract_name: record.artifact,
+                address: record.address,
+                tx_hash: record.tx_hash,
+                skipped,
+                calls,
+            };
+
+            on_progress(&result);
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    /// Deploys the step's contract and records it right away, before any post-deploy call
+    async fn deploy_step(
+        &self,
+        step: &PlanStep,
+        artifact: &ContractArtifact,
+        artifact_dir: &str,
+        gas_strategy: GasStrategy,
+        outputs: &HashMap<String, StepOutput>,
+    ) -> Result<RegistryEntry> {
+        let contract_name = step.artifact_name();
+
+        let abi_str = serde_json::to_string(&artifact.abi)
+            .map_err(|e| anyhow!("Failed to serialize ABI: {}", e))?;
+
+        let constructor = DynAbiConstructor::from_json_abi(&abi_str)?;
+        let constructor_args = parse_args(&step.args, &constructor.get_inputs(), outputs)?;
+        let encoded_args = constructor.encode_input(&constructor_args)?;
+
+        let linked = LibraryLinker::new(&self.deployer, artifact_dir)
+            .link(artifact, gas_strategy)
+            .await?;
+        let deployment = self
+            .deployer
+            .deploy(&linked.artifact, Some(encoded_args), gas_strategy)
+            .await?;
+
+        let version = MetadataManager::record_tagged_deployment(
+            &self.network.id,
+            &step.name,
+            PLAN_TAG,
+            contract_name,
+            &linked.artifact,
+            &format!("{:#x}", deployment.contract_address),
+            &format!("{:#x}", deployment.tx_hash),
+            &format!("{:#x}", self.deployer.address()),
+            &linked.libraries,
+        )?;
+
+        DeploymentRegistry::load(x_core::project::deployments_dir(), &self.network.id)?
+            .get(&step.name)
+            .and_then(|h| h.get_version(version))
+            .cloned()
+            .ok_or_else(|| anyhow!("{} was deployed but is missing from the registry", step.name))
+    }
+
+    /// Makes the step's post-deploy calls that `record` has not completed yet, recording each one
+    async fn run_calls(
+        &self,
+        step: &PlanStep,
+        artifact: &ContractArtifact,
+        record: &RegistryEntry,
+        outputs: &HashMap<String, StepOutput>,
+    ) -> Result<Vec<(String, String)>> {
+        let abi_str = serde_json::to_string(&artifact.abi)
+            .map_err(|e| anyhow!("Failed to serialize ABI: {}", e))?;
+        let address = record.address.parse().map_err(|_| anyhow!("Invalid contract address"))?;
+
+        let mut calls = Vec::new();
+        for (index, call) in step.calls.iter().enumerate().skip(record.calls.len()) {
+            let function = DynAbiFunction::from_json_abi(&abi_str, &call.function)?;
+            let call_args = parse_args(&call.args, &function.get_inputs(), outputs)?;
+
+            let result = self
+                .executor
+                .call_write_function(address, &function, &call_args, &call.function)
+                .await
+                .map_err(|e| {
+                    anyhow!(
+                        "call {} ({}) failed: {} ({} is recorded at {}, the next run resumes at this call)",
+                        index + 1,
+                        call.function,
+                        e,
+                        step.artifact_name(),
+                        record.address
+                    )
+                })?;
+
+            MetadataManager::record_call(&self.network.id, &step.name, record.version, &result.tx_hash)?;
+            calls.push((call.function.clone(), result.tx_hash));
+        }
+
+        Ok(calls)
+    }
+}
+
+/// The deployment a plan step made earlier, if its bytecode still matches the step's artifact
+///
+/// Only the version tagged [`PLAN_TAG`] counts, a plain `deploy` of the same name is ignored.
+fn recorded_step<'a>(registry: &'a DeploymentRegistry, step: &str, bytecode_hash: &str) -> Option<&'a RegistryEntry> {
+    let history = registry.get(step)?;
+    let version = history.tags.get(PLAN_TAG)?;
+
+    history
+        .get_version(*version)
+        .filter(|entry| entry.bytecode_hash.as_deref() == Some(bytecode_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = r#"
+[[step]]
+name = "Vault"
+args = ["${Token.address}", 100]
+
+[[step.call]]
+function = "transferOwnership"
+args = ["${Admin.address}"]
+
+[[step]]
+name = "Token"
+artifact = "MyToken"
+
+[[step]]
+name = "Admin"
+"#;
+
+    #[test]
+    fn test_execution_order_follows_references() {
+        let plan = DeploymentPlan::from_toml(PLAN).unwrap();
+        let order: Vec<&str> = plan
+            .execution_order()
+            .unwrap()
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+
+        assert_eq!(order, vec!["Token", "Admin", "Vault"]);
+        assert_eq!(plan.steps[1].artifact_name(), "MyToken");
+        assert_eq!(plan.steps[2].artifact_name(), "Admin");
+    }
+
+    #[test]
+    fn test_cycle_and_unknown_step_rejected() {
+        let cycle = r#"
+[[step]]
+name = "A"
+args = ["${B.address}"]
+
+[[step]]
+name = "B"
+depends_on = ["A"]
+"#;
+        let plan = DeploymentPlan::from_toml(cycle).unwrap();
+        assert!(plan.execution_order().is_err());
+
+        let unknown = r#"
+[[step]]
+name = "A"
+args = ["${Missing.address}"]
+"#;
+        let plan = DeploymentPlan::from_toml(unknown).unwrap();
+        assert!(plan.execution_order().is_err());
+    }
+
+    #[test]
+    fn test_resolve_placeholders() {
+        let mut outputs = HashMap::new();
+        outputs.insert(
+            "Token".to_string(),
+            StepOutput {
+                address: "0xabc".to_string(),
+                tx_hash: "0xdef".to_string(),
+            },
+        );
+
+        assert_eq!(resolve_placeholders("${Token.address}", &outputs).unwrap(), "0xabc");
+        assert_eq!(resolve_placeholders("${Token.tx_hash}", &outputs).unwrap(), "0xdef");
+        assert!(resolve_placeholders("${Token.owner}", &outputs).is_err());
+        assert!(resolve_placeholders("${Other.address}", &outputs).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_step_names_rejected() {
+        let plan = r#"
+[[step]]
+name = "A"
+
+[[step]]
+name = "A"
+"#;
+        assert!(DeploymentPlan::from_toml(plan).is_err());
+    }
+
+    #[test]
+    fn test_plain_deployment_of_same_name_is_not_resumed() {
+        use std::collections::BTreeMap;
+        use x_core::registry::NewDeployment;
+
+        let deployment = |address: &str, bytecode_hash: &str| NewDeployment {
+            artifact: "Vault".to_string(),
+            address: address.to_string(),
+            tx_hash: "0x01".to_string(),
+            deployer: "0x02".to_string(),
+            timestamp: 1,
+            bytecode_hash: Some(bytecode_hash.to_string()),
+            abi_hash: None,
+            libraries: BTreeMap::new(),
+        };
+
+        let mut registry = DeploymentRegistry::load_file("/nonexistent/sepolia.json", "sepolia").unwrap();
+        registry.register("Vault", deployment("0xaaa", "0x11"));
+        assert!(recorded_step(&registry, "Vault", "0x11").is_none());
+
+        registry.register("Vault", deployment("0xbbb", "0x11"));
+        registry.tag("Vault@2", PLAN_TAG).unwrap();
+        registry.register("Vault", deployment("0xccc", "0x11"));
+        assert_eq!(recorded_step(&registry, "Vault", "0x11").unwrap().address, "0xbbb");
+
+        // The artifact changed since the plan deployed it
+        assert!(recorded_step(&registry, "Vault", "0x22").is_none());
+    }
+}
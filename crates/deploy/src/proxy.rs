@@ -0,0 +1,477 @@
+use crate::artifact::{ArtifactLoader, ContractArtifact};
+use crate::deployer::ContractDeployer;
+use crate::linker::{LibraryLinker, LinkMap};
+use crate::metadata::{ImplementationMetadata, MetadataManager};
+use crate::storage_layout::{LayoutDiff, StorageLayout};
+use alloy_dyn_abi::DynSolValue;
+use anyhow::{anyhow, Result};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use x_core::gas::GasStrategy;
+use x_core::invoker::{Codec, ContractExecutor, DynAbiConstructor, DynAbiFunction};
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+
+/// EIP-1967 implementation slot, `keccak256("eip1967.proxy.implementation") - 1`
+pub const IMPLEMENTATION_SLOT: &str = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+/// EIP-1967 admin slot, `keccak256("eip1967.proxy.admin") - 1`
+pub const ADMIN_SLOT: &str = "0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyKind {
+    Transparent,
+    Uups,
+}
+
+impl ProxyKind {
+    pub fn parse(kind: &str) -> Result<Self> {
+        match kind.to_lowercase().as_str() {
+            "transparent" => Ok(ProxyKind::Transparent),
+            "uups" => Ok(ProxyKind::Uups),
+            _ => Err(anyhow!("Invalid proxy kind: {} (expected transparent or uups)", kind)),
+        }
+    }
+
+    /// OpenZeppelin artifact deployed as the proxy itself
+    pub fn artifact_name(&self) -> &'static str {
+        match self {
+            ProxyKind::Transparent => "TransparentUpgradeableProxy",
+            ProxyKind::Uups => "ERC1967Proxy",
+        }
+    }
+}
+
+impl std::fmt::Display for ProxyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyKind::Transparent => write!(f, "transparent"),
+            ProxyKind::Uups => write!(f, "uups"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProxyDeployment {
+    pub proxy_address: Address,
+    pub proxy_tx_hash: H256,
+    pub implementation_address: Address,
+    pub implementation_tx_hash: H256,
+    pub admin: Option<Address>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpgradeResult {
+    pub previous_implementation: Address,
+    pub implementation_address: Address,
+    pub implementation_tx_hash: H256,
+    pub upgrade_tx_hash: String,
+}
+
+/// Encodes a call to `function_name` from the artifact ABI, e.g. an `initialize` call
+pub fn encode_function_call(
+    artifact: &ContractArtifact,
+    function_name: &str,
+    args: &[String],
+) -> Result<Vec<u8>> {
+    let abi_str = serde_json::to_string(&artifact.abi)
+        .map_err(|e| anyhow!("Failed to serialize ABI: {}", e))?;
+
+    let function = DynAbiFunction::from_json_abi(&abi_str, function_name)?;
+    let inputs = function.get_inputs();
+
+    if args.len() != inputs.len() {
+        anyhow::bail!("Expected {} arguments for {}, got {}", inputs.len(), function_name, args.len());
+    }
+
+    let mut values = Vec::new();
+    for (i, (arg, (_, ty))) in args.iter().zip(&inputs).enumerate() {
+        let value = Codec::parse_value(arg, ty)
+            .map_err(|e| anyhow!("Failed to parse argument {}: {}", i, e))?;
+        values.push(value);
+    }
+
+    Ok(function.encode_call(function_name, &values)?.to_vec())
+}
+
+fn has_function(artifact: &ContractArtifact, function_name: &str) -> bool {
+    artifact
+        .abi
+        .as_array()
+        .map(|items| {
+            items.iter().any(|item| {
+                item.get("type").and_then(|t| t.as_str()) == Some("function")
+                    && item.get("name").and_then(|n| n.as_str()) == Some(function_name)
+            })
+        })
+        .unwrap_or(false)
+}
+
+fn to_alloy_address(address: Address) -> alloy_primitives::Address {
+    alloy_primitives::Address::from_slice(address.as_bytes())
+}
+
+/// Implementation address stored in the proxy's EIP-1967 slot
+pub async fn read_implementation(client: &HttpClient, proxy: Address) -> Result<Address> {
+    let value = client.get_storage_at(proxy, IMPLEMENTATION_SLOT.parse()?).await?;
+    Ok(Address::from_slice(&value.as_bytes()[12..]))
+}
+
+/// Admin address stored in the proxy's EIP-1967 slot, zero for UUPS proxies
+pub async fn read_admin(client: &HttpClient, proxy: Address) -> Result<Address> {
+    let value = client.get_storage_at(proxy, ADMIN_SLOT.parse()?).await?;
+    Ok(Address::from_slice(&value.as_bytes()[12..]))
+}
+
+/// Compares the storage layout of the proxy's current implementation with `new_implementation`
+///
+/// The deployed layout comes from the snapshot recorded with the implementation, or from
+/// its artifact in `artifact_dir` for implementations recorded without one.
+pub async fn check_storage_layout(
+    client: &HttpClient,
+    network_id: &str,
+    proxy: Address,
+    new_implementation: &ContractArtifact,
+    artifact_dir: &str,
+) -> Result<LayoutDiff> {
+    let proxy_str = format!("{:#x}", proxy);
+    let record = MetadataManager::find_proxy(network_id, &proxy_str)?
+        .ok_or_else(|| anyhow!("Proxy {} is not tracked, its deployed storage layout is unknown", proxy_str))?;
+
+    let current = record
+        .current_implementation()
+        .ok_or_else(|| anyhow!("Proxy {} has no recorded implementation", proxy_str))?;
+
+    let on_chain = format!("{:#x}", read_implementation(client, proxy).await?);
+    if !on_chain.eq_ignore_ascii_case(&current.address) {
+        anyhow::bail!(
+            "Proxy {} points to {} but the last recorded implementation is {}, refusing to compare against a stale layout",
+            proxy_str,
+            on_chain,
+            current.address
+        );
+    }
+
+    let deployed_layout = match &current.storage_layout {
+        Some(layout) => StorageLayout::from_value(layout)?,
+        None => {
+            let artifact = ArtifactLoader::load_contract(artifact_dir, &current.contract_name)?;
+            ArtifactLoader::get_storage_layout(&artifact)?
+        }
+    };
+
+    let new_layout = ArtifactLoader::get_storage_layout(new_implementation)?;
+
+    Ok(deployed_layout.diff(&new_layout))
+}
+
+pub struct ProxyManager {
+    deployer: ContractDeployer,
+    executor: ContractExecutor,
+    http_client: HttpClient,
+    network: Network,
+}
+
+impl ProxyManager {
+    pub async fn new(
+        rpc_url: &str,
+        private_key: &str,
+        network: Network,
+    ) -> Result<Self> {
+        let deployer = ContractDeployer::new(rpc_url, private_key, network.clone()).await?;
+        let executor = ContractExecutor::new(rpc_url, private_key, network.clone()).await?;
+        let http_client = HttpClient::for_network(rpc_url, &network).await?;
+
+        Ok(ProxyManager {
+            deployer,
+            executor,
+            http_client,
+            network,
+        })
+    }
+
+    pub async fn get_implementation(&self, proxy: Address) -> Result<Address> {
+        read_implementation(&self.http_client, proxy).await
+    }
+
+    pub async fn get_admin(&self, proxy: Address) -> Result<Address> {
+        read_admin(&self.http_client, proxy).await
+    }
+
+    /// Deploys `implementation` behind a new proxy, calling `init_data` through the proxy
+    ///
+    /// The proxy is recorded in the deployments file under `contract_name`, so the invoker
+    /// talks to it with the implementation ABI, and its implementation history is started.
+    pub async fn deploy_proxy(
+        &self,
+        contract_name: &str,
+        implementation: &ContractArtifact,
+        kind: ProxyKind,
+        init_data: Vec<u8>,
+        artifact_dir: &str,
+        gas_strategy: GasStrategy,
+    ) -> Result<ProxyDeployment> {
+        if kind == ProxyKind::Uups && !has_function(implementation, "upgradeToAndCall") {
+            anyhow::bail!(
+                "{} does not expose upgradeToAndCall, a UUPS proxy pointing to it could never be upgraded",
+                contract_name
+            );
+        }
+
+        let proxy_name = kind.artifact_name();
+        let proxy_artifact = ArtifactLoader::load_contract(artifact_dir, proxy_name)
+            .map_err(|e| anyhow!("{} (compile contracts/Proxies.sol first)", e))?;
+
+        let (implementation_result, libraries) = self
+            .deploy_implementation(implementation, artifact_dir, gas_strategy)
+            .await?;
+        let implementation_address = implementation_result.contract_address;
+        let deployer_address = self.deployer.address();
+
+        let constructor_args = match kind {
+            ProxyKind::Transparent => vec![
+                DynSolValue::Address(to_alloy_address(implementation_address)),
+                DynSolValue::Address(to_alloy_address(deployer_address)),
+                DynSolValue::Bytes(init_data),
+            ],
+            ProxyKind::Uups => vec![
+                DynSolValue::Address(to_alloy_address(implementation_address)),
+                DynSolValue::Bytes(init_data),
+            ],
+        };
+
+        let proxy_abi = serde_json::to_string(&proxy_artifact.abi)
+            .map_err(|e| anyhow!("Failed to serialize ABI: {}", e))?;
+        let encoded_args = DynAbiConstructor::from_json_abi(&proxy_abi)?.encode_input(&constructor_args)?;
+
+        let proxy_result = self
+            .deployer
+            .deploy(&proxy_artifact, Some(encoded_args), gas_strategy)
+            .await?;
+        let proxy_address = proxy_result.contract_address;
+
+        let admin = match kind {
+            ProxyKind::Transparent => Some(self.get_admin(proxy_address).await?),
+            ProxyKind::Uups => None,
+        };
+
+        MetadataManager::record_linked_deployment(
+            &self.network.id,
+            x_core::artifacts::contract_name(contract_name),
+            contract_name,
+            implementation,
+            &format!("{:#x}", proxy_address),
+            &format!("{:#x}", proxy_result.tx_hash),
+            &format!("{:#x}", deployer_address),
+            &libraries,
+        )?;
+
+        MetadataManager::save_proxy_implementation(
+            &self.network.id,
+            &format!("{:#x}", proxy_address),
+            kind,
+            admin.map(|a| format!("{:#x}", a)).as_deref(),
+            ImplementationMetadata {
+                contract_name: contract_name.to_string(),
+                address: format!("{:#x}", implementation_address),
+                tx_hash: format!("{:#x}", implementation_result.tx_hash),
+                timestamp: MetadataManager::current_timestamp()?,
+                storage_layout: implementation.storage_layout.clone(),
+            },
+        )?;
+
+        Ok(ProxyDeployment {
+            proxy_address,
+            proxy_tx_hash: proxy_result.tx_hash,
+            implementation_address,
+            implementation_tx_hash: implementation_result.tx_hash,
+            admin,
+        })
+    }
+
+    /// Compares the storage layout of the proxy's current implementation with `new_implementation`
+    pub async fn check_storage_layout(
+        &self,
+        proxy: Address,
+        new_implementation: &ContractArtifact,
+        artifact_dir: &str,
+    ) -> Result<LayoutDiff> {
+        check_storage_layout(&self.http_client, &self.network.id, proxy, new_implementation, artifact_dir).await
+    }
+
+    /// Deploys a new implementation and points the proxy at it via `upgradeToAndCall`
+    ///
+    /// UUPS proxies are upgraded directly, transparent proxies through their `ProxyAdmin`.
+    /// The proxy kind is taken from the recorded proxy metadata unless `kind` is given.
+    /// Unless `check_storage` is false, upgrades that would corrupt storage are refused.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upgrade(
+        &self,
+        proxy: Address,
+        contract_name: &str,
+        implementation: &ContractArtifact,
+        kind: Option<ProxyKind>,
+        call_data: Vec<u8>,
+        artifact_dir: &str,
+        check_storage: bool,
+        gas_strategy: GasStrategy,
+    ) -> Result<UpgradeResult> {
+        let proxy_str = format!("{:#x}", proxy);
+        let record = MetadataManager::find_proxy(&self.network.id, &proxy_str)?;
+
+        let kind = kind
+            .or(record.as_ref().map(|r| r.kind))
+            .ok_or_else(|| anyhow!("Proxy {} is not tracked, specify its kind", proxy_str))?;
+
+        if kind == ProxyKind::Uups && !has_function(implementation, "upgradeToAndCall") {
+            anyhow::bail!(
+                "{} does not expose upgradeToAndCall, upgrading to it would lock the proxy",
+                contract_name
+            );
+        }
+
+        let previous_implementation = self.get_implementation(proxy).await?;
+        if previous_implementation.is_zero() {
+            anyhow::bail!("{} is not an EIP-1967 proxy (implementation slot is empty)", proxy_str);
+        }
+
+        if check_storage {
+            let diff = self.check_storage_layout(proxy, implementation, artifact_dir).await?;
+            if !diff.is_compatible() {
+                anyhow::bail!(
+                    "Storage layout of {} is incompatible with the deployed implementation:\n{}",
+                    contract_name,
+                    diff
+                );
+            }
+        }
+
+        let (implementation_result, libraries) = self
+            .deploy_implementation(implementation, artifact_dir, gas_strategy)
+            .await?;
+        let implementation_address = implementation_result.contract_address;
+
+        let (target, function, args) = match kind {
+            ProxyKind::Uups => (
+                proxy,
+                DynAbiFunction::from_signature("function upgradeToAndCall(address newImplementation, bytes data)")?,
+                vec![
+                    DynSolValue::Address(to_alloy_address(implementation_address)),
+                    DynSolValue::Bytes(call_data),
+                ],
+            ),
+            ProxyKind::Transparent => (
+                self.get_admin(proxy).await?,
+                DynAbiFunction::from_signature("function upgradeAndCall(address proxy, address implementation, bytes data)")?,
+                vec![
+                    DynSolValue::Address(to_alloy_address(proxy)),
+                    DynSolValue::Address(to_alloy_address(implementation_address)),
+                    DynSolValue::Bytes(call_data),
+                ],
+            ),
+        };
+
+        let function_name = match kind {
+            ProxyKind::Uups => "upgradeToAndCall",
+            ProxyKind::Transparent => "upgradeAndCall",
+        };
+
+        let result = self
+            .executor
+            .call_write_function(to_alloy_address(target), &function, &args, function_name)
+            .await?;
+
+        let current = self.get_implementation(proxy).await?;
+        if current != implementation_address {
+            anyhow::bail!(
+                "Upgrade transaction {} succeeded but the proxy still points to {:#x}",
+                result.tx_hash,
+                current
+            );
+        }
+
+        MetadataManager::record_linked_deployment(
+            &self.network.id,
+            x_core::artifacts::contract_name(contract_name),
+            contract_name,
+            implementation,
+            &proxy_str,
+            &result.tx_hash,
+            &format!("{:#x}", self.deployer.address()),
+            &libraries,
+        )?;
+
+        MetadataManager::save_proxy_implementation(
+            &self.network.id,
+            &proxy_str,
+            kind,
+            None,
+            ImplementationMetadata {
+                contract_name: contract_name.to_string(),
+                address: format!("{:#x}", implementation_address),
+                tx_hash: format!("{:#x}", implementation_result.tx_hash),
+                timestamp: MetadataManager::current_timestamp()?,
+                storage_layout: implementation.storage_layout.clone(),
+            },
+        )?;
+
+        Ok(UpgradeResult {
+            previous_implementation,
+            implementation_address,
+            implementation_tx_hash: implementation_result.tx_hash,
+            upgrade_tx_hash: result.tx_hash,
+        })
+    }
+
+    /// Deploys an implementation, linking its libraries first
+    async fn deploy_implementation(
+        &self,
+        implementation: &ContractArtifact,
+        artifact_dir: &str,
+        gas_strategy: GasStrategy,
+    ) -> Result<(crate::deployer::DeploymentResult, LinkMap)> {
+        let abi_str = serde_json::to_string(&implementation.abi)
+            .map_err(|e| anyhow!("Failed to serialize ABI: {}", e))?;
+
+        let constructor_args = DynAbiConstructor::from_json_abi(&abi_str)?
+            .encode_input(&[])
+            .map_err(|_| anyhow!("Implementation constructors cannot take arguments, use an initializer instead"))?;
+
+        let linked = LibraryLinker::new(&self.deployer, artifact_dir)
+            .link(implementation, gas_strategy)
+            .await?;
+        let result = self
+            .deployer
+            .deploy(&linked.artifact, Some(constructor_args), gas_strategy)
+            .await?;
+
+        Ok((result, linked.libraries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eip1967_slots() {
+        let implementation = x_core::crypto::keccak256(b"eip1967.proxy.implementation");
+        let admin = x_core::crypto::keccak256(b"eip1967.proxy.admin");
+
+        let minus_one = |hash: Vec<u8>| {
+            let value = U256::from_big_endian(&hash) - U256::one();
+            format!("{:#066x}", value)
+        };
+
+        assert_eq!(minus_one(implementation), IMPLEMENTATION_SLOT);
+        assert_eq!(minus_one(admin), ADMIN_SLOT);
+    }
+
+    #[test]
+    fn test_proxy_kind_parse() {
+        assert_eq!(ProxyKind::parse("UUPS").unwrap(), ProxyKind::Uups);
+        assert_eq!(ProxyKind::parse("transparent").unwrap().artifact_name(), "TransparentUpgradeableProxy");
+        assert!(ProxyKind::parse("beacon").is_err());
+    }
+}
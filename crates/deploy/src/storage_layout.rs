@@ -0,0 +1,495 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Solidity storage layout as emitted by solc/forge with `extra_output = ["storageLayout"]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageLayout {
+    #[serde(default)]
+    pub storage: Vec<StorageVariable>,
+    #[serde(default)]
+    pub types: Option<HashMap<String, StorageType>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageVariable {
+    pub label: String,
+    pub slot: String,
+    pub offset: u64,
+    #[serde(rename = "type")]
+    pub ty: String,
+    #[serde(default)]
+    pub contract: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageType {
+    pub label: String,
+    #[serde(rename = "numberOfBytes")]
+    pub number_of_bytes: String,
+    /// `inplace`, `mapping`, `dynamic_array` or `bytes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Fields of a struct, with slots relative to the struct's own slot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<StorageVariable>>,
+    /// Key type id of a mapping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Value type id of a mapping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Element type id of an array
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutChange {
+    Removed { label: String, slot: String, offset: u64, ty: String },
+    Moved { label: String, from: (String, u64), to: (String, u64) },
+    TypeChanged { label: String, slot: String, from: String, to: String },
+    /// Same type name, but its struct members, mapping key/value or array element changed
+    InnerTypeChanged { label: String, slot: String, ty: String, detail: String },
+    Renamed { from: String, to: String, slot: String, ty: String },
+    GapShrunk { label: String, from: String, to: String },
+    Added { label: String, slot: String, offset: u64, ty: String },
+}
+
+impl LayoutChange {
+    /// Whether the change corrupts data already stored by the old implementation
+    pub fn is_breaking(&self) -> bool {
+        matches!(
+            self,
+            LayoutChange::Removed { .. }
+                | LayoutChange::Moved { .. }
+                | LayoutChange::TypeChanged { .. }
+                | LayoutChange::InnerTypeChanged { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for LayoutChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutChange::Removed { label, slot, offset, ty } => {
+                write!(f, "- {} ({}) removed from slot {} offset {}", label, ty, slot, offset)
+            }
+            LayoutChange::Moved { label, from, to } => write!(
+                f,
+                "~ {} moved from slot {} offset {} to slot {} offset {}",
+                label, from.0, from.1, to.0, to.1
+            ),
+            LayoutChange::TypeChanged { label, slot, from, to } => {
+                write!(f, "~ {} in slot {} changed type from {} to {}", label, slot, from, to)
+            }
+            LayoutChange::InnerTypeChanged { label, slot, ty, detail } => {
+                write!(f, "~ {} ({}) in slot {}: {}", label, ty, slot, detail)
+            }
+            LayoutChange::Renamed { from, to, slot, ty } => {
+                write!(f, "  {} renamed to {} in slot {} ({})", from, to, slot, ty)
+            }
+            LayoutChange::GapShrunk { label, from, to } => {
+                write!(f, "  {} shrunk from {} to {}", label, from, to)
+            }
+            LayoutChange::Added { label, slot, offset, ty } => {
+                write!(f, "+ {} ({}) added in slot {} offset {}", label, ty, slot, offset)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LayoutDiff {
+    pub changes: Vec<LayoutChange>,
+}
+
+impl LayoutDiff {
+    pub fn is_compatible(&self) -> bool {
+        !self.changes.iter().any(|c| c.is_breaking())
+    }
+
+    pub fn breaking_changes(&self) -> Vec<&LayoutChange> {
+        self.changes.iter().filter(|c| c.is_breaking()).collect()
+    }
+}
+
+impl std::fmt::Display for LayoutDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "Storage layout unchanged");
+        }
+
+        for change in &self.changes {
+            let marker = if change.is_breaking() { "✗" } else { "✓" };
+            writeln!(f, "{} {}", marker, change)?;
+        }
+        Ok(())
+    }
+}
+
+impl StorageLayout {
+    pub fn from_value(value: &serde_json::Value) -> Result<Self> {
+        serde_json::from_value(value.clone())
+            .map_err(|e| anyhow!("Failed to parse storage layout: {}", e))
+    }
+
+    /// Human readable type of a variable, falling back to the raw type id
+    fn type_label(&self, ty: &str) -> String {
+        self.types
+            .as_ref()
+            .and_then(|types| types.get(ty))
+            .map(|t| t.label.clone())
+            .unwrap_or_else(|| ty.to_string())
+    }
+
+    fn type_size(&self, ty: &str) -> Option<&str> {
+        self.types
+            .as_ref()
+            .and_then(|types| types.get(ty))
+            .map(|t| t.number_of_bytes.as_str())
+    }
+
+    fn get_type(&self, ty: &str) -> Option<&StorageType> {
+        self.types.as_ref().and_then(|types| types.get(ty))
+    }
+
+    /// First difference between type `old_ty` of this layout and `new_ty` of `new`, if any
+    ///
+    /// Struct members, mapping keys and values and array elements are compared recursively.
+    /// `seen` holds the pairs already being compared, structs can refer to themselves through
+    /// a mapping or dynamic array.
+    fn type_difference(
+        &self,
+        old_ty: &str,
+        new: &StorageLayout,
+        new_ty: &str,
+        seen: &mut HashSet<(String, String)>,
+    ) -> Option<String> {
+        let (old_type, new_type) = match (self.get_type(old_ty), new.get_type(new_ty)) {
+            (Some(old_type), Some(new_type)) => (old_type, new_type),
+            _ => {
+                let (from, to) = (self.type_label(old_ty), new.type_label(new_ty));
+                return (from != to).then(|| format!("{} became {}", from, to));
+            }
+        };
+
+        if old_type.label != new_type.label {
+            return Some(format!("{} became {}", old_type.label, new_type.label));
+        }
+        if old_type.number_of_bytes != new_type.number_of_bytes {
+            return Some(format!(
+                "{} changed size from {} to {} bytes",
+                old_type.label, old_type.number_of_bytes, new_type.number_of_bytes
+            ));
+        }
+        if old_type.encoding != new_type.encoding {
+            return Some(format!("{} changed encoding", old_type.label));
+        }
+        if !seen.insert((old_ty.to_string(), new_ty.to_string())) {
+            return None;
+        }
+
+        match (&old_type.members, &new_type.members) {
+            (Some(old_members), Some(new_members)) => {
+                if old_members.len() != new_members.len() {
+                    return Some(format!(
+                        "{} has {} members instead of {}",
+                        old_type.label,
+                        new_members.len(),
+                        old_members.len()
+                    ));
+                }
+
+                for (old_member, new_member) in old_members.iter().zip(new_members) {
+                    if old_member.label != new_member.label {
+                        return Some(format!(
+                            "member {} of {} replaced by {}",
+                            old_member.label, old_type.label, new_member.label
+                        ));
+                    }
+                    if old_member.slot != new_member.slot || old_member.offset != new_member.offset {
+                        return Some(format!(
+                            "member {} of {} moved from slot {} offset {} to slot {} offset {}",
+                            old_member.label,
+                            old_type.label,
+                            old_member.slot,
+                            old_member.offset,
+                            new_member.slot,
+                            new_member.offset
+                        ));
+                    }
+                    if let Some(detail) = self.type_difference(&old_member.ty, new, &new_member.ty, seen) {
+                        return Some(format!("member {} of {}: {}", old_member.label, old_type.label, detail));
+                    }
+                }
+            }
+            (None, None) => {}
+            _ => return Some(format!("members of {} changed", old_type.label)),
+        }
+
+        let inner = [
+            ("key", &old_type.key, &new_type.key),
+            ("value", &old_type.value, &new_type.value),
+            ("element", &old_type.base, &new_type.base),
+        ];
+        for (part, old_inner, new_inner) in inner {
+            match (old_inner, new_inner) {
+                (Some(old_inner), Some(new_inner)) => {
+                    if let Some(detail) = self.type_difference(old_inner, new, new_inner, seen) {
+                        return Some(format!("{} of {}: {}", part, old_type.label, detail));
+                    }
+                }
+                (None, None) => {}
+                _ => return Some(format!("{} of {} changed", part, old_type.label)),
+            }
+        }
+
+        None
+    }
+
+    fn find_at(&self, slot: &str, offset: u64) -> Option<&StorageVariable> {
+        self.storage.iter().find(|v| v.slot == slot && v.offset == offset)
+    }
+
+    /// Compares this (deployed) layout against the layout of a new implementation
+    pub fn diff(&self, new: &StorageLayout) -> LayoutDiff {
+        let mut changes = Vec::new();
+        let mut matched: Vec<&str> = Vec::new();
+
+        for old_var in &self.storage {
+            let old_type = self.type_label(&old_var.ty);
+
+            match new.storage.iter().find(|v| v.label == old_var.label) {
+                Some(new_var) => {
+                    matched.push(&new_var.label);
+                    let new_type = new.type_label(&new_var.ty);
+
+                    if new_var.slot != old_var.slot || new_var.offset != old_var.offset {
+                        changes.push(LayoutChange::Moved {
+                            label: old_var.label.clone(),
+                            from: (old_var.slot.clone(), old_var.offset),
+                            to: (new_var.slot.clone(), new_var.offset),
+                        });
+                    } else if new_type != old_type
+                        || new.type_size(&new_var.ty) != self.type_size(&old_var.ty)
+                    {
+                        if is_gap(&old_var.label) && is_smaller_array(&old_type, &new_type) {
+                            changes.push(LayoutChange::GapShrunk {
+                                label: old_var.label.clone(),
+                                from: old_type,
+                                to: new_type,
+                            });
+                        } else {
+                            changes.push(LayoutChange::TypeChanged {
+                                label: old_var.label.clone(),
+                                slot: old_var.slot.clone(),
+                                from: old_type,
+                                to: new_type,
+                            });
+                        }
+                    } else if let Some(detail) =
+                        self.type_difference(&old_var.ty, new, &new_var.ty, &mut HashSet::new())
+                    {
+                        changes.push(LayoutChange::InnerTypeChanged {
+                            label: old_var.label.clone(),
+                            slot: old_var.slot.clone(),
+                            ty: old_type,
+                            detail,
+                        });
+                    }
+                }
+                None => match new.find_at(&old_var.slot, old_var.offset) {
+                    Some(new_var)
+                        if self.type_difference(&old_var.ty, new, &new_var.ty, &mut HashSet::new()).is_none()
+                            && !self.storage.iter().any(|v| v.label == new_var.label) =>
+                    {
+                        matched.push(&new_var.label);
+                        changes.push(LayoutChange::Renamed {
+                            from: old_var.label.clone(),
+                            to: new_var.label.clone(),
+                            slot: old_var.slot.clone(),
+                            ty: old_type,
+                        });
+                    }
+                    _ => changes.push(LayoutChange::Removed {
+                        label: old_var.label.clone(),
+                        slot: old_var.slot.clone(),
+                        offset: old_var.offset,
+                        ty: old_type,
+                    }),
+                },
+            }
+        }
+
+        for new_var in &new.storage {
+            if !matched.contains(&new_var.label.as_str()) {
+                changes.push(LayoutChange::Added {
+                    label: new_var.label.clone(),
+                    slot: new_var.slot.clone(),
+                    offset: new_var.offset,
+                    ty: new.type_label(&new_var.ty),
+                });
+            }
+        }
+
+        LayoutDiff { changes }
+    }
+}
+
+fn is_gap(label: &str) -> bool {
+    label.starts_with("__gap")
+}
+
+/// `uint256[50]` -> `uint256[45]` style shrinking of a storage gap
+fn is_smaller_array(old: &str, new: &str) -> bool {
+    let split = |ty: &str| -> Option<(String, u64)> {
+        let (base, len) = ty.strip_suffix(']')?.rsplit_once('[')?;
+        Some((base.to_string(), len.parse().ok()?))
+    };
+
+    match (split(old), split(new)) {
+        (Some((old_base, old_len)), Some((new_base, new_len))) => old_base == new_base && new_len < old_len,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(vars: &[(&str, &str, u64, &str)]) -> StorageLayout {
+        let mut types = HashMap::new();
+        for (_, _, _, ty) in vars {
+            types.insert(
+                ty.to_string(),
+                StorageType {
+                    label: ty.trim_start_matches("t_").to_string(),
+                    number_of_bytes: "32".to_string(),
+                    encoding: None,
+                    members: None,
+                    key: None,
+                    value: None,
+                    base: None,
+                },
+            );
+        }
+
+        StorageLayout {
+            storage: vars
+                .iter()
+                .map(|(label, slot, offset, ty)| StorageVariable {
+                    label: label.to_string(),
+                    slot: slot.to_string(),
+                    offset: *offset,
+                    ty: ty.to_string(),
+                    contract: "contracts/Token.sol:Token".to_string(),
+                })
+                .collect(),
+            types: Some(types),
+        }
+    }
+
+    #[test]
+    fn test_appending_is_compatible() {
+        let old = layout(&[("owner", "0", 0, "t_address"), ("supply", "1", 0, "t_uint256")]);
+        let new = layout(&[
+            ("owner", "0", 0, "t_address"),
+            ("supply", "1", 0, "t_uint256"),
+            ("paused", "2", 0, "t_bool"),
+        ]);
+
+        let diff = old.diff(&new);
+        assert!(diff.is_compatible());
+        assert_eq!(diff.changes.len(), 1);
+    }
+
+    #[test]
+    fn test_reorder_and_removal_are_breaking() {
+        let old = layout(&[("owner", "0", 0, "t_address"), ("supply", "1", 0, "t_uint256")]);
+
+        let reordered = layout(&[("supply", "0", 0, "t_uint256"), ("owner", "1", 0, "t_address")]);
+        assert!(!old.diff(&reordered).is_compatible());
+
+        let removed = layout(&[("owner", "0", 0, "t_address")]);
+        let diff = old.diff(&removed);
+        assert!(!diff.is_compatible());
+        assert!(matches!(diff.breaking_changes()[0], LayoutChange::Removed { .. }));
+    }
+
+    #[test]
+    fn test_type_change_is_breaking() {
+        let old = layout(&[("supply", "0", 0, "t_uint256")]);
+        let new = layout(&[("supply", "0", 0, "t_int256")]);
+
+        let diff = old.diff(&new);
+        assert!(matches!(diff.changes[0], LayoutChange::TypeChanged { .. }));
+        assert!(!diff.is_compatible());
+    }
+
+    #[test]
+    fn test_rename_and_gap_shrink_are_allowed() {
+        let old = layout(&[("supply", "0", 0, "t_uint256"), ("__gap", "1", 0, "t_uint256[50]")]);
+        let new = layout(&[
+            ("totalSupply", "0", 0, "t_uint256"),
+            ("__gap", "1", 0, "t_uint256[49]"),
+            ("paused", "50", 0, "t_bool"),
+        ]);
+
+        let diff = old.diff(&new);
+        assert!(diff.is_compatible(), "{}", diff);
+        assert!(diff.changes.iter().any(|c| matches!(c, LayoutChange::Renamed { .. })));
+        assert!(diff.changes.iter().any(|c| matches!(c, LayoutChange::GapShrunk { .. })));
+    }
+
+    /// `info` and `infos` hold a struct of an address and a uint96 packed into one slot
+    fn struct_layout(members: &[(&str, u64, &str)]) -> StorageLayout {
+        let members: Vec<serde_json::Value> = members
+            .iter()
+            .map(|(label, offset, ty)| {
+                serde_json::json!({ "label": label, "slot": "0", "offset": offset, "type": ty })
+            })
+            .collect();
+
+        StorageLayout::from_value(&serde_json::json!({
+            "storage": [
+                { "label": "info", "slot": "0", "offset": 0, "type": "t_struct(Info)1_storage" },
+                { "label": "infos", "slot": "1", "offset": 0, "type": "t_mapping(t_address,t_struct(Info)1_storage)" }
+            ],
+            "types": {
+                "t_address": { "encoding": "inplace", "label": "address", "numberOfBytes": "20" },
+                "t_uint96": { "encoding": "inplace", "label": "uint96", "numberOfBytes": "12" },
+                "t_struct(Info)1_storage": {
+                    "encoding": "inplace",
+                    "label": "struct Token.Info",
+                    "numberOfBytes": "32",
+                    "members": members
+                },
+                "t_mapping(t_address,t_struct(Info)1_storage)": {
+                    "encoding": "mapping",
+                    "label": "mapping(address => struct Token.Info)",
+                    "numberOfBytes": "32",
+                    "key": "t_address",
+                    "value": "t_struct(Info)1_storage"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reordered_struct_members_are_breaking() {
+        let old = struct_layout(&[("owner", 0, "t_address"), ("balance", 20, "t_uint96")]);
+        assert!(old.diff(&old.clone()).changes.is_empty());
+
+        let new = struct_layout(&[("balance", 0, "t_uint96"), ("owner", 12, "t_address")]);
+        let diff = old.diff(&new);
+        assert!(!diff.is_compatible(), "{}", diff);
+        assert_eq!(diff.changes.len(), 2);
+        assert!(diff.changes.iter().all(|c| matches!(c, LayoutChange::InnerTypeChanged { .. })));
+
+        // Retyped member of the same size
+        let retyped = struct_layout(&[("owner", 0, "t_address"), ("balance", 20, "t_int96")]);
+        assert!(!old.diff(&retyped).is_compatible());
+    }
+}
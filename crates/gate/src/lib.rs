@@ -1,6 +1,6 @@
 use anyhow::Result;
-use std::fs;
 use std::path::Path;
+use x_core::artifacts::{scan_artifacts, ArtifactEntry};
 use x_core::networks::Network;
 
 pub struct Gate;
@@ -28,9 +28,11 @@ impl Gate {
         ]
     }
 
-    pub fn get_available_contracts() -> Result<Vec<String>> {
-        let contracts_path = "contracts";
-        let artifacts_path = "artifacts";
+    /// Deployable contracts compiled from sources under `contracts/`, including nested folders
+    /// and files that declare several contracts
+    pub fn get_available_contracts() -> Result<Vec<ArtifactEntry>> {
+        let contracts_path = x_core::project::contracts_dir();
+        let artifacts_path = x_core::project::artifacts_dir();
         
         if !Path::new(contracts_path).exists() {
             return Err(anyhow::anyhow!("Contracts folder not found at {}", contracts_path));
@@ -40,38 +42,20 @@ impl Gate {
             return Err(anyhow::anyhow!("Artifacts folder not found at {}", artifacts_path));
         }
 
-        let mut contracts = Vec::new();
-        
-        for entry in fs::read_dir(contracts_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.is_file() {
-                if let Some(file_name) = path.file_name() {
-                    if let Some(name_str) = file_name.to_str() {
-                        if name_str.ends_with(".sol") {
-                            let contract_name = name_str
-                                .trim_end_matches(".sol")
-                                .to_string();
-                            
-                            let artifact_path = Path::new(artifacts_path)
-                                .join(format!("{}.sol", contract_name))
-                                .join(format!("{}.json", contract_name));
-                            
-                            if artifact_path.exists() {
-                                contracts.push(contract_name);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let contracts: Vec<ArtifactEntry> = scan_artifacts(artifacts_path)?
+            .into_iter()
+            .filter(|entry| entry.deployable)
+            .filter(|entry| {
+                entry.source.as_deref().is_none_or(|source| {
+                    source.starts_with(&format!("{}/", contracts_path)) || !source.contains('/')
+                })
+            })
+            .collect();
 
         if contracts.is_empty() {
             return Err(anyhow::anyhow!("No compiled contracts found in {}", contracts_path));
         }
 
-        contracts.sort();
         Ok(contracts)
     }
 
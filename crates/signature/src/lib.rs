@@ -1,5 +1,6 @@
 pub mod sign;
 pub mod verify;
+pub mod permit;
 
-pub use sign::{sign_message, get_address_from_private_key};
+pub use sign::{sign_message, sign_hash, get_address_from_private_key};
 pub use verify::verify_message;
@@ -0,0 +1,426 @@
+use crate::sign::sign_hash;
+use anyhow::Result;
+use ethers::abi::{self, ParamType, Token};
+use ethers::providers::Middleware;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712, Eip712DomainType, TypedData, Types};
+use ethers::types::{Address, Bytes, Signature, TransactionRequest, H256, U256};
+use ethers::utils::id;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use x_core::network::HttpClient;
+
+/// Canonical Permit2 deployment, the same on every chain
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+pub fn permit2_address() -> Address {
+    PERMIT2_ADDRESS.parse().expect("valid Permit2 address")
+}
+
+/// Typed data with its digest and signature
+#[derive(Debug, Clone)]
+pub struct SignedTypedData {
+    pub typed_data: TypedData,
+    pub digest: H256,
+    pub signature: Signature,
+}
+
+impl SignedTypedData {
+    /// The typed data as `eth_signTypedData_v4` JSON
+    pub fn typed_data_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.typed_data).map_err(|e| anyhow::anyhow!("Failed to serialize typed data: {}", e))
+    }
+}
+
+/// Hashes `typed_data` per EIP-712 and signs the digest
+pub fn sign_typed_data(private_key: &str, typed_data: TypedData) -> Result<SignedTypedData> {
+    let digest = typed_data
+        .encode_eip712()
+        .map_err(|e| anyhow::anyhow!("Failed to hash typed data: {}", e))?;
+    let signature = sign_hash(private_key, digest)?;
+
+    Ok(SignedTypedData { typed_data, digest: H256::from(digest), signature })
+}
+
+fn encode_call(signature: &str, args: &[Token]) -> Bytes {
+    let mut data = id(signature).to_vec();
+    data.extend(abi::encode(args));
+    data.into()
+}
+
+async fn call(client: &HttpClient, to: Address, data: Bytes) -> Result<Bytes> {
+    let tx: TypedTransaction = TransactionRequest::new().to(to).data(data).into();
+    client
+        .get_provider()
+        .call(&tx, None)
+        .await
+        .map_err(|e| anyhow::anyhow!("Call to {:#x} failed: {}", to, e))
+}
+
+async fn call_decode(client: &HttpClient, to: Address, signature: &str, args: &[Token], outputs: &[ParamType]) -> Result<Vec<Token>> {
+    let output = call(client, to, encode_call(signature, args)).await?;
+    abi::decode(outputs, &output).map_err(|e| anyhow::anyhow!("{:#x} returned an invalid {} result: {}", to, signature, e))
+}
+
+fn types(entries: &[(&str, &[(&str, &str)])]) -> Types {
+    entries
+        .iter()
+        .map(|(name, fields)| {
+            let fields = fields
+                .iter()
+                .map(|(name, ty)| Eip712DomainType { name: name.to_string(), r#type: ty.to_string() })
+                .collect();
+            (name.to_string(), fields)
+        })
+        .collect()
+}
+
+/// The `EIP712Domain` type entry matching the fields the domain sets, for wallets that expect it
+fn domain_type(domain: &EIP712Domain) -> Vec<Eip712DomainType> {
+    let fields = [
+        ("name", "string", domain.name.is_some()),
+        ("version", "string", domain.version.is_some()),
+        ("chainId", "uint256", domain.chain_id.is_some()),
+        ("verifyingContract", "address", domain.verifying_contract.is_some()),
+        ("salt", "bytes32", domain.salt.is_some()),
+    ];
+    fields
+        .iter()
+        .filter(|(_, _, present)| *present)
+        .map(|(name, ty, _)| Eip712DomainType { name: name.to_string(), r#type: ty.to_string() })
+        .collect()
+}
+
+fn typed_data(domain: EIP712Domain, mut types: Types, primary_type: &str, message: Value) -> TypedData {
+    types.insert("EIP712Domain".to_string(), domain_type(&domain));
+    let message: BTreeMap<String, Value> = match message {
+        Value::Object(map) => map.into_iter().collect(),
+        _ => BTreeMap::new(),
+    };
+
+    TypedData { domain, types, primary_type: primary_type.to_string(), message }
+}
+
+/// Signing domain of an EIP-2612 token
+///
+/// Uses EIP-5267 `eip712Domain()` when the token has it. Otherwise rebuilds the domain from
+/// `name()` and `version()` (or the common versions "1" and "2") and keeps the one whose
+/// separator matches `DOMAIN_SEPARATOR()`.
+pub async fn token_domain(client: &HttpClient, token: Address, chain_id: u64) -> Result<EIP712Domain> {
+    let eip5267 = [
+        ParamType::FixedBytes(1),
+        ParamType::String,
+        ParamType::String,
+        ParamType::Uint(256),
+        ParamType::Address,
+        ParamType::FixedBytes(32),
+        ParamType::Array(Box::new(ParamType::Uint(256))),
+    ];
+    if let Ok(values) = call_decode(client, token, "eip712Domain()", &[], &eip5267).await
+        && let [Token::FixedBytes(fields), Token::String(name), Token::String(version), Token::Uint(chain), Token::Address(contract), Token::FixedBytes(salt), _] =
+            values.as_slice()
+    {
+        let has = |bit: u8| fields.first().is_some_and(|f| f & (1 << bit) != 0);
+        return Ok(EIP712Domain {
+            name: has(0).then(|| name.clone()),
+            version: has(1).then(|| version.clone()),
+            chain_id: has(2).then_some(*chain),
+            verifying_contract: has(3).then_some(*contract),
+            salt: has(4).then(|| salt.as_slice().try_into().unwrap_or_default()),
+        });
+    }
+
+    let name = match call_decode(client, token, "name()", &[], &[ParamType::String]).await?.as_slice() {
+        [Token::String(name)] => name.clone(),
+        _ => anyhow::bail!("{:#x} returned an invalid name()", token),
+    };
+    let version = match call_decode(client, token, "version()", &[], &[ParamType::String]).await.as_deref() {
+        Ok([Token::String(version)]) => Some(version.clone()),
+        _ => None,
+    };
+    let separator = match call_decode(client, token, "DOMAIN_SEPARATOR()", &[], &[ParamType::FixedBytes(32)]).await?.as_slice() {
+        [Token::FixedBytes(separator)] => separator.clone(),
+        _ => anyhow::bail!("{:#x} returned an invalid DOMAIN_SEPARATOR()", token),
+    };
+
+    let mut versions: Vec<Option<String>> = vec![version, Some("1".to_string()), Some("2".to_string()), None];
+    versions.dedup();
+
+    versions
+        .into_iter()
+        .map(|version| EIP712Domain {
+            name: Some(name.clone()),
+            version,
+            chain_id: Some(chain_id.into()),
+            verifying_contract: Some(token),
+            salt: None,
+        })
+        .find(|domain| domain.separator().as_slice() == separator.as_slice())
+        .ok_or_else(|| anyhow::anyhow!("Could not reproduce the DOMAIN_SEPARATOR() of {:#x}", token))
+}
+
+/// Next EIP-2612 nonce of `owner`
+pub async fn token_nonce(client: &HttpClient, token: Address, owner: Address) -> Result<U256> {
+    match call_decode(client, token, "nonces(address)", &[Token::Address(owner)], &[ParamType::Uint(256)]).await?.as_slice() {
+        [Token::Uint(nonce)] => Ok(*nonce),
+        _ => anyhow::bail!("{:#x} returned an invalid nonces()", token),
+    }
+}
+
+/// An EIP-2612 `Permit` for `token.permit(...)`
+#[derive(Debug, Clone)]
+pub struct Erc2612Permit {
+    pub owner: Address,
+    pub spender: Address,
+    pub value: U256,
+    pub nonce: U256,
+    pub deadline: U256,
+}
+
+impl Erc2612Permit {
+    pub fn typed_data(&self, domain: EIP712Domain) -> TypedData {
+        let types = types(&[(
+            "Permit",
+            &[("owner", "address"), ("spender", "address"), ("value", "uint256"), ("nonce", "uint256"), ("deadline", "uint256")],
+        )]);
+        let message = json!({
+            "owner": self.owner,
+            "spender": self.spender,
+            "value": self.value.to_string(),
+            "nonce": self.nonce.to_string(),
+            "deadline": self.deadline.to_string(),
+        });
+        typed_data(domain, types, "Permit", message)
+    }
+
+    /// `permit(owner, spender, value, deadline, v, r, s)` calldata for the token
+    pub fn calldata(&self, signature: &Signature) -> Bytes {
+        let bytes = signature.to_vec();
+        encode_call(
+            "permit(address,address,uint256,uint256,uint8,bytes32,bytes32)",
+            &[
+                Token::Address(self.owner),
+                Token::Address(self.spender),
+                Token::Uint(self.value),
+                Token::Uint(self.deadline),
+                Token::Uint(signature.v.into()),
+                Token::FixedBytes(bytes[..32].to_vec()),
+                Token::FixedBytes(bytes[32..64].to_vec()),
+            ],
+        )
+    }
+}
+
+pub fn permit2_domain(chain_id: u64) -> EIP712Domain {
+    EIP712Domain {
+        name: Some("Permit2".to_string()),
+        version: None,
+        chain_id: Some(chain_id.into()),
+        verifying_contract: Some(permit2_address()),
+        salt: None,
+    }
+}
+
+/// Nonce of the Permit2 allowance `owner` gave `spender` for `token`
+pub async fn permit2_allowance_nonce(client: &HttpClient, owner: Address, token: Address, spender: Address) -> Result<U256> {
+    let outputs = [ParamType::Uint(160), ParamType::Uint(48), ParamType::Uint(48)];
+    let args = [Token::Address(owner), Token::Address(token), Token::Address(spender)];
+    match call_decode(client, permit2_address(), "allowance(address,address,address)", &args, &outputs).await?.as_slice() {
+        [_, _, Token::Uint(nonce)] => Ok(*nonce),
+        _ => anyhow::bail!("Permit2 returned an invalid allowance()"),
+    }
+}
+
+/// Lowest unordered Permit2 nonce of `owner` that has not been used yet
+pub async fn permit2_unused_nonce(client: &HttpClient, owner: Address) -> Result<U256> {
+    for word in 0u64..256 {
+        let args = [Token::Address(owner), Token::Uint(word.into())];
+        let bitmap = match call_decode(client, permit2_address(), "nonceBitmap(address,uint256)", &args, &[ParamType::Uint(256)]).await?.as_slice() {
+            [Token::Uint(bitmap)] => *bitmap,
+            _ => anyhow::bail!("Permit2 returned an invalid nonceBitmap()"),
+        };
+
+        if let Some(bit) = (0..256).find(|bit| !bitmap.bit(*bit)) {
+            return Ok(U256::from(word) * 256 + bit);
+        }
+    }
+    anyhow::bail!("No unused Permit2 nonce in the first 65536")
+}
+
+/// A Permit2 `PermitSingle`, for `Permit2.permit(owner, permitSingle, signature)`
+#[derive(Debug, Clone)]
+pub struct PermitSingle {
+    pub token: Address,
+    /// uint160
+    pub amount: U256,
+    /// uint48 timestamp at which the allowance expires
+    pub expiration: u64,
+    /// uint48
+    pub nonce: u64,
+    pub spender: Address,
+    pub sig_deadline: U256,
+}
+
+impl PermitSingle {
+    pub fn typed_data(&self, chain_id: u64) -> TypedData {
+        let types = types(&[
+            ("PermitSingle", &[("details", "PermitDetails"), ("spender", "address"), ("sigDeadline", "uint256")]),
+            ("PermitDetails", &[("token", "address"), ("amount", "uint160"), ("expiration", "uint48"), ("nonce", "uint48")]),
+        ]);
+        let message = json!({
+            "details": {
+                "token": self.token,
+                "amount": self.amount.to_string(),
+                "expiration": self.expiration.to_string(),
+                "nonce": self.nonce.to_string(),
+            },
+            "spender": self.spender,
+            "sigDeadline": self.sig_deadline.to_string(),
+        });
+        typed_data(permit2_domain(chain_id), types, "PermitSingle", message)
+    }
+
+    pub fn calldata(&self, owner: Address, signature: &Signature) -> Bytes {
+        let details = Token::Tuple(vec![
+            Token::Address(self.token),
+            Token::Uint(self.amount),
+            Token::Uint(self.expiration.into()),
+            Token::Uint(self.nonce.into()),
+        ]);
+        encode_call(
+            "permit(address,((address,uint160,uint48,uint48),address,uint256),bytes)",
+            &[
+                Token::Address(owner),
+                Token::Tuple(vec![details, Token::Address(self.spender), Token::Uint(self.sig_deadline)]),
+                Token::Bytes(signature.to_vec()),
+            ],
+        )
+    }
+}
+
+/// A Permit2 `PermitTransferFrom`, for `Permit2.permitTransferFrom(...)` called by `spender`
+#[derive(Debug, Clone)]
+pub struct PermitTransferFrom {
+    pub token: Address,
+    pub amount: U256,
+    pub spender: Address,
+    pub nonce: U256,
+    pub deadline: U256,
+}
+
+impl PermitTransferFrom {
+    pub fn typed_data(&self, chain_id: u64) -> TypedData {
+        let types = types(&[
+            (
+                "PermitTransferFrom",
+                &[("permitted", "TokenPermissions"), ("spender", "address"), ("nonce", "uint256"), ("deadline", "uint256")],
+            ),
+            ("TokenPermissions", &[("token", "address"), ("amount", "uint256")]),
+        ]);
+        let message = json!({
+            "permitted": {
+                "token": self.token,
+                "amount": self.amount.to_string(),
+            },
+            "spender": self.spender,
+            "nonce": self.nonce.to_string(),
+            "deadline": self.deadline.to_string(),
+        });
+        typed_data(permit2_domain(chain_id), types, "PermitTransferFrom", message)
+    }
+
+    /// Calldata moving the whole permitted amount from `owner` to `to`
+    pub fn calldata(&self, owner: Address, to: Address, signature: &Signature) -> Bytes {
+        let permit = Token::Tuple(vec![
+            Token::Tuple(vec![Token::Address(self.token), Token::Uint(self.amount)]),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline),
+        ]);
+        encode_call(
+            "permitTransferFrom(((address,uint256),uint256,uint256),(address,uint256),address,bytes)",
+            &[
+                permit,
+                Token::Tuple(vec![Token::Address(to), Token::Uint(self.amount)]),
+                Token::Address(owner),
+                Token::Bytes(signature.to_vec()),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::transaction::eip712::hash_type;
+
+    const KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    #[test]
+    fn test_type_hashes_match_the_contracts() {
+        let erc2612 = Erc2612Permit {
+            owner: Address::zero(),
+            spender: Address::zero(),
+            value: U256::zero(),
+            nonce: U256::zero(),
+            deadline: U256::zero(),
+        }
+        .typed_data(EIP712Domain::default());
+        assert_eq!(
+            hex::encode(hash_type("Permit", &erc2612.types).unwrap()),
+            "6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9"
+        );
+
+        let single = PermitSingle {
+            token: Address::zero(),
+            amount: U256::zero(),
+            expiration: 0,
+            nonce: 0,
+            spender: Address::zero(),
+            sig_deadline: U256::zero(),
+        }
+        .typed_data(1);
+        assert_eq!(
+            hex::encode(hash_type("PermitSingle", &single.types).unwrap()),
+            "f3841cd1ff0085026a6327b620b67997ce40f282c88a8e905a7a5626e310f3d0"
+        );
+
+        let transfer = PermitTransferFrom {
+            token: Address::zero(),
+            amount: U256::zero(),
+            spender: Address::zero(),
+            nonce: U256::zero(),
+            deadline: U256::zero(),
+        }
+        .typed_data(1);
+        assert_eq!(
+            hex::encode(hash_type("PermitTransferFrom", &transfer.types).unwrap()),
+            "939c21a48a8dbe3a9a2404a1d46691e4d39f6583d6ec6b35714604c986d80106"
+        );
+    }
+
+    #[test]
+    fn test_signature_recovers_owner() {
+        let owner = crate::get_address_from_private_key(KEY).unwrap();
+        let permit = Erc2612Permit {
+            owner,
+            spender: Address::repeat_byte(0x22),
+            value: U256::from(1000),
+            nonce: U256::zero(),
+            deadline: U256::MAX,
+        };
+        let domain = EIP712Domain {
+            name: Some("Token".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some(1.into()),
+            verifying_contract: Some(Address::repeat_byte(0xaa)),
+            salt: None,
+        };
+
+        let signed = sign_typed_data(KEY, permit.typed_data(domain)).unwrap();
+        assert_eq!(signed.signature.recover(signed.digest).unwrap(), owner);
+
+        let calldata = permit.calldata(&signed.signature);
+        assert_eq!(&calldata[..4], &[0xd5, 0x05, 0xac, 0xcf]);
+        assert_eq!(calldata.len(), 4 + 7 * 32);
+    }
+}
@@ -1,7 +1,7 @@
 use x_core::crypto;
 use x_core::config;
 use anyhow::Result;
-use ethers::types::Address;
+use ethers::types::{Address, Signature, U256};
 use secp256k1::{Message, Secp256k1, SecretKey};
 use sha3::{Digest, Keccak256};
 
@@ -18,6 +18,17 @@ use sha3::{Digest, Keccak256};
 /// # Returns
 /// Signature as hex string with 0x prefix, total 132 characters (0x + 64 + 64 + 2)
 pub fn sign_message(private_key: &str, message: &str) -> Result<String> {
+    let message_payload = crypto::prepare_message_for_signing(message);
+    let digest: [u8; 32] = crypto::keccak256(&message_payload)
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid message digest"))?;
+
+    let signature = sign_hash(private_key, digest)?;
+    Ok(crypto::bytes_to_hex(&signature.to_vec()))
+}
+
+/// Signs a 32-byte digest as is, e.g. an EIP-712 hash, with v as 27 or 28
+pub fn sign_hash(private_key: &str, digest: [u8; 32]) -> Result<Signature> {
     let private_key_str = config::normalize_private_key(private_key);
 
     let key_bytes = crypto::hex_to_bytes(&private_key_str)?;
@@ -28,9 +39,6 @@ pub fn sign_message(private_key: &str, message: &str) -> Result<String> {
     let secret_key = SecretKey::from_slice(&key_bytes)
         .map_err(|e| anyhow::anyhow!("Invalid private key: {}", e))?;
 
-    let message_payload = crypto::prepare_message_for_signing(message);
-    let digest = crypto::keccak256(&message_payload);
-
     let message = Message::from_slice(&digest)
         .map_err(|e| anyhow::anyhow!("Invalid message digest: {}", e))?;
 
@@ -39,11 +47,11 @@ pub fn sign_message(private_key: &str, message: &str) -> Result<String> {
 
     let (recovery_id, sig_bytes) = sig.serialize_compact();
 
-    let mut signature = Vec::new();
-    signature.extend_from_slice(&sig_bytes);
-    signature.push(recovery_id.to_i32() as u8 + 27);
-
-    Ok(crypto::bytes_to_hex(&signature))
+    Ok(Signature {
+        r: U256::from_big_endian(&sig_bytes[..32]),
+        s: U256::from_big_endian(&sig_bytes[32..]),
+        v: recovery_id.to_i32() as u64 + 27,
+    })
 }
 
 /// Derives the Ethereum address from a private key
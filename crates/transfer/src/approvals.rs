@@ -0,0 +1,194 @@
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::utils::keccak256;
+use std::collections::BTreeSet;
+use x_core::gas::GasStrategy;
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use anyhow::Result;
+
+use crate::nft::owner_of;
+use crate::sender::{call, call_uint, encode_call, send_calls, ContractCall};
+
+/// Blocks per `eth_getLogs` request, most public RPCs accept this range
+pub const LOG_CHUNK_SIZE: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApprovalKind {
+    /// ERC-20 allowance
+    Allowance(U256),
+    /// ERC-721 approval of a single token id
+    Token(U256),
+    /// ERC-721/ERC-1155 `setApprovalForAll`
+    All,
+}
+
+/// An approval still in effect
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LiveApproval {
+    pub spender: Address,
+    pub token: Address,
+    pub kind: ApprovalKind,
+}
+
+impl LiveApproval {
+    /// The call that zeroes this approval out
+    pub fn revoke_call(&self) -> ContractCall {
+        let data = match self.kind {
+            ApprovalKind::Allowance(_) => {
+                encode_call("approve(address,uint256)", &[Token::Address(self.spender), Token::Uint(U256::zero())])
+            }
+            ApprovalKind::Token(id) => {
+                encode_call("approve(address,uint256)", &[Token::Address(Address::zero()), Token::Uint(id)])
+            }
+            ApprovalKind::All => {
+                encode_call("setApprovalForAll(address,bool)", &[Token::Address(self.spender), Token::Bool(false)])
+            }
+        };
+        ContractCall::new(self.token, data)
+    }
+}
+
+/// Approvals granted at some point, before checking whether they still hold
+#[derive(Debug, Default, PartialEq)]
+pub struct ApprovalCandidates {
+    /// `(token, spender)` ERC-20 allowances
+    pub allowances: BTreeSet<(Address, Address)>,
+    /// `(token, id, approved)` ERC-721 token approvals
+    pub tokens: BTreeSet<(Address, U256, Address)>,
+    /// `(token, operator)` approvals for all
+    pub operators: BTreeSet<(Address, Address)>,
+}
+
+impl ApprovalCandidates {
+    /// Collects every approval granted in `logs`; ERC-20 and ERC-721 `Approval` share a topic
+    /// and are told apart by the indexed token id
+    pub fn from_logs(logs: &[Log]) -> Self {
+        let approval = H256::from(keccak256("Approval(address,address,uint256)"));
+        let approval_for_all = H256::from(keccak256("ApprovalForAll(address,address,bool)"));
+
+        let mut candidates = Self::default();
+        for log in logs {
+            match log.topics.as_slice() {
+                [topic, _, spender] if *topic == approval => {
+                    candidates.allowances.insert((log.address, Address::from(*spender)));
+                }
+                [topic, _, approved, id] if *topic == approval => {
+                    let id = U256::from_big_endian(id.as_bytes());
+                    // Later approvals of the same id replace earlier ones
+                    candidates.tokens.retain(|(token, other, _)| !(*token == log.address && *other == id));
+                    candidates.tokens.insert((log.address, id, Address::from(*approved)));
+                }
+                [topic, _, operator] if *topic == approval_for_all => {
+                    candidates.operators.insert((log.address, Address::from(*operator)));
+                }
+                _ => {}
+            }
+        }
+        candidates
+    }
+}
+
+/// Scans the `Approval` and `ApprovalForAll` logs of `owner` and keeps the approvals
+/// that are still live on-chain, sorted by spender
+pub async fn scan_approvals(client: &HttpClient, owner: Address, from_block: u64, to_block: u64) -> Result<Vec<LiveApproval>> {
+    let filter = Filter::new()
+        .topic0(vec![
+            H256::from(keccak256("Approval(address,address,uint256)")),
+            H256::from(keccak256("ApprovalForAll(address,address,bool)")),
+        ])
+        .topic1(H256::from(owner));
+
+    let logs = client.get_logs_in_range(&filter, from_block, to_block, LOG_CHUNK_SIZE).await?;
+    let candidates = ApprovalCandidates::from_logs(&logs);
+
+    let mut live = Vec::new();
+
+    for (token, spender) in candidates.allowances {
+        let args = [Token::Address(owner), Token::Address(spender)];
+        // Tokens that self-destructed or stopped answering are skipped
+        if let Ok(allowance) = call_uint(client, token, "allowance(address,address)", &args).await
+            && !allowance.is_zero()
+        {
+            live.push(LiveApproval { spender, token, kind: ApprovalKind::Allowance(allowance) });
+        }
+    }
+
+    for (token, id, approved) in candidates.tokens {
+        if approved.is_zero() || owner_of(client, token, id).await.ok() != Some(owner) {
+            continue;
+        }
+        let output = call(client, token, encode_call("getApproved(uint256)", &[Token::Uint(id)])).await;
+        if let Ok(output) = output
+            && matches!(abi::decode(&[ParamType::Address], &output).as_deref(), Ok([Token::Address(current)]) if *current == approved)
+        {
+            live.push(LiveApproval { spender: approved, token, kind: ApprovalKind::Token(id) });
+        }
+    }
+
+    for (token, operator) in candidates.operators {
+        let data = encode_call("isApprovedForAll(address,address)", &[Token::Address(owner), Token::Address(operator)]);
+        if let Ok(output) = call(client, token, data).await
+            && matches!(abi::decode(&[ParamType::Bool], &output).as_deref(), Ok([Token::Bool(true)]))
+        {
+            live.push(LiveApproval { spender: operator, token, kind: ApprovalKind::All });
+        }
+    }
+
+    live.sort();
+    Ok(live)
+}
+
+/// Revokes every approval in one pipelined batch, returning the receipts in the same order
+pub async fn revoke_approvals(
+    private_key: &str,
+    network: &Network,
+    approvals: &[LiveApproval],
+    gas_strategy: GasStrategy,
+) -> Result<Vec<TransactionReceipt>> {
+    let calls: Vec<ContractCall> = approvals.iter().map(LiveApproval::revoke_call).collect();
+    send_calls(private_key, network, &calls, gas_strategy).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_from_logs() {
+        let approval = H256::from(keccak256("Approval(address,address,uint256)"));
+        let approval_for_all = H256::from(keccak256("ApprovalForAll(address,address,bool)"));
+        let owner = H256::from(Address::repeat_byte(0x01));
+        let token = Address::repeat_byte(0xaa);
+        let nft = Address::repeat_byte(0xbb);
+        let spender = Address::repeat_byte(0x22);
+        let log = |address: Address, topics: Vec<H256>| Log { address, topics, ..Default::default() };
+
+        let logs = vec![
+            log(token, vec![approval, owner, H256::from(spender)]),
+            log(token, vec![approval, owner, H256::from(spender)]),
+            log(nft, vec![approval, owner, H256::from(spender), H256::from_low_u64_be(5)]),
+            log(nft, vec![approval, owner, H256::from(Address::zero()), H256::from_low_u64_be(5)]),
+            log(nft, vec![approval_for_all, owner, H256::from(spender)]),
+        ];
+
+        let candidates = ApprovalCandidates::from_logs(&logs);
+        assert_eq!(candidates.allowances, BTreeSet::from([(token, spender)]));
+        assert_eq!(candidates.tokens, BTreeSet::from([(nft, U256::from(5), Address::zero())]));
+        assert_eq!(candidates.operators, BTreeSet::from([(nft, spender)]));
+    }
+
+    #[test]
+    fn test_revoke_calls() {
+        let token = Address::repeat_byte(0xaa);
+        let spender = Address::repeat_byte(0x22);
+
+        let allowance = LiveApproval { spender, token, kind: ApprovalKind::Allowance(100.into()) }.revoke_call();
+        assert_eq!(allowance.to, token);
+        assert_eq!(&allowance.data[..4], &[0x09, 0x5e, 0xa7, 0xb3]);
+        assert!(allowance.data[36..].iter().all(|b| *b == 0));
+
+        let all = LiveApproval { spender, token, kind: ApprovalKind::All }.revoke_call();
+        assert_eq!(&all.data[..4], &[0xa2, 0x2c, 0xb4, 0x65]);
+    }
+}
@@ -0,0 +1,104 @@
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use x_core::gas::GasStrategy;
+use x_core::invoker::multicall::{decode_aggregate3, encode_aggregate3_value, ensure_multicall, Call3Value, CallResult};
+use x_core::invoker::EncodedCall;
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use anyhow::Result;
+
+use crate::sender::{send_call, send_calls, signer_address, ContractCall};
+
+/// Receipt of one sequentially sent call
+#[derive(Debug, Clone)]
+pub struct BatchTxResult {
+    pub receipt: TransactionReceipt,
+    /// Return data from simulating the call before sending, `None` when the simulation reverted
+    pub return_data: Option<Bytes>,
+}
+
+async fn simulate(client: &HttpClient, from: Address, to: Address, value: U256, data: Bytes) -> Result<Bytes> {
+    let tx: TypedTransaction = TransactionRequest::new().from(from).to(to).value(value).data(data).into();
+    client
+        .get_provider()
+        .call(&tx, None)
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// Sends every call in one `aggregate3Value` transaction through `batcher` (Multicall3 unless
+/// given), so a single revert reverts them all
+///
+/// The batch is simulated first for the per-call return data, and so that a failing batch stops
+/// before it costs gas. Calls run with the batcher as `msg.sender`.
+pub async fn send_batch_atomic(
+    private_key: &str,
+    network: &Network,
+    batcher: Option<Address>,
+    calls: &[EncodedCall],
+    gas_strategy: GasStrategy,
+) -> Result<(Vec<CallResult>, TransactionReceipt)> {
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::for_network(rpc_url, network).await?;
+
+    let batcher = match batcher {
+        Some(batcher) => {
+            if client.get_code(batcher).await?.is_empty() {
+                anyhow::bail!("No contract at batcher address {:#x}", batcher);
+            }
+            batcher
+        }
+        None => ensure_multicall(&client, network.chain_id, x_core::project::artifacts_dir()).await?,
+    };
+
+    let batch: Vec<Call3Value> = calls.iter().map(|c| c.to_call3_value(false)).collect();
+    let data = encode_aggregate3_value(&batch);
+    let value = calls
+        .iter()
+        .try_fold(U256::zero(), |sum, c| sum.checked_add(c.value))
+        .ok_or_else(|| anyhow::anyhow!("Total value of the batch overflows uint256"))?;
+
+    let from = signer_address(private_key)?;
+    let output = simulate(&client, from, batcher, value, data.clone())
+        .await
+        .map_err(|e| anyhow::anyhow!("Batch would revert: {}", e))?;
+    let results = decode_aggregate3(&output)?;
+
+    let call = ContractCall::new(batcher, data).with_value(value);
+    let receipt = send_call(private_key, network, call, gas_strategy).await?;
+    Ok((results, receipt))
+}
+
+/// Sends every call as its own transaction with pipelined nonces, in order
+///
+/// Each call is simulated against the current state first for its return data, calls that
+/// depend on an earlier one of the batch may only succeed once sent.
+pub async fn send_batch_sequential(
+    private_key: &str,
+    network: &Network,
+    calls: &[EncodedCall],
+    gas_strategy: GasStrategy,
+) -> Result<Vec<BatchTxResult>> {
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::for_network(rpc_url, network).await?;
+    let from = signer_address(private_key)?;
+
+    let mut simulated = Vec::with_capacity(calls.len());
+    for call in calls {
+        simulated.push(simulate(&client, from, call.target, call.value, call.data.clone()).await.ok());
+    }
+
+    let contract_calls: Vec<ContractCall> = calls
+        .iter()
+        .map(|c| ContractCall::new(c.target, c.data.clone()).with_value(c.value))
+        .collect();
+    let receipts = send_calls(private_key, network, &contract_calls, gas_strategy).await?;
+
+    Ok(receipts
+        .into_iter()
+        .zip(simulated)
+        .map(|(receipt, return_data)| BatchTxResult { receipt, return_data })
+        .collect())
+}
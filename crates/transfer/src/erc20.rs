@@ -0,0 +1,292 @@
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::utils::{format_units, keccak256, parse_units, ParseUnits};
+use x_core::gas::GasStrategy;
+use x_core::invoker::multicall::{self, Call3};
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use anyhow::Result;
+
+use crate::sender::{call, call_uint, encode_call, send_call, signer_address, ContractCall};
+
+/// On-chain metadata of an ERC-20 token
+#[derive(Debug, Clone)]
+pub struct TokenInfo {
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TokenInfo {
+    /// Raw amount for a human amount such as `1.5`, or `max` for the largest allowance
+    pub fn parse_amount(&self, amount: &str) -> Result<U256> {
+        let amount = amount.trim();
+        if amount.eq_ignore_ascii_case("max") {
+            return Ok(U256::MAX);
+        }
+        if amount.starts_with('-') {
+            anyhow::bail!("Invalid amount {}: must not be negative", amount);
+        }
+        if amount.split_once('.').is_some_and(|(_, fraction)| fraction.len() > self.decimals as usize) {
+            anyhow::bail!("Invalid amount {}: {} only has {} decimals", amount, self.symbol, self.decimals);
+        }
+
+        match parse_units(amount, self.decimals as u32).map_err(|e| anyhow::anyhow!("Invalid amount {}: {}", amount, e))? {
+            ParseUnits::U256(raw) => Ok(raw),
+            ParseUnits::I256(_) => anyhow::bail!("Invalid amount {}: must not be negative", amount),
+        }
+    }
+
+    pub fn format_amount(&self, raw: U256) -> String {
+        if raw == U256::MAX {
+            return format!("unlimited {}", self.symbol);
+        }
+        let amount = format_units(raw, self.decimals as u32).unwrap_or_else(|_| raw.to_string());
+        format!("{} {}", amount, self.symbol)
+    }
+}
+
+/// A `Transfer` or `Approval` event emitted by the token
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenEvent {
+    Transfer { from: Address, to: Address, value: U256 },
+    Approval { owner: Address, spender: Address, value: U256 },
+}
+
+#[derive(Debug)]
+pub struct TokenTxResult {
+    pub tx_hash: String,
+    pub events: Vec<TokenEvent>,
+}
+
+/// Reads `symbol()` and `decimals()`; older tokens returning `bytes32` symbols are supported
+pub async fn token_info(client: &HttpClient, token: Address) -> Result<TokenInfo> {
+    let decimals = call_uint(client, token, "decimals()", &[]).await?;
+    if decimals > U256::from(u8::MAX) {
+        anyhow::bail!("Token {:#x} reports invalid decimals: {}", token, decimals);
+    }
+
+    let output = call(client, token, encode_call("symbol()", &[])).await?;
+    let symbol = match abi::decode(&[ParamType::String], &output) {
+        Ok(tokens) => tokens.into_iter().next().and_then(|t| t.into_string()).unwrap_or_default(),
+        Err(_) => String::from_utf8_lossy(&output[..output.len().min(32)]).trim_end_matches('\0').to_string(),
+    };
+
+    Ok(TokenInfo { address: token, symbol, decimals: decimals.as_u32() as u8 })
+}
+
+pub async fn balance_of(client: &HttpClient, token: Address, owner: Address) -> Result<U256> {
+    call_uint(client, token, "balanceOf(address)", &[Token::Address(owner)]).await
+}
+
+/// Balances of many owners in one Multicall3 round-trip, `None` where the call failed
+pub async fn balances_of(client: &HttpClient, chain_id: u64, token: Address, owners: &[Address]) -> Result<Vec<Option<U256>>> {
+    let calls: Vec<Call3> = owners
+        .iter()
+        .map(|owner| Call3::new(token, encode_call("balanceOf(address)", &[Token::Address(*owner)])))
+        .collect();
+
+    let results = multicall::aggregate(client, chain_id, &calls).await?;
+    Ok(results
+        .iter()
+        .map(|result| {
+            (result.success && result.return_data.len() >= 32).then(|| U256::from_big_endian(&result.return_data[..32]))
+        })
+        .collect())
+}
+
+pub async fn allowance(client: &HttpClient, token: Address, owner: Address, spender: Address) -> Result<U256> {
+    call_uint(client, token, "allowance(address,address)", &[Token::Address(owner), Token::Address(spender)]).await
+}
+
+/// `Transfer` and `Approval` events of `token` in the receipt
+pub fn decode_token_events(receipt: &TransactionReceipt, token: Address) -> Vec<TokenEvent> {
+    let transfer = H256::from(keccak256("Transfer(address,address,uint256)"));
+    let approval = H256::from(keccak256("Approval(address,address,uint256)"));
+
+    receipt
+        .logs
+        .iter()
+        // ERC-721 transfers index the token id and have a fourth topic
+        .filter(|log| log.address == token && log.topics.len() == 3 && log.data.len() == 32)
+        .filter_map(|log| {
+            let first = Address::from(log.topics[1]);
+            let second = Address::from(log.topics[2]);
+            let value = U256::from_big_endian(&log.data);
+
+            if log.topics[0] == transfer {
+                Some(TokenEvent::Transfer { from: first, to: second, value })
+            } else if log.topics[0] == approval {
+                Some(TokenEvent::Approval { owner: first, spender: second, value })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Sends `data` to the token and decodes its events from the receipt
+async fn send_token_tx(
+    private_key: &str,
+    network: &Network,
+    token: Address,
+    data: Bytes,
+    gas_strategy: GasStrategy,
+) -> Result<TokenTxResult> {
+    let receipt = send_call(private_key, network, ContractCall::new(token, data), gas_strategy).await?;
+
+    Ok(TokenTxResult {
+        tx_hash: format!("{:?}", receipt.transaction_hash),
+        events: decode_token_events(&receipt, token),
+    })
+}
+
+/// Transfers `amount` (human units) of the token to `to`
+///
+/// With `from` set to another account, spends its allowance through `transferFrom`.
+/// The balance, and the allowance when needed, are checked before sending.
+pub async fn transfer_token_with_strategy_async(
+    private_key: &str,
+    network: &Network,
+    token: Address,
+    to: Address,
+    amount: &str,
+    from: Option<Address>,
+    gas_strategy: GasStrategy,
+) -> Result<(TokenInfo, TokenTxResult)> {
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::new(rpc_url).await?;
+
+    let info = token_info(&client, token).await?;
+    let value = info.parse_amount(amount)?;
+    if value == U256::MAX {
+        anyhow::bail!("'max' is only valid for approvals");
+    }
+
+    let signer = signer_address(private_key)?;
+    let owner = from.unwrap_or(signer);
+
+    let balance = balance_of(&client, token, owner).await?;
+    if balance < value {
+        anyhow::bail!(
+            "Insufficient balance: {:#x} holds {}, transfer needs {}",
+            owner,
+            info.format_amount(balance),
+            info.format_amount(value)
+        );
+    }
+
+    let data = if owner == signer {
+        encode_call("transfer(address,uint256)", &[Token::Address(to), Token::Uint(value)])
+    } else {
+        let allowed = allowance(&client, token, owner, signer).await?;
+        if allowed < value {
+            anyhow::bail!(
+                "Insufficient allowance: {:#x} allows {:#x} to spend {}, transfer needs {}",
+                owner,
+                signer,
+                info.format_amount(allowed),
+                info.format_amount(value)
+            );
+        }
+        encode_call(
+            "transferFrom(address,address,uint256)",
+            &[Token::Address(owner), Token::Address(to), Token::Uint(value)],
+        )
+    };
+
+    let result = send_token_tx(private_key, network, token, data, gas_strategy).await?;
+    Ok((info, result))
+}
+
+/// Approves `spender` for `amount` (human units, or `max`)
+///
+/// Tokens such as USDT refuse to change a non-zero allowance to another non-zero value,
+/// so an existing allowance is reset to zero first in that case.
+pub async fn approve_token_with_strategy_async(
+    private_key: &str,
+    network: &Network,
+    token: Address,
+    spender: Address,
+    amount: &str,
+    gas_strategy: GasStrategy,
+) -> Result<(TokenInfo, TokenTxResult)> {
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::new(rpc_url).await?;
+
+    let info = token_info(&client, token).await?;
+    let value = info.parse_amount(amount)?;
+
+    let owner = signer_address(private_key)?;
+    let current = allowance(&client, token, owner, spender).await?;
+    if current == value {
+        anyhow::bail!("Allowance of {:#x} is already {}", spender, info.format_amount(value));
+    }
+
+    let approve = |value: U256| encode_call("approve(address,uint256)", &[Token::Address(spender), Token::Uint(value)]);
+
+    let mut events = Vec::new();
+    if !current.is_zero() && !value.is_zero() {
+        events.extend(send_token_tx(private_key, network, token, approve(U256::zero()), gas_strategy).await?.events);
+    }
+
+    let mut result = send_token_tx(private_key, network, token, approve(value), gas_strategy).await?;
+    events.append(&mut result.events);
+    result.events = events;
+
+    Ok((info, result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_amounts() {
+        let usdc = TokenInfo { address: Address::zero(), symbol: "USDC".to_string(), decimals: 6 };
+
+        assert_eq!(usdc.parse_amount("1.5").unwrap(), U256::from(1_500_000u64));
+        assert_eq!(usdc.parse_amount("MAX").unwrap(), U256::MAX);
+        assert!(usdc.parse_amount("1.0000001").is_err());
+        assert!(usdc.parse_amount("-1").is_err());
+        assert!(usdc.parse_amount(" -0.5").is_err());
+
+        assert_eq!(usdc.format_amount(U256::from(2_500_000u64)), "2.500000 USDC");
+        assert_eq!(usdc.format_amount(U256::MAX), "unlimited USDC");
+    }
+
+    #[test]
+    fn test_decode_token_events() {
+        let token: Address = "0x00000000000000000000000000000000000000aa".parse().unwrap();
+        let from = H256::from(Address::repeat_byte(0x11));
+        let to = H256::from(Address::repeat_byte(0x22));
+        let mut value = [0u8; 32];
+        U256::from(100u64).to_big_endian(&mut value);
+
+        let log = |topic0: &str, topics: Vec<H256>| Log {
+            address: token,
+            topics: [vec![H256::from(keccak256(topic0))], topics].concat(),
+            data: value.to_vec().into(),
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            logs: vec![
+                log("Transfer(address,address,uint256)", vec![from, to]),
+                log("Approval(address,address,uint256)", vec![from, to]),
+                // ERC-721 style transfer, skipped
+                log("Transfer(address,address,uint256)", vec![from, to, to]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            decode_token_events(&receipt, token),
+            vec![
+                TokenEvent::Transfer { from: Address::repeat_byte(0x11), to: Address::repeat_byte(0x22), value: 100u64.into() },
+                TokenEvent::Approval { owner: Address::repeat_byte(0x11), spender: Address::repeat_byte(0x22), value: 100u64.into() },
+            ]
+        );
+    }
+}
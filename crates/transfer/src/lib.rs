@@ -1,4 +1,9 @@
 pub mod transfer_eth;
+pub mod erc20;
+pub mod sender;
+pub mod nft;
+pub mod approvals;
+pub mod batch;
 
 pub use transfer_eth::{transfer_eth, transfer_eth_async, transfer_eth_with_strategy_async};
 pub use x_core::gas::GasStrategy;
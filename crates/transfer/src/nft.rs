@@ -0,0 +1,312 @@
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::utils::keccak256;
+use x_core::gas::GasStrategy;
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use anyhow::Result;
+
+use crate::sender::{call, call_uint, encode_call, send_call, send_calls, signer_address, ContractCall};
+
+const ERC721_INTERFACE: [u8; 4] = [0x80, 0xac, 0x58, 0xcd];
+const ERC1155_INTERFACE: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NftStandard {
+    Erc721,
+    Erc1155,
+}
+
+/// A transfer of one token id, from either standard
+#[derive(Debug, Clone, PartialEq)]
+pub struct NftTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub id: U256,
+    pub amount: U256,
+}
+
+/// Outcome of one mint of a batch
+#[derive(Debug)]
+pub struct MintResult {
+    pub recipient: Address,
+    pub tx_hash: String,
+    pub success: bool,
+    /// Token ids minted to the recipient, read from the `Transfer` events
+    pub token_ids: Vec<U256>,
+}
+
+async fn supports_interface(client: &HttpClient, nft: Address, interface: [u8; 4]) -> bool {
+    let data = encode_call("supportsInterface(bytes4)", &[Token::FixedBytes(interface.to_vec())]);
+    match call(client, nft, data).await {
+        Ok(output) => matches!(abi::decode(&[ParamType::Bool], &output).as_deref(), Ok([Token::Bool(true)])),
+        Err(_) => false,
+    }
+}
+
+/// Detects the standard through ERC-165
+pub async fn detect_standard(client: &HttpClient, nft: Address) -> Result<NftStandard> {
+    if supports_interface(client, nft, ERC721_INTERFACE).await {
+        Ok(NftStandard::Erc721)
+    } else if supports_interface(client, nft, ERC1155_INTERFACE).await {
+        Ok(NftStandard::Erc1155)
+    } else {
+        anyhow::bail!("{:#x} supports neither ERC-721 nor ERC-1155", nft)
+    }
+}
+
+/// ERC-721 `Transfer` and ERC-1155 `TransferSingle`/`TransferBatch` events of `nft` in the receipt
+pub fn decode_nft_transfers(receipt: &TransactionReceipt, nft: Address) -> Vec<NftTransfer> {
+    let transfer = H256::from(keccak256("Transfer(address,address,uint256)"));
+    let single = H256::from(keccak256("TransferSingle(address,address,address,uint256,uint256)"));
+    let batch = H256::from(keccak256("TransferBatch(address,address,address,uint256[],uint256[])"));
+
+    let mut transfers = Vec::new();
+    for log in receipt.logs.iter().filter(|log| log.address == nft) {
+        match log.topics.as_slice() {
+            // ERC-20 transfers keep the amount in data and have only three topics
+            [topic, from, to, id] if *topic == transfer => transfers.push(NftTransfer {
+                from: Address::from(*from),
+                to: Address::from(*to),
+                id: U256::from_big_endian(id.as_bytes()),
+                amount: U256::one(),
+            }),
+            [topic, _, from, to] if *topic == single => {
+                if let Ok(values) = abi::decode(&[ParamType::Uint(256), ParamType::Uint(256)], &log.data)
+                    && let [Token::Uint(id), Token::Uint(amount)] = values.as_slice()
+                {
+                    transfers.push(NftTransfer { from: Address::from(*from), to: Address::from(*to), id: *id, amount: *amount });
+                }
+            }
+            [topic, _, from, to] if *topic == batch => {
+                let array = ParamType::Array(Box::new(ParamType::Uint(256)));
+                if let Ok(values) = abi::decode(&[array.clone(), array], &log.data)
+                    && let [Token::Array(ids), Token::Array(amounts)] = values.as_slice()
+                {
+                    for (id, amount) in ids.iter().zip(amounts) {
+                        if let (Some(id), Some(amount)) = (id.clone().into_uint(), amount.clone().into_uint()) {
+                            transfers.push(NftTransfer { from: Address::from(*from), to: Address::from(*to), id, amount });
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    transfers
+}
+
+/// Calls `safeMint(to)` once per recipient, pipelining the transactions
+pub async fn mint_batch(
+    private_key: &str,
+    network: &Network,
+    nft: Address,
+    recipients: &[Address],
+    gas_strategy: GasStrategy,
+) -> Result<Vec<MintResult>> {
+    let calls: Vec<ContractCall> = recipients
+        .iter()
+        .map(|to| ContractCall::new(nft, encode_call("safeMint(address)", &[Token::Address(*to)])))
+        .collect();
+
+    let receipts = send_calls(private_key, network, &calls, gas_strategy).await?;
+
+    Ok(recipients
+        .iter()
+        .zip(receipts)
+        .map(|(recipient, receipt)| MintResult {
+            recipient: *recipient,
+            tx_hash: format!("{:?}", receipt.transaction_hash),
+            success: receipt.status == Some(1u64.into()),
+            token_ids: decode_nft_transfers(&receipt, nft)
+                .into_iter()
+                .filter(|t| t.from.is_zero() && t.to == *recipient)
+                .map(|t| t.id)
+                .collect(),
+        })
+        .collect())
+}
+
+/// Transfers tokens from the signer with `safeTransferFrom`
+///
+/// ERC-721 takes one id and no amounts; ERC-1155 takes an amount per id and
+/// uses `safeBatchTransferFrom` for several ids. Ownership is checked first.
+pub async fn transfer_nft(
+    private_key: &str,
+    network: &Network,
+    nft: Address,
+    to: Address,
+    ids: &[U256],
+    amounts: &[U256],
+    gas_strategy: GasStrategy,
+) -> Result<(String, Vec<NftTransfer>)> {
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let client = HttpClient::new(rpc_url).await?;
+    let from = signer_address(private_key)?;
+
+    let data = match detect_standard(&client, nft).await? {
+        NftStandard::Erc721 => {
+            let [id] = ids else {
+                anyhow::bail!("ERC-721 transfers take exactly one token id");
+            };
+            if !amounts.is_empty() {
+                anyhow::bail!("ERC-721 transfers do not take amounts");
+            }
+
+            let owner = owner_of(&client, nft, *id).await?;
+            if owner != from {
+                anyhow::bail!("Token {} is owned by {:#x}, not {:#x}", id, owner, from);
+            }
+            encode_call(
+                "safeTransferFrom(address,address,uint256)",
+                &[Token::Address(from), Token::Address(to), Token::Uint(*id)],
+            )
+        }
+        NftStandard::Erc1155 => {
+            if ids.is_empty() || ids.len() != amounts.len() {
+                anyhow::bail!("ERC-1155 transfers take one amount per token id");
+            }
+
+            let balances = balance_of_batch(&client, nft, &vec![from; ids.len()], ids).await?;
+            for ((id, amount), balance) in ids.iter().zip(amounts).zip(&balances) {
+                if balance < amount {
+                    anyhow::bail!("Insufficient balance of token {}: {:#x} holds {}, transfer needs {}", id, from, balance, amount);
+                }
+            }
+
+            if let ([id], [amount]) = (ids, amounts) {
+                encode_call(
+                    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+                    &[Token::Address(from), Token::Address(to), Token::Uint(*id), Token::Uint(*amount), Token::Bytes(Vec::new())],
+                )
+            } else {
+                encode_call(
+                    "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+                    &[
+                        Token::Address(from),
+                        Token::Address(to),
+                        Token::Array(ids.iter().map(|id| Token::Uint(*id)).collect()),
+                        Token::Array(amounts.iter().map(|amount| Token::Uint(*amount)).collect()),
+                        Token::Bytes(Vec::new()),
+                    ],
+                )
+            }
+        }
+    };
+
+    let receipt = send_call(private_key, network, ContractCall::new(nft, data), gas_strategy).await?;
+    Ok((format!("{:?}", receipt.transaction_hash), decode_nft_transfers(&receipt, nft)))
+}
+
+pub async fn owner_of(client: &HttpClient, nft: Address, id: U256) -> Result<Address> {
+    let output = call(client, nft, encode_call("ownerOf(uint256)", &[Token::Uint(id)])).await?;
+    match abi::decode(&[ParamType::Address], &output).as_deref() {
+        Ok([Token::Address(owner)]) => Ok(*owner),
+        _ => Err(anyhow::anyhow!("{:#x} returned an invalid ownerOf result", nft)),
+    }
+}
+
+/// Token ids held by `owner` on an ERC721Enumerable contract
+pub async fn owned_tokens(client: &HttpClient, nft: Address, owner: Address) -> Result<Vec<U256>> {
+    let balance = call_uint(client, nft, "balanceOf(address)", &[Token::Address(owner)]).await?;
+
+    let count: u64 = balance
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("{:#x} reported an implausible balance of {} for {:#x}", nft, balance, owner))?;
+
+    let mut ids = Vec::new();
+    for index in 0..count {
+        let id = call_uint(
+            client,
+            nft,
+            "tokenOfOwnerByIndex(address,uint256)",
+            &[Token::Address(owner), Token::Uint(index.into())],
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("{} (is the contract ERC721Enumerable?)", e))?;
+        ids.push(id);
+    }
+    Ok(ids)
+}
+
+/// ERC-1155 `balanceOfBatch`, one balance per `(owner, id)` pair
+pub async fn balance_of_batch(client: &HttpClient, nft: Address, owners: &[Address], ids: &[U256]) -> Result<Vec<U256>> {
+    let data = encode_call(
+        "balanceOfBatch(address[],uint256[])",
+        &[
+            Token::Array(owners.iter().map(|o| Token::Address(*o)).collect()),
+            Token::Array(ids.iter().map(|id| Token::Uint(*id)).collect()),
+        ],
+    );
+    let output = call(client, nft, data).await?;
+
+    match abi::decode(&[ParamType::Array(Box::new(ParamType::Uint(256)))], &output) {
+        Ok(mut tokens) => match tokens.pop() {
+            Some(Token::Array(values)) => Ok(values.into_iter().filter_map(|v| v.into_uint()).collect()),
+            _ => Err(anyhow::anyhow!("{:#x} returned an invalid balanceOfBatch result", nft)),
+        },
+        Err(e) => Err(anyhow::anyhow!("{:#x} returned an invalid balanceOfBatch result: {}", nft, e)),
+    }
+}
+
+/// `tokenURI(id)` for ERC-721, or `uri(id)` for ERC-1155 with its `{id}` placeholder filled in
+pub async fn token_uri(client: &HttpClient, nft: Address, id: U256) -> Result<String> {
+    let standard = detect_standard(client, nft).await?;
+    let signature = match standard {
+        NftStandard::Erc721 => "tokenURI(uint256)",
+        NftStandard::Erc1155 => "uri(uint256)",
+    };
+
+    let output = call(client, nft, encode_call(signature, &[Token::Uint(id)])).await?;
+    let uri = match abi::decode(&[ParamType::String], &output).as_deref() {
+        Ok([Token::String(uri)]) => uri.clone(),
+        _ => anyhow::bail!("{:#x} returned an invalid {} result", nft, signature),
+    };
+
+    Ok(match standard {
+        NftStandard::Erc1155 => uri.replace("{id}", &format!("{:064x}", id)),
+        NftStandard::Erc721 => uri,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_nft_transfers() {
+        let nft: Address = "0x00000000000000000000000000000000000000aa".parse().unwrap();
+        let operator = H256::from(Address::repeat_byte(0x33));
+        let from = H256::from(Address::zero());
+        let to = H256::from(Address::repeat_byte(0x22));
+        let topic = |signature: &str| H256::from(keccak256(signature));
+
+        let receipt = TransactionReceipt {
+            logs: vec![
+                Log {
+                    address: nft,
+                    topics: vec![topic("Transfer(address,address,uint256)"), from, to, H256::from_low_u64_be(7)],
+                    ..Default::default()
+                },
+                Log {
+                    address: nft,
+                    topics: vec![topic("TransferBatch(address,address,address,uint256[],uint256[])"), operator, from, to],
+                    data: abi::encode(&[
+                        Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+                        Token::Array(vec![Token::Uint(10.into()), Token::Uint(20.into())]),
+                    ])
+                    .into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let transfers = decode_nft_transfers(&receipt, nft);
+        assert_eq!(transfers.len(), 3);
+        assert_eq!(transfers[0], NftTransfer { from: Address::zero(), to: Address::repeat_byte(0x22), id: 7.into(), amount: 1.into() });
+        assert_eq!(transfers[2].id, 2.into());
+        assert_eq!(transfers[2].amount, 20.into());
+    }
+}
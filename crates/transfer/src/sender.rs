@@ -0,0 +1,159 @@
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::id;
+use ethers::types::Eip1559TransactionRequest;
+use x_core::gas::{GasCalculator, GasStrategy};
+use x_core::network::HttpClient;
+use x_core::networks::Network;
+use anyhow::Result;
+
+use crate::transfer_eth::check_eip1559_support;
+
+/// A call to send to a contract
+#[derive(Debug, Clone)]
+pub struct ContractCall {
+    pub to: Address,
+    pub data: Bytes,
+    /// Native currency sent along, in wei
+    pub value: U256,
+}
+
+impl ContractCall {
+    pub fn new(to: Address, data: Bytes) -> Self {
+        Self { to, data, value: U256::zero() }
+    }
+
+    pub fn with_value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+}
+
+pub(crate) fn signer_address(private_key: &str) -> Result<Address> {
+    x_core::config::normalize_private_key(private_key)
+        .parse::<LocalWallet>()
+        .map(|wallet| wallet.address())
+        .map_err(|e| anyhow::anyhow!("Invalid private key: {}", e))
+}
+
+pub(crate) fn encode_call(signature: &str, args: &[Token]) -> Bytes {
+    let mut data = id(signature).to_vec();
+    data.extend(abi::encode(args));
+    data.into()
+}
+
+pub(crate) async fn call(client: &HttpClient, to: Address, data: Bytes) -> Result<Bytes> {
+    let tx: TypedTransaction = TransactionRequest::new().to(to).data(data).into();
+    client
+        .get_provider()
+        .call(&tx, None)
+        .await
+        .map_err(|e| anyhow::anyhow!("Call to {:#x} failed: {}", to, e))
+}
+
+pub(crate) async fn call_uint(client: &HttpClient, to: Address, signature: &str, args: &[Token]) -> Result<U256> {
+    let output = call(client, to, encode_call(signature, args)).await?;
+    match abi::decode(&[ParamType::Uint(256)], &output).ok().and_then(|t| t.into_iter().next()) {
+        Some(Token::Uint(value)) => Ok(value),
+        _ => Err(anyhow::anyhow!("{:#x} returned an invalid {} result", to, signature)),
+    }
+}
+
+/// Sends every call back to back with consecutive nonces, then waits for all receipts
+///
+/// Receipts are returned in call order, including reverted ones; a call that cannot be
+/// estimated or sent stops the batch before anything after it is sent.
+pub async fn send_calls(
+    private_key: &str,
+    network: &Network,
+    calls: &[ContractCall],
+    gas_strategy: GasStrategy,
+) -> Result<Vec<TransactionReceipt>> {
+    let private_key = x_core::config::normalize_private_key(private_key);
+    let wallet = private_key
+        .parse::<LocalWallet>()
+        .map_err(|e| anyhow::anyhow!("Invalid private key: {}", e))?;
+
+    let rpc_url = network.rpc.first()
+        .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
+    let http_client = HttpClient::for_network(rpc_url, network).await?;
+    let client = SignerMiddleware::new(http_client.get_provider().clone(), wallet.with_chain_id(network.chain_id));
+    let from_addr = client.address();
+
+    let is_eip1559 = check_eip1559_support(&client).await.unwrap_or(true);
+    let mut nonce = http_client.get_transaction_count(from_addr, BlockNumber::Pending).await?;
+
+    let mut pending = Vec::with_capacity(calls.len());
+    for call in calls {
+        let sent = if is_eip1559 {
+            let gas_estimate = GasCalculator::estimate_gas(
+                &client, from_addr, call.to, call.value, Some(call.data.to_vec()), gas_strategy, Some(from_addr),
+            )
+            .await?;
+
+            let mut tx = Eip1559TransactionRequest::new()
+                .to(call.to)
+                .value(call.value)
+                .data(call.data.clone())
+                .nonce(nonce)
+                .gas(gas_estimate.gas_limit)
+                .chain_id(network.chain_id);
+            if let (Some(max_priority_fee), Some(max_fee_per_gas)) =
+                (gas_estimate.max_priority_fee, gas_estimate.max_fee_per_gas)
+            {
+                tx = tx.max_priority_fee_per_gas(max_priority_fee).max_fee_per_gas(max_fee_per_gas);
+            }
+
+            client.send_transaction(tx, None).await
+        } else {
+            let gas_estimate = GasCalculator::estimate_gas_legacy(
+                &client, from_addr, call.to, call.value, Some(call.data.to_vec()), gas_strategy, Some(from_addr),
+            )
+            .await?;
+
+            let tx = TransactionRequest::new()
+                .to(call.to)
+                .value(call.value)
+                .data(call.data.clone())
+                .nonce(nonce)
+                .gas(gas_estimate.gas_limit)
+                .gas_price(gas_estimate.gas_price)
+                .chain_id(network.chain_id);
+
+            client.send_transaction(tx, None).await
+        }
+        .map_err(|e| anyhow::anyhow!("Failed to send transaction: {}", e))?;
+
+        pending.push(sent);
+        nonce += U256::one();
+    }
+
+    let mut receipts = Vec::with_capacity(pending.len());
+    for pending_tx in pending {
+        let receipt = pending_tx.confirmations(x_core::project::confirmations()).await
+            .map_err(|e| anyhow::anyhow!("Failed to confirm transaction: {}", e))?
+            .ok_or_else(|| anyhow::anyhow!("Transaction confirmation timeout"))?;
+        receipts.push(receipt);
+    }
+
+    Ok(receipts)
+}
+
+/// Sends a single call and fails when it reverts
+pub async fn send_call(
+    private_key: &str,
+    network: &Network,
+    call: ContractCall,
+    gas_strategy: GasStrategy,
+) -> Result<TransactionReceipt> {
+    let receipt = send_calls(private_key, network, &[call], gas_strategy)
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("Transaction was not sent"))?;
+
+    if receipt.status != Some(1u64.into()) {
+        anyhow::bail!("Transaction {:#x} reverted", receipt.transaction_hash);
+    }
+    Ok(receipt)
+}
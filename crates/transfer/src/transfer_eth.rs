@@ -37,7 +37,7 @@ pub async fn transfer_eth_with_strategy_async(
     let rpc_url = network.rpc.first()
         .ok_or_else(|| anyhow::anyhow!("No RPC URL available for network"))?;
 
-    let http_client = HttpClient::new(rpc_url).await?;
+    let http_client = HttpClient::for_network(rpc_url, network).await?;
     let provider = http_client.get_provider();
 
     let ws_client = if !network.ws_rpc.is_empty() {
@@ -114,7 +114,7 @@ pub async fn transfer_eth_with_strategy_async(
             }
         }
 
-        let receipt = pending_tx.confirmations(1).await
+        let receipt = pending_tx.confirmations(x_core::project::confirmations()).await
             .map_err(|e| anyhow::anyhow!("Failed to confirm transaction: {}", e))?
             .ok_or_else(|| anyhow::anyhow!("Transaction confirmation timeout"))?;
 
@@ -157,7 +157,7 @@ pub async fn transfer_eth_with_strategy_async(
             }
         }
 
-        let receipt = pending_tx.confirmations(1).await
+        let receipt = pending_tx.confirmations(x_core::project::confirmations()).await
             .map_err(|e| anyhow::anyhow!("Failed to confirm transaction: {}", e))?
             .ok_or_else(|| anyhow::anyhow!("Transaction confirmation timeout"))?;
 
@@ -167,7 +167,7 @@ pub async fn transfer_eth_with_strategy_async(
     }
 }
 
-async fn check_eip1559_support<M: Middleware>(client: &M) -> Result<bool> {
+pub(crate) async fn check_eip1559_support<M: Middleware>(client: &M) -> Result<bool> {
     match client.get_block(BlockNumber::Latest).await {
         Ok(Some(block)) => Ok(block.base_fee_per_gas.is_some()),
         _ => Ok(true),